@@ -1,27 +1,44 @@
 //! client API module
 // std
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 // thread
 use std::thread;
 
 // crossbeam
-use crossbeam::{unbounded, Receiver, Sender};
+use crossbeam::{bounded, unbounded, Receiver, RecvTimeoutError, Sender};
 
 // virtual router
 use crate::VirtualRouter;
 
+// finite state machine
+use crate::fsm;
+
+// statistics
+use crate::stats;
+
 // router
 mod router;
 
 // sessions
 mod sessions;
 use sessions::auth::auth_api_client;
+use sessions::registry::SessionRegistry;
 use sessions::token::SessionToken;
 
 // config
 use crate::config;
 
+// debugging
+use crate::debug::{print_debug, Verbose};
+
+// constants
+use crate::constants::{
+    API_ACCESS_LEVEL_ADMIN, API_ACCESS_LEVEL_READ, API_ACCESS_LEVEL_WRITE,
+    API_SESSION_EXPIRY_INTERVAL, DEBUG_LEVEL_INFO, DEBUG_SRC_API,
+};
+
 /// Upstream API structure
 pub struct UpstreamAPI {
     sender: Sender<FSMQueryResult>,     // channel for queries to fsm
@@ -39,24 +56,24 @@ impl<'a> UpstreamAPI {
     pub fn spawn_thread(
         &self,
         down_api: &DownstreamAPI,
-        cfg: config::CConfig,
-        vrs: &Vec<Arc<RwLock<VirtualRouter>>>,
+        cfg: &Arc<RwLock<config::CConfig>>,
+        vrs: &Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>,
+        ctl: Sender<VRouterControl>,
+        debug: &Verbose,
     ) {
         // upstream transmit and receives channels
         let (utx, urx) = self.channels();
 
-        // clone receives and transmit channels for queries/responses
+        // clone the queries receive channel (answered on their reply channels)
         let qrx = down_api.q_receiver.clone();
-        let rtx = down_api.r_sender.clone();
 
-        // duplicate virtual routers vector
-        let mut vrouters: Vec<Arc<RwLock<VirtualRouter>>> = Vec::new();
-        for vr in vrs {
-            vrouters.push(vr.clone());
-        }
+        // share the configuration and virtual routers vector (replaced upon reloads)
+        let cfg = Arc::clone(cfg);
+        let vrouters = Arc::clone(vrs);
+        let debug = *debug;
 
         // spawn Client API thread
-        thread::spawn(|| capi_thread_loop(utx, urx, qrx, rtx, cfg, vrouters));
+        thread::spawn(move || capi_thread_loop(utx, urx, qrx, cfg, vrouters, ctl, debug));
     }
     // channels() method
     // channels to virtual routers finite-state-machines
@@ -65,6 +82,24 @@ impl<'a> UpstreamAPI {
     }
 }
 
+/// VRouterControl enumerator
+/// virtual routers and static routes changes, applied by the main thread owning the workers
+/// (carrying the updated configuration and the result's channel)
+pub enum VRouterControl {
+    Add(
+        config::CConfig,
+        config::VRConfig,
+        Sender<Result<(), String>>,
+    ),
+    Remove(config::CConfig, u8, String, Sender<Result<(), String>>),
+    Route(
+        config::CConfig,
+        Option<config::Static>,
+        Option<config::Static>,
+        Sender<Result<(), String>>,
+    ),
+}
+
 /// FSMQueryResult structure
 #[derive(Debug)]
 pub struct FSMQueryResult {
@@ -101,66 +136,183 @@ pub enum FSMResponse {
 /// Downstream API structure
 #[derive(Clone, StateData)]
 pub struct DownstreamAPI {
-    q_sender: Arc<Mutex<Sender<ClientAPIQuery>>>,
-    q_receiver: Arc<Mutex<Receiver<ClientAPIQuery>>>,
-    r_sender: Arc<Mutex<Sender<ClientAPIResponse>>>,
-    r_receiver: Arc<Mutex<Receiver<ClientAPIResponse>>>,
+    q_sender: Arc<Mutex<Sender<ClientAPIRequest>>>,
+    q_receiver: Arc<Mutex<Receiver<ClientAPIRequest>>>,
 }
 
+/// Client API request type
+/// a query and the channel its answer is sent on (one per query, so that
+/// concurrent clients cannot read each other's answers)
+pub type ClientAPIRequest = (ClientAPIQuery, Sender<ClientAPIResponse>);
+
 /// Downstream API implementation
 impl DownstreamAPI {
     // new() method
     pub fn new() -> Self {
         let (qtx, qrx) = unbounded();
-        let (rtx, rrx) = unbounded();
         Self {
             q_sender: Arc::new(Mutex::new(qtx)),
             q_receiver: Arc::new(Mutex::new(qrx)),
-            r_sender: Arc::new(Mutex::new(rtx)),
-            r_receiver: Arc::new(Mutex::new(rrx)),
         }
     }
     // query() method
-    pub fn query(&self, q: ClientAPIQuery) {
-        let qtx = self.q_sender.lock().unwrap();
-        // panic if send fails
-        qtx.send(q).unwrap();
-    }
-    // answer() method
-    pub fn read(&self) -> ClientAPIResponse {
-        let rrx = self.r_receiver.lock().unwrap();
-        let answer = rrx.recv().unwrap();
-        answer
+    /// send a query and wait for its answer (blocking)
+    pub fn query(&self, q: ClientAPIQuery) -> ClientAPIResponse {
+        let (rtx, rrx) = bounded(1);
+        {
+            let qtx = self.q_sender.lock().unwrap();
+            // panic if send fails
+            qtx.send((q, rtx)).unwrap();
+        }
+        rrx.recv().unwrap()
     }
 }
 
 /// ClientAPIQuery enumerator
 pub enum ClientAPIQuery {
     AuthRequest(String, String),
+    AuthLogout(SessionToken),
+    AuthRevoke(SessionToken, String),
     CfgGlobalAll(SessionToken),
     CfgVrrpAll(SessionToken),
     CfgProtoAll(SessionToken),
+    CfgSecretsAll(SessionToken),
     RunGlobalAll(SessionToken),
     RunVRRPAll(SessionToken),
     RunVRRPGrp(SessionToken, u8),
     RunVRRPGrpIntf(SessionToken, u8, String),
     RunProtoAll(SessionToken),
     RunProtoStatic(SessionToken),
+    CfgVrrpAdd(SessionToken, config::VRConfig),
+    CfgVrrpModify(SessionToken, u8, String, config::VRConfigUpdate),
+    CfgVrrpDelete(SessionToken, u8, String),
+    RunProtoStaticAdd(SessionToken, config::Static),
+    RunProtoStaticModify(SessionToken, String, String, config::Static),
+    RunProtoStaticDelete(SessionToken, String, String),
+    RunVRRPAdmin(SessionToken, u8, String, AdminAction),
+    RunVRRPStats(SessionToken, u8, String),
+    RunVRRPStatsReset(SessionToken, u8, String),
+    RunEvents(SessionToken),
+    RunMetrics(SessionToken),
+}
+
+/// ClientAPIQuery implementation
+impl ClientAPIQuery {
+    // session() getter
+    /// session of the query (none for the authentication queries)
+    fn session(&self) -> Option<&SessionToken> {
+        match self {
+            ClientAPIQuery::AuthRequest(..) => None,
+            ClientAPIQuery::AuthLogout(sess)
+            | ClientAPIQuery::AuthRevoke(sess, ..)
+            | ClientAPIQuery::CfgGlobalAll(sess)
+            | ClientAPIQuery::CfgVrrpAll(sess)
+            | ClientAPIQuery::CfgProtoAll(sess)
+            | ClientAPIQuery::CfgSecretsAll(sess)
+            | ClientAPIQuery::RunGlobalAll(sess)
+            | ClientAPIQuery::RunVRRPAll(sess)
+            | ClientAPIQuery::RunVRRPGrp(sess, ..)
+            | ClientAPIQuery::RunVRRPGrpIntf(sess, ..)
+            | ClientAPIQuery::RunProtoAll(sess)
+            | ClientAPIQuery::RunProtoStatic(sess)
+            | ClientAPIQuery::CfgVrrpAdd(sess, ..)
+            | ClientAPIQuery::CfgVrrpModify(sess, ..)
+            | ClientAPIQuery::CfgVrrpDelete(sess, ..)
+            | ClientAPIQuery::RunProtoStaticAdd(sess, ..)
+            | ClientAPIQuery::RunProtoStaticModify(sess, ..)
+            | ClientAPIQuery::RunProtoStaticDelete(sess, ..)
+            | ClientAPIQuery::RunVRRPAdmin(sess, ..)
+            | ClientAPIQuery::RunVRRPStats(sess, ..)
+            | ClientAPIQuery::RunVRRPStatsReset(sess, ..)
+            | ClientAPIQuery::RunEvents(sess)
+            | ClientAPIQuery::RunMetrics(sess) => Some(sess),
+        }
+    }
+    // access_level() getter
+    /// minimum access level required by the query
+    fn access_level(&self) -> u8 {
+        match self {
+            // the unredacted configuration, with the API and VRRP authentication secrets
+            ClientAPIQuery::CfgSecretsAll(_) => API_ACCESS_LEVEL_ADMIN,
+            // the sessions of the other users
+            ClientAPIQuery::AuthRevoke(..) => API_ACCESS_LEVEL_ADMIN,
+            // runtime changes
+            ClientAPIQuery::CfgVrrpAdd(..)
+            | ClientAPIQuery::CfgVrrpModify(..)
+            | ClientAPIQuery::CfgVrrpDelete(..)
+            | ClientAPIQuery::RunProtoStaticAdd(..)
+            | ClientAPIQuery::RunProtoStaticModify(..)
+            | ClientAPIQuery::RunProtoStaticDelete(..)
+            | ClientAPIQuery::RunVRRPAdmin(..)
+            | ClientAPIQuery::RunVRRPStatsReset(..) => API_ACCESS_LEVEL_WRITE,
+            // running state and non-sensitive configuration
+            ClientAPIQuery::AuthRequest(..)
+            | ClientAPIQuery::AuthLogout(_)
+            | ClientAPIQuery::CfgGlobalAll(_)
+            | ClientAPIQuery::CfgVrrpAll(_)
+            | ClientAPIQuery::CfgProtoAll(_)
+            | ClientAPIQuery::RunGlobalAll(_)
+            | ClientAPIQuery::RunVRRPAll(_)
+            | ClientAPIQuery::RunVRRPGrp(..)
+            | ClientAPIQuery::RunVRRPGrpIntf(..)
+            | ClientAPIQuery::RunProtoAll(_)
+            | ClientAPIQuery::RunProtoStatic(_)
+            | ClientAPIQuery::RunVRRPStats(..)
+            | ClientAPIQuery::RunEvents(_)
+            | ClientAPIQuery::RunMetrics(_) => API_ACCESS_LEVEL_READ,
+        }
+    }
+}
+
+/// AdminAction enumerator
+/// administrative actions on a running virtual router
+#[derive(Debug)]
+pub enum AdminAction {
+    Resign,
+    Maintenance(bool),
+    Takeover,
 }
 
 /// ClientAPIResponse enumerator
 pub enum ClientAPIResponse {
     Unauthorized,
+    Forbidden,
+    NotFound,
+    NotImplemented,
+    BadRequest(ResponseError),
+    Done,
     AuthResponse(Option<SessionToken>),
     CfgGlobalAll(config::CConfig),
     CfgVrrpAll(Vec<config::VRConfig>),
     CfgProtoAll(config::Protocols),
+    CfgSecretsAll(config::CConfig),
     RunGlobalAll(ResponseGlobalAttr),
     RunVRRPAll(Vec<ResponseVRRPAttr>),
     RunVRRPGrp(Option<Vec<ResponseVRRPAttr>>),
     RunVRRPGrpIntf(Option<ResponseVRRPAttrExt>),
+    RunVRRPStats(Option<ResponseVRRPStatsAttr>),
     RunProtoAll(Option<ResponseProtoAttr>),
     RunProtoStatic(Option<Vec<ResponseProtoStaticAttr>>),
+    RunMetrics(String),
+    RunEvents,
+}
+
+/// ResponseError structure (Serialize-able)
+#[derive(Serialize)]
+pub struct ResponseError {
+    error: String,
+    details: Vec<String>,
+}
+
+// ResponseError implementation
+impl ResponseError {
+    // new() method
+    pub fn new(error: &str, details: Vec<String>) -> ResponseError {
+        ResponseError {
+            error: error.to_string(),
+            details,
+        }
+    }
 }
 
 /// ReponseGlobalAttr structure (Serialize-able)
@@ -191,17 +343,52 @@ pub struct ResponseVRRPAttr {
 #[derive(Serialize)]
 pub struct ResponseVRRPAttrExt {
     virtual_ip: String,
+    virtual_ips: Vec<String>,
     group: u8,
     interface: String,
     vif: String,
     priority: u8,
     preempt: bool,
+    preempt_delay: u32,
+    startup_delay: u32,
+    preempt_delay_pending: Option<f32>,
+    startup_delay_pending: Option<f32>,
     state: String,
     auth_type: u8,
     interface_hwaddress: String,
-    advert_interval: u8,
+    advert_interval: f32,
     masterdown_interval: f32,
     skew_time: f32,
+    track_interfaces: Vec<ResponseTrackAttr>,
+    track_scripts: Vec<ResponseTrackAttr>,
+    maintenance: bool,
+    statistics: ResponseVRRPStatsAttr,
+}
+
+/// ResponseVRRPStatsAttr structure (Serialize-able)
+/// statistics of a virtual router (RFC6527 vrrpv3StatisticsTable)
+#[derive(Serialize)]
+pub struct ResponseVRRPStatsAttr {
+    master_transitions: u64,
+    new_master_reason: String,
+    rcvd_advertisements: u64,
+    adv_interval_errors: u64,
+    ip_ttl_errors: u64,
+    rcvd_pri_zero_packets: u64,
+    sent_pri_zero_packets: u64,
+    invalid_type_packets: u64,
+    address_list_errors: u64,
+    packet_length_errors: u64,
+    refresh_rate: u32,
+    discontinuity_time: u64,
+}
+
+/// ResponseTrackAttr structure (Serialize-able)
+#[derive(Serialize)]
+pub struct ResponseTrackAttr {
+    name: String,
+    weight: u8,
+    state: String,
 }
 
 /// RunProtoAttr structure (Serialize-able)
@@ -224,11 +411,18 @@ pub struct ResponseProtoStaticAttr {
 pub fn capi_thread_loop(
     _utx: Sender<FSMQueryResult>,
     _urx: Receiver<FSMQueryResult>,
-    qrx: Arc<Mutex<Receiver<ClientAPIQuery>>>,
-    rtx: Arc<Mutex<Sender<ClientAPIResponse>>>,
-    cfg: config::CConfig,
-    vrs: Vec<Arc<RwLock<VirtualRouter>>>,
+    qrx: Arc<Mutex<Receiver<ClientAPIRequest>>>,
+    shared_cfg: Arc<RwLock<config::CConfig>>,
+    shared_vrs: Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>,
+    ctl: Sender<VRouterControl>,
+    debug: Verbose,
 ) {
+    // sessions issued by this thread
+    let mut sessions = SessionRegistry::new();
+    // the expired sessions are closed periodically, even without any query
+    let expiry_interval = Duration::from_secs(API_SESSION_EXPIRY_INTERVAL);
+    let mut last_expiry = Instant::now();
+
     loop {
         // declare empty response
         let resp;
@@ -236,98 +430,164 @@ pub fn capi_thread_loop(
         // acquire mutex lock
         let qrx = qrx.lock().unwrap();
 
-        // listen for downstream queries (blocking)
-        let q = qrx.recv().unwrap();
-        match q {
-            ClientAPIQuery::AuthRequest(user, passwd) => {
-                let r = auth_api_client(&cfg, user, passwd);
-                resp = ClientAPIResponse::AuthResponse(r);
-            }
-            ClientAPIQuery::CfgGlobalAll(sess) => match sess.validate(&cfg) {
-                Some(_) => {
-                    let r = capi_req_cfg_global_all(&cfg);
-                    resp = ClientAPIResponse::CfgGlobalAll(r);
+        // listen for downstream queries (blocking, up to the next expiry check)
+        let received = qrx.recv_timeout(expiry_interval);
+        if last_expiry.elapsed() >= expiry_interval {
+            sessions.expire(&shared_cfg.read().unwrap());
+            last_expiry = Instant::now();
+        }
+        let (q, rtx) = match received {
+            Ok(r) => r,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+
+        // snapshot the running configuration and virtual routers
+        let cfg = shared_cfg.read().unwrap().clone();
+        let vrs = shared_vrs.read().unwrap().clone();
+
+        // refuse the queries of the sessions lacking the required access level
+        let denied = q
+            .session()
+            .and_then(|sess| authorize(&cfg, &mut sessions, sess, q.access_level()));
+        match denied {
+            Some(r) => resp = r,
+            None => match q {
+                ClientAPIQuery::AuthRequest(user, passwd) => {
+                    let r = auth_api_client(&cfg, user, passwd);
+                    if let Some(sess) = &r {
+                        sessions.register(&cfg, sess);
+                    }
+                    resp = ClientAPIResponse::AuthResponse(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
+                ClientAPIQuery::AuthLogout(sess) => {
+                    sessions.remove(&sess);
+                    resp = ClientAPIResponse::Done;
                 }
-            },
-            ClientAPIQuery::CfgVrrpAll(sess) => match sess.validate(&cfg) {
-                Some(_) => {
-                    let r = capi_req_cfg_vrrp_all(&cfg);
-                    resp = ClientAPIResponse::CfgVrrpAll(r);
+                ClientAPIQuery::AuthRevoke(sess, user) => {
+                    let n = sessions.revoke_user(&user);
+                    print_debug(
+                        &debug,
+                        DEBUG_LEVEL_INFO,
+                        DEBUG_SRC_API,
+                        format!(
+                            "Client API: user {} revoked {} session(s) of user {}",
+                            sess.user(),
+                            n,
+                            user
+                        ),
+                    );
+                    resp = ClientAPIResponse::Done;
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
+                ClientAPIQuery::CfgGlobalAll(_) => {
+                    let r = capi_req_cfg_global_all(&cfg);
+                    resp = ClientAPIResponse::CfgGlobalAll(r);
                 }
-            },
-            ClientAPIQuery::CfgProtoAll(sess) => match sess.validate(&cfg) {
-                Some(_) => {
-                    let r = capi_req_cfg_proto_all(&cfg);
-                    resp = ClientAPIResponse::CfgProtoAll(r);
+                ClientAPIQuery::CfgVrrpAll(_) => {
+                    resp = capi_req_cfg_vrrp_all(&cfg);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
+                ClientAPIQuery::CfgProtoAll(_) => {
+                    resp = capi_req_cfg_proto_all(&cfg);
                 }
-            },
-            ClientAPIQuery::RunGlobalAll(sess) => match sess.validate(&cfg) {
-                Some(_) => {
+                ClientAPIQuery::CfgSecretsAll(sess) => {
+                    let r = capi_req_cfg_secrets_all(&cfg, &sess, &debug);
+                    resp = ClientAPIResponse::CfgSecretsAll(r);
+                }
+                ClientAPIQuery::RunGlobalAll(_) => {
                     let r = capi_req_run_global_all(&cfg);
                     resp = ClientAPIResponse::RunGlobalAll(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
-                }
-            },
-            ClientAPIQuery::RunVRRPAll(sess) => match sess.validate(&cfg) {
-                Some(_) => {
+                ClientAPIQuery::RunVRRPAll(_) => {
                     let r = capi_req_run_vrrp_all(&vrs);
                     resp = ClientAPIResponse::RunVRRPAll(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
-                }
-            },
-            ClientAPIQuery::RunVRRPGrp(sess, gid) => match sess.validate(&cfg) {
-                Some(_) => {
+                ClientAPIQuery::RunVRRPGrp(_, gid) => {
                     let r = capi_req_run_vrrp_grp(&vrs, gid);
                     resp = ClientAPIResponse::RunVRRPGrp(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
-                }
-            },
-            ClientAPIQuery::RunVRRPGrpIntf(sess, gid, intf) => match sess.validate(&cfg) {
-                Some(_) => {
+                ClientAPIQuery::RunVRRPGrpIntf(_, gid, intf) => {
                     let r = capi_req_run_vrrp_grp_intf(&vrs, gid, intf);
                     resp = ClientAPIResponse::RunVRRPGrpIntf(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
-                }
-            },
-            ClientAPIQuery::RunProtoAll(sess) => match sess.validate(&cfg) {
-                Some(_) => {
+                ClientAPIQuery::RunProtoAll(_) => {
                     let r = capi_req_run_proto_all(&vrs);
                     resp = ClientAPIResponse::RunProtoAll(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
-                }
-            },
-            ClientAPIQuery::RunProtoStatic(sess) => match sess.validate(&cfg) {
-                Some(_) => {
+                ClientAPIQuery::RunProtoStatic(_) => {
                     let r = capi_req_run_proto_static(&vrs);
                     resp = ClientAPIResponse::RunProtoStatic(r);
                 }
-                None => {
-                    resp = ClientAPIResponse::Unauthorized;
+                ClientAPIQuery::CfgVrrpAdd(sess, vr) => {
+                    resp = capi_req_cfg_vrrp_add(&cfg, &ctl, &sess, vr, &debug);
+                }
+                ClientAPIQuery::CfgVrrpModify(sess, gid, intf, update) => {
+                    resp = capi_req_cfg_vrrp_modify(
+                        &cfg,
+                        &shared_cfg,
+                        &vrs,
+                        &sess,
+                        gid,
+                        intf,
+                        update,
+                        &debug,
+                    );
+                }
+                ClientAPIQuery::CfgVrrpDelete(sess, gid, intf) => {
+                    resp = capi_req_cfg_vrrp_delete(&cfg, &ctl, &vrs, &sess, gid, intf, &debug);
+                }
+                ClientAPIQuery::RunProtoStaticAdd(sess, st) => {
+                    resp =
+                        capi_req_run_proto_static_change(&cfg, &ctl, &sess, None, Some(st), &debug);
+                }
+                ClientAPIQuery::RunProtoStaticModify(sess, route, mask, st) => {
+                    resp = capi_req_run_proto_static_change(
+                        &cfg,
+                        &ctl,
+                        &sess,
+                        Some((route, mask)),
+                        Some(st),
+                        &debug,
+                    );
+                }
+                ClientAPIQuery::RunProtoStaticDelete(sess, route, mask) => {
+                    resp = capi_req_run_proto_static_change(
+                        &cfg,
+                        &ctl,
+                        &sess,
+                        Some((route, mask)),
+                        None,
+                        &debug,
+                    );
+                }
+                ClientAPIQuery::RunMetrics(_) => {
+                    resp = ClientAPIResponse::RunMetrics(capi_req_run_metrics(&vrs));
+                }
+                ClientAPIQuery::RunEvents(_) => {
+                    resp = ClientAPIResponse::RunEvents;
+                }
+                ClientAPIQuery::RunVRRPStats(_, gid, intf) => {
+                    let r =
+                        find_vrouter(&vrs, gid, &intf).map(|vr| stats_attr(&vr.read().unwrap()));
+                    resp = ClientAPIResponse::RunVRRPStats(r);
+                }
+                ClientAPIQuery::RunVRRPStatsReset(_, gid, intf) => {
+                    match find_vrouter(&vrs, gid, &intf) {
+                        Some(vr) => {
+                            vr.read().unwrap().stats.reset();
+                            resp = ClientAPIResponse::Done;
+                        }
+                        None => {
+                            resp = ClientAPIResponse::NotFound;
+                        }
+                    }
+                }
+                ClientAPIQuery::RunVRRPAdmin(sess, gid, intf, action) => {
+                    resp = capi_req_run_vrrp_admin(&vrs, &sess, gid, intf, action, &debug);
                 }
             },
         }
 
-        // send queries answer back
-        let rtx = rtx.lock().unwrap();
+        // send the query's answer back
         let _r = rtx.send(resp);
     }
 }
@@ -345,22 +605,356 @@ pub fn capi_start_app(
     thread::spawn(move || router::start(down_api, host, tls, tls_key, tls_cert));
 }
 
+// capi_start_local() function
+pub fn capi_start_local(
+    down_api: DownstreamAPI,
+    path: String,
+    mode: u32,
+    group: Option<String>,
+    debug: &Verbose,
+) {
+    // spawn the local socket server in a new thread
+    let debug = *debug;
+    thread::spawn(move || router::start_local(down_api, path, mode, group, debug));
+}
+
+// capi_start_metrics() function
+pub fn capi_start_metrics(vrs: Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>, host: String) {
+    // spawn the Prometheus metrics server in a new thread
+    thread::spawn(move || router::start_metrics(vrs, host));
+}
+
+// authorize() function
+/// check the session and its access level, return an Unauthorized or
+/// Forbidden response if the query must be refused
+fn authorize(
+    cfg: &config::CConfig,
+    sessions: &mut SessionRegistry,
+    sess: &SessionToken,
+    level: u8,
+) -> Option<ClientAPIResponse> {
+    match sessions.access_level(cfg, sess) {
+        None => Some(ClientAPIResponse::Unauthorized),
+        Some(l) if l < level => Some(ClientAPIResponse::Forbidden),
+        Some(_) => None,
+    }
+}
+
+// config_errors() function
+/// validate a modified configuration, return a BadRequest response if invalid
+fn config_errors(cfg: &config::CConfig) -> Option<ClientAPIResponse> {
+    let errors: Vec<String> = cfg
+        .validate()
+        .iter()
+        .filter(|i| i.is_error())
+        .map(|i| i.to_string())
+        .collect();
+    if errors.is_empty() {
+        None
+    } else {
+        Some(ClientAPIResponse::BadRequest(ResponseError::new(
+            "Invalid configuration",
+            errors,
+        )))
+    }
+}
+
+// find_vrouter() function
+/// find the running virtual router of a group on a (configured) interface
+fn find_vrouter<'a>(
+    vrs: &'a Vec<Arc<RwLock<VirtualRouter>>>,
+    gid: u8,
+    intf: &str,
+) -> Option<&'a Arc<RwLock<VirtualRouter>>> {
+    vrs.iter().find(|&vr| {
+        let vr = vr.read().unwrap();
+        vr.parameters.vrid() == gid && vr.parameters.cfg_interface() == intf
+    })
+}
+
+// send_control() function
+/// have the main thread apply a virtual router creation or removal
+fn send_control<F>(ctl: &Sender<VRouterControl>, build: F) -> ClientAPIResponse
+where
+    F: FnOnce(Sender<Result<(), String>>) -> VRouterControl,
+{
+    let (tx, rx) = unbounded();
+    // the main thread does not handle the changes on every platform
+    if ctl.send(build(tx)).is_err() {
+        return ClientAPIResponse::NotImplemented;
+    }
+    match rx.recv() {
+        Ok(Ok(())) => ClientAPIResponse::Done,
+        Ok(Err(e)) => ClientAPIResponse::BadRequest(ResponseError::new(&e, Vec::new())),
+        Err(_) => ClientAPIResponse::NotImplemented,
+    }
+}
+
+// capi_req_cfg_vrrp_add() function
+fn capi_req_cfg_vrrp_add(
+    cfg: &config::CConfig,
+    ctl: &Sender<VRouterControl>,
+    sess: &SessionToken,
+    vr: config::VRConfig,
+    debug: &Verbose,
+) -> ClientAPIResponse {
+    // validate the configuration including the new virtual router
+    let mut ncfg = cfg.clone();
+    ncfg.vrouter.get_or_insert_with(Vec::new).push(vr.clone());
+    if let Some(r) = config_errors(&ncfg) {
+        return r;
+    }
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_API,
+        format!(
+            "Client API: user {} is adding virtual router for group {} on interface {}",
+            sess.user(),
+            vr.group(),
+            vr.interface()
+        ),
+    );
+    send_control(ctl, |tx| VRouterControl::Add(ncfg, vr, tx))
+}
+
+// capi_req_cfg_vrrp_modify() function
+fn capi_req_cfg_vrrp_modify(
+    cfg: &config::CConfig,
+    shared_cfg: &RwLock<config::CConfig>,
+    vrs: &Vec<Arc<RwLock<VirtualRouter>>>,
+    sess: &SessionToken,
+    gid: u8,
+    intf: String,
+    update: config::VRConfigUpdate,
+    debug: &Verbose,
+) -> ClientAPIResponse {
+    let vr = match find_vrouter(vrs, gid, &intf) {
+        Some(vr) => vr,
+        None => return ClientAPIResponse::NotFound,
+    };
+    // validate the configuration including the updated virtual router
+    let mut ncfg = cfg.clone();
+    let c = match ncfg
+        .vrouter
+        .iter_mut()
+        .flatten()
+        .find(|c| c.group() == gid && *c.interface() == intf)
+    {
+        Some(c) => c,
+        None => return ClientAPIResponse::NotFound,
+    };
+    *c = c.updated(&update);
+    let c = c.clone();
+    if let Some(r) = config_errors(&ncfg) {
+        return r;
+    }
+    // apply the update to the running virtual router
+    vr.write().unwrap().apply_config(&c);
+    *shared_cfg.write().unwrap() = ncfg;
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_API,
+        format!(
+            "Client API: user {} modified virtual router for group {} on interface {}",
+            sess.user(),
+            gid,
+            intf
+        ),
+    );
+    ClientAPIResponse::Done
+}
+
+// capi_req_cfg_vrrp_delete() function
+fn capi_req_cfg_vrrp_delete(
+    cfg: &config::CConfig,
+    ctl: &Sender<VRouterControl>,
+    vrs: &Vec<Arc<RwLock<VirtualRouter>>>,
+    sess: &SessionToken,
+    gid: u8,
+    intf: String,
+    debug: &Verbose,
+) -> ClientAPIResponse {
+    if find_vrouter(vrs, gid, &intf).is_none() {
+        return ClientAPIResponse::NotFound;
+    }
+    // validate the configuration without the virtual router
+    let mut ncfg = cfg.clone();
+    if let Some(v) = ncfg.vrouter.as_mut() {
+        v.retain(|c| !(c.group() == gid && *c.interface() == intf));
+    }
+    if let Some(r) = config_errors(&ncfg) {
+        return r;
+    }
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_API,
+        format!(
+            "Client API: user {} is removing virtual router for group {} on interface {}",
+            sess.user(),
+            gid,
+            intf
+        ),
+    );
+    send_control(ctl, |tx| VRouterControl::Remove(ncfg, gid, intf, tx))
+}
+
+// capi_req_run_vrrp_admin() function
+/// send an administrative action to the worker of a virtual router
+fn capi_req_run_vrrp_admin(
+    vrs: &Vec<Arc<RwLock<VirtualRouter>>>,
+    sess: &SessionToken,
+    gid: u8,
+    intf: String,
+    action: AdminAction,
+    debug: &Verbose,
+) -> ClientAPIResponse {
+    let vr = match find_vrouter(vrs, gid, &intf) {
+        Some(vr) => vr.read().unwrap(),
+        None => return ClientAPIResponse::NotFound,
+    };
+    let user = sess.user();
+    let (event, desc) = match action {
+        AdminAction::Resign => (fsm::Event::Resign(user.clone()), "resignation"),
+        AdminAction::Maintenance(true) => (
+            fsm::Event::Maintenance(user.clone(), true),
+            "maintenance mode",
+        ),
+        AdminAction::Maintenance(false) => (
+            fsm::Event::Maintenance(user.clone(), false),
+            "maintenance mode release",
+        ),
+        AdminAction::Takeover => {
+            if vr.flags.maintenance() {
+                return ClientAPIResponse::BadRequest(ResponseError::new(
+                    "The virtual router is in maintenance mode",
+                    Vec::new(),
+                ));
+            }
+            (fsm::Event::Takeover(user.clone()), "takeover")
+        }
+    };
+    let tx = match vr.parameters.notification() {
+        Some(tx) => tx,
+        None => {
+            return ClientAPIResponse::BadRequest(ResponseError::new(
+                "The virtual router is not running",
+                Vec::new(),
+            ))
+        }
+    };
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_API,
+        format!(
+            "Client API: user {} requested the {} of virtual router for group {} on interface {}",
+            user, desc, gid, intf
+        ),
+    );
+    let _r = tx.lock().unwrap().send(event);
+    ClientAPIResponse::Done
+}
+
+// capi_req_run_proto_static_change() function
+/// add (no target route), modify or remove (no new route) a static route
+fn capi_req_run_proto_static_change(
+    cfg: &config::CConfig,
+    ctl: &Sender<VRouterControl>,
+    sess: &SessionToken,
+    target: Option<(String, String)>,
+    new: Option<config::Static>,
+    debug: &Verbose,
+) -> ClientAPIResponse {
+    let mut routes = match &cfg.protocols {
+        Some(p) => p.r#static.clone().unwrap_or_default(),
+        None => Vec::new(),
+    };
+    // take the target route out of the static routes
+    let (pos, old) = match &target {
+        Some((route, mask)) => match routes.iter().position(|st| st.is_route(route, mask)) {
+            Some(pos) => (pos, Some(routes.remove(pos))),
+            None => return ClientAPIResponse::NotFound,
+        },
+        None => (routes.len(), None),
+    };
+    if let Some(st) = &new {
+        routes.insert(pos, st.clone());
+    }
+    // validate the configuration with the updated static routes
+    let mut ncfg = cfg.clone();
+    ncfg.protocols = Some(config::Protocols {
+        r#static: Some(routes),
+    });
+    if let Some(r) = config_errors(&ncfg) {
+        return r;
+    }
+    let action = match (&old, &new) {
+        (None, _) => "adding",
+        (Some(_), Some(_)) => "modifying",
+        (Some(_), None) => "removing",
+    };
+    let msg = match &target {
+        Some((route, mask)) => format!(
+            "Client API: user {} is {} static route {}/{}",
+            sess.user(),
+            action,
+            route,
+            mask
+        ),
+        None => format!(
+            "Client API: user {} is {} a static route",
+            sess.user(),
+            action
+        ),
+    };
+    print_debug(debug, DEBUG_LEVEL_INFO, DEBUG_SRC_API, msg);
+    send_control(ctl, |tx| VRouterControl::Route(ncfg, old, new, tx))
+}
+
 // capi_req_cfg_global_all() function
 fn capi_req_cfg_global_all(cfg: &config::CConfig) -> config::CConfig {
-    // return the entire global configuration (cloned)
-    cfg.clone()
+    // return the entire global configuration (cloned), without its secrets
+    cfg.redacted()
 }
 
 // capi_req_cfg_vrrp_all() function
-fn capi_req_cfg_vrrp_all(cfg: &config::CConfig) -> Vec<config::VRConfig> {
-    // return the configured virtual routers vector (cloned)
-    cfg.vrouter.as_ref().unwrap().to_vec()
+fn capi_req_cfg_vrrp_all(cfg: &config::CConfig) -> ClientAPIResponse {
+    // return the configured virtual routers vector (cloned), without their secrets
+    match &cfg.vrouter {
+        Some(v) => ClientAPIResponse::CfgVrrpAll(v.iter().map(|vr| vr.redacted()).collect()),
+        None => ClientAPIResponse::NotFound,
+    }
 }
 
 // capi_req_cfg_proto_all() function
-fn capi_req_cfg_proto_all(cfg: &config::CConfig) -> config::Protocols {
+fn capi_req_cfg_proto_all(cfg: &config::CConfig) -> ClientAPIResponse {
     // return the configured protocols (cloned)
-    cfg.protocols.as_ref().unwrap().clone()
+    match &cfg.protocols {
+        Some(p) => ClientAPIResponse::CfgProtoAll(p.clone()),
+        None => ClientAPIResponse::NotFound,
+    }
+}
+
+// capi_req_cfg_secrets_all() function
+fn capi_req_cfg_secrets_all(
+    cfg: &config::CConfig,
+    sess: &SessionToken,
+    debug: &Verbose,
+) -> config::CConfig {
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_API,
+        format!(
+            "Client API: user {} is reading the configuration secrets",
+            sess.user()
+        ),
+    );
+    // return the entire global configuration (cloned), with its secrets
+    cfg.clone()
 }
 
 // capi_req_run_global_all() function
@@ -472,6 +1066,7 @@ fn capi_req_run_vrrp_grp_intf(
             // build VRRP attributes response
             let attrs = ResponseVRRPAttrExt {
                 virtual_ip: vr.parameters.attr_vip(),
+                virtual_ips: vr.parameters.attr_vips(),
                 group: vr.parameters.vrid(),
                 interface: {
                     match vr.current_state() {
@@ -487,6 +1082,10 @@ fn capi_req_run_vrrp_grp_intf(
                 },
                 priority: vr.parameters.prio(),
                 preempt: vr.parameters.preempt(),
+                preempt_delay: vr.parameters.preempt_delay(),
+                startup_delay: vr.parameters.startup_delay(),
+                preempt_delay_pending: vr.timers.preempt_delay_remaining(),
+                startup_delay_pending: vr.timers.startup_delay_remaining(),
                 state: vr.states.states(),
                 auth_type: vr.parameters.authtype(),
                 interface_hwaddress: format!(
@@ -498,9 +1097,31 @@ fn capi_req_run_vrrp_grp_intf(
                     vr.parameters.ifmac()[4],
                     vr.parameters.ifmac()[5],
                 ),
-                advert_interval: vr.parameters.adverint(),
+                advert_interval: vr.parameters.adverint() as f32 / 1000.0,
                 masterdown_interval: vr.parameters.master_down(),
                 skew_time: vr.parameters.skewtime(),
+                track_interfaces: vr
+                    .parameters
+                    .tracked()
+                    .iter()
+                    .map(|t| ResponseTrackAttr {
+                        name: t.name().clone(),
+                        weight: t.decrement(),
+                        state: if t.is_up() { "up" } else { "down" }.to_string(),
+                    })
+                    .collect(),
+                track_scripts: vr
+                    .parameters
+                    .tracked_scripts()
+                    .iter()
+                    .map(|t| ResponseTrackAttr {
+                        name: t.name().clone(),
+                        weight: t.decrement(),
+                        state: if t.is_up() { "healthy" } else { "failed" }.to_string(),
+                    })
+                    .collect(),
+                maintenance: vr.flags.maintenance(),
+                statistics: stats_attr(&vr),
             };
             // return vr's attributes
             Some(attrs)
@@ -510,6 +1131,152 @@ fn capi_req_run_vrrp_grp_intf(
     }
 }
 
+// stats_attr() function
+/// build the statistics section of a virtual router
+fn stats_attr(vr: &VirtualRouter) -> ResponseVRRPStatsAttr {
+    ResponseVRRPStatsAttr {
+        master_transitions: vr.stats.transitions("Master"),
+        new_master_reason: vr.stats.new_master_reason().name().to_string(),
+        rcvd_advertisements: vr.stats.adverts_rcvd(),
+        adv_interval_errors: vr.stats.drops(stats::DropReason::Adverint),
+        ip_ttl_errors: vr.stats.drops(stats::DropReason::Ttl),
+        rcvd_pri_zero_packets: vr.stats.prio_zero_rcvd(),
+        sent_pri_zero_packets: vr.stats.prio_zero_sent(),
+        invalid_type_packets: vr.stats.drops(stats::DropReason::InvalidType),
+        address_list_errors: vr.stats.address_list_errors(),
+        packet_length_errors: vr.stats.drops(stats::DropReason::PacketLength),
+        // the counters change at most once per advertisement interval (in ms)
+        refresh_rate: vr.parameters.adverint(),
+        discontinuity_time: vr.stats.discontinuity_time(),
+    }
+}
+
+// capi_req_run_metrics() function
+/// format the virtual routers' metrics (Prometheus text format)
+fn capi_req_run_metrics(vrs: &Vec<Arc<RwLock<VirtualRouter>>>) -> String {
+    let vrs: Vec<_> = vrs.iter().map(|vr| vr.read().unwrap()).collect();
+    let labels: Vec<String> = vrs
+        .iter()
+        .map(|vr| {
+            format!(
+                "group=\"{}\",interface=\"{}\"",
+                vr.parameters.vrid(),
+                vr.parameters.cfg_interface()
+            )
+        })
+        .collect();
+    let mut m = String::new();
+
+    // metric header
+    let header = |m: &mut String, name: &str, kind: &str, help: &str| {
+        m.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, help, name, kind
+        ));
+    };
+
+    header(
+        &mut m,
+        "rvrrpd_vrouter_state",
+        "gauge",
+        "Current state of the virtual router.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        for state in &["Init", "Backup", "Master", "Down"] {
+            let v = if vr.current_state() == *state { 1 } else { 0 };
+            m.push_str(&format!(
+                "rvrrpd_vrouter_state{{{},state=\"{}\"}} {}\n",
+                l, state, v
+            ));
+        }
+    }
+    header(
+        &mut m,
+        "rvrrpd_vrouter_state_duration_seconds",
+        "gauge",
+        "Time spent in the current state.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        m.push_str(&format!(
+            "rvrrpd_vrouter_state_duration_seconds{{{}}} {:.3}\n",
+            l,
+            vr.stats.state_duration().as_millis() as f64 / 1000.0
+        ));
+    }
+    header(
+        &mut m,
+        "rvrrpd_vrouter_priority",
+        "gauge",
+        "Effective priority of the virtual router.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        m.push_str(&format!(
+            "rvrrpd_vrouter_priority{{{}}} {}\n",
+            l,
+            vr.parameters.prio()
+        ));
+    }
+    header(
+        &mut m,
+        "rvrrpd_vrouter_transitions_total",
+        "counter",
+        "State transitions of the virtual router, by new state.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        for state in &stats::TRANSITION_STATES {
+            m.push_str(&format!(
+                "rvrrpd_vrouter_transitions_total{{{},state=\"{}\"}} {}\n",
+                l,
+                state,
+                vr.stats.transitions(state)
+            ));
+        }
+    }
+    header(
+        &mut m,
+        "rvrrpd_vrouter_advertisements_sent_total",
+        "counter",
+        "Advertisements sent by the virtual router.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        m.push_str(&format!(
+            "rvrrpd_vrouter_advertisements_sent_total{{{}}} {}\n",
+            l,
+            vr.stats.adverts_sent()
+        ));
+    }
+    header(
+        &mut m,
+        "rvrrpd_vrouter_advertisements_received_total",
+        "counter",
+        "Valid advertisements received by the virtual router.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        m.push_str(&format!(
+            "rvrrpd_vrouter_advertisements_received_total{{{}}} {}\n",
+            l,
+            vr.stats.adverts_rcvd()
+        ));
+    }
+    header(
+        &mut m,
+        "rvrrpd_vrouter_advertisements_dropped_total",
+        "counter",
+        "Received advertisements dropped, by reason.",
+    );
+    for (vr, l) in vrs.iter().zip(&labels) {
+        for reason in stats::DropReason::all().iter() {
+            m.push_str(&format!(
+                "rvrrpd_vrouter_advertisements_dropped_total{{{},reason=\"{}\"}} {}\n",
+                l,
+                reason.name(),
+                vr.stats.drops(*reason)
+            ));
+        }
+    }
+    m
+}
+
 // capi_req_run_proto_all() function
 fn capi_req_run_proto_all(vrs: &Vec<Arc<RwLock<VirtualRouter>>>) -> Option<ResponseProtoAttr> {
     // get static attributes vector (if any)
@@ -529,8 +1296,9 @@ fn capi_req_run_proto_static(
 ) -> Option<Vec<ResponseProtoStaticAttr>> {
     // create static attributes vector
     let mut pattrs: Vec<ResponseProtoStaticAttr> = Vec::new();
-    // access only first virtual router
-    let vr = &vrs[0];
+    // access only first virtual router (the protocols are shared),
+    // no static route is running without virtual router
+    let vr = vrs.first()?;
     // get read access
     let vro = vr.read().unwrap();
     // get access to protocols structure
@@ -542,27 +1310,9 @@ fn capi_req_run_proto_static(
             for st in stv {
                 // build protocols attributes response
                 let attrs = ResponseProtoStaticAttr {
-                    destination: format!(
-                        "{}.{}.{}.{}",
-                        st.route()[0],
-                        st.route()[1],
-                        st.route()[2],
-                        st.route()[3]
-                    ),
-                    mask: format!(
-                        "{}.{}.{}.{}",
-                        st.mask()[0],
-                        st.mask()[1],
-                        st.mask()[2],
-                        st.mask()[3]
-                    ),
-                    next_hop: format!(
-                        "{}.{}.{}.{}",
-                        st.nh()[0],
-                        st.nh()[1],
-                        st.nh()[2],
-                        st.nh()[3]
-                    ),
+                    destination: st.route().to_string(),
+                    mask: st.mask().to_string(),
+                    next_hop: st.nh().to_string(),
                     metric: st.metric(),
                     mtu: st.mtu(),
                 };
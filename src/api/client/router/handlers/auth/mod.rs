@@ -35,10 +35,10 @@ pub fn client(mut state: State) -> Box<HandlerFuture> {
                     }
                 };
                 // send authentication request
-                down.query(q);
+                let answer = down.query(q);
 
-                // read downstream API channel
-                match down.read() {
+                // read the answer
+                match answer {
                     // read authentication response
                     ClientAPIResponse::AuthResponse(sess) => {
                         match sess {
@@ -51,6 +51,8 @@ pub fn client(mut state: State) -> Box<HandlerFuture> {
                                     Cookie::build(COOKIE_TIMESTAMP, st.ts_since()).http_only(true);
                                 let mut cookie_nonce =
                                     Cookie::build(COOKIE_NONCE, st.nonce()).http_only(true);
+                                let mut cookie_level =
+                                    Cookie::build(COOKIE_LEVEL, st.level()).http_only(true);
                                 let mut cookie_token =
                                     Cookie::build(COOKIE_TOKEN, st.token()).http_only(true);
                                 // add 'secure' attribute if TLS is enabled
@@ -58,6 +60,7 @@ pub fn client(mut state: State) -> Box<HandlerFuture> {
                                     cookie_user = cookie_user.secure(true);
                                     cookie_ts = cookie_ts.secure(true);
                                     cookie_nonce = cookie_nonce.secure(true);
+                                    cookie_level = cookie_level.secure(true);
                                     cookie_token = cookie_token.secure(true);
                                 }
                                 // add 'SameSite' attribute and finish the cookies
@@ -65,6 +68,8 @@ pub fn client(mut state: State) -> Box<HandlerFuture> {
                                 let cookie_ts = cookie_ts.same_site(SameSite::Strict).finish();
                                 let cookie_nonce =
                                     cookie_nonce.same_site(SameSite::Strict).finish();
+                                let cookie_level =
+                                    cookie_level.same_site(SameSite::Strict).finish();
                                 let cookie_token =
                                     cookie_token.same_site(SameSite::Strict).finish();
                                 // create an empty response
@@ -76,6 +81,8 @@ pub fn client(mut state: State) -> Box<HandlerFuture> {
                                     .append(SET_COOKIE, cookie_ts.to_string().parse().unwrap());
                                 resp.headers_mut()
                                     .append(SET_COOKIE, cookie_nonce.to_string().parse().unwrap());
+                                resp.headers_mut()
+                                    .append(SET_COOKIE, cookie_level.to_string().parse().unwrap());
                                 resp.headers_mut()
                                     .append(SET_COOKIE, cookie_token.to_string().parse().unwrap());
                                 // return future
@@ -101,6 +108,36 @@ pub fn client(mut state: State) -> Box<HandlerFuture> {
     Box::new(f)
 }
 
+/// logout() handler function
+pub fn logout(state: State) -> (State, Response<Body>) {
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream, closing the session
+    let q = ClientAPIQuery::AuthLogout(read_session(&state));
+    let answer = down.query(q);
+
+    // build the response from the answer
+    let htbody = write_response(&state, answer, StatusCode::NO_CONTENT);
+    return (state, htbody);
+}
+
+/// revoke() handler function
+pub fn revoke(mut state: State) -> (State, Response<Body>) {
+    let p = UserExtractor::take_from(&mut state);
+
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream, closing all the sessions of the user
+    let q = ClientAPIQuery::AuthRevoke(read_session(&state), p.user);
+    let answer = down.query(q);
+
+    // build the response from the answer
+    let htbody = write_response(&state, answer, StatusCode::NO_CONTENT);
+    return (state, htbody);
+}
+
 /// regex_captures_authav() function
 /// creates a globally accessible and static compiled regular expression
 fn regex_captures_authav(content: &String) -> Option<regex::Captures> {
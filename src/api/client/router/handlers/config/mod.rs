@@ -9,3 +9,6 @@ pub mod vrouter;
 
 // protocols configuration handlers
 pub mod protocols;
+
+// unredacted configuration handlers
+pub mod secrets;
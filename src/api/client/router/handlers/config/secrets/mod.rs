@@ -0,0 +1,29 @@
+//! Client API - unredacted configuration handlers
+use super::*;
+
+/// all() handler function
+pub fn all(state: State) -> (State, Response<Body>) {
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // read the session
+    let sess = read_session(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::CfgSecretsAll(sess);
+    let answer = down.query(q);
+
+    // set HTTP body from the answer
+    let htbody = {
+        match answer {
+            // if a response is returned
+            ClientAPIResponse::CfgSecretsAll(ans) => serialize_answer(&state, ans),
+            ClientAPIResponse::Unauthorized => {
+                create_empty_response(&state, StatusCode::UNAUTHORIZED)
+            }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
+            _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    };
+    return (state, htbody);
+}
@@ -1,35 +1,64 @@
 //! Client API - virtual routers configuration handlers
 use super::*;
 
+// gotham
+use gotham::handler::HandlerFuture;
+
 /// all() handler function
 pub fn all(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
-
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
+    // read the session
+    let sess = read_session(&state);
 
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::CfgVrrpAll(sess);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer (blocking)
+    // build the response from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             // if a response is returned
             ClientAPIResponse::CfgVrrpAll(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
+            ClientAPIResponse::NotFound => create_empty_response(&state, StatusCode::NOT_FOUND),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
     return (state, htbody);
 }
+
+/// create() handler function
+pub fn create(state: State) -> Box<HandlerFuture> {
+    json_write_query(state, StatusCode::CREATED, |sess, vr| {
+        ClientAPIQuery::CfgVrrpAdd(sess, vr)
+    })
+}
+
+/// modify() handler function
+pub fn modify(mut state: State) -> Box<HandlerFuture> {
+    let p = GroupIdInterfaceExtractor::take_from(&mut state);
+    json_write_query(state, StatusCode::OK, move |sess, update| {
+        ClientAPIQuery::CfgVrrpModify(sess, p.group_id, p.interface, update)
+    })
+}
+
+/// delete() handler function
+pub fn delete(mut state: State) -> (State, Response<Body>) {
+    let p = GroupIdInterfaceExtractor::take_from(&mut state);
+
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::CfgVrrpDelete(read_session(&state), p.group_id, p.interface);
+    let answer = down.query(q);
+
+    // build the response from the answer
+    let htbody = write_response(&state, answer, StatusCode::OK);
+    return (state, htbody);
+}
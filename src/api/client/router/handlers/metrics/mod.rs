@@ -0,0 +1,50 @@
+//! Client API - Prometheus metrics handlers
+use super::*;
+
+// Prometheus text exposition format
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// scrape() handler function
+pub fn scrape(state: State) -> (State, Response<Body>) {
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let answer = down.query(ClientAPIQuery::RunMetrics(read_session(&state)));
+
+    // build the response from the answer
+    let htbody = {
+        match answer {
+            ClientAPIResponse::RunMetrics(m) => create_response(
+                &state,
+                StatusCode::OK,
+                METRICS_CONTENT_TYPE.parse::<mime::Mime>().unwrap(),
+                m,
+            ),
+            ClientAPIResponse::Unauthorized => {
+                create_empty_response(&state, StatusCode::UNAUTHORIZED)
+            }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
+            _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    };
+    return (state, htbody);
+}
+
+/// public() handler function (metrics listener, no authentication)
+pub fn public(state: State) -> (State, Response<Body>) {
+    // read the virtual routers directly, the anonymous scrapes never reach
+    // the client API thread
+    let m = {
+        let source = MetricsSource::borrow_from(&state);
+        let vrs = source.vrouters.read().unwrap().clone();
+        capi_req_run_metrics(&vrs)
+    };
+    let htbody = create_response(
+        &state,
+        StatusCode::OK,
+        METRICS_CONTENT_TYPE.parse::<mime::Mime>().unwrap(),
+        m,
+    );
+    return (state, htbody);
+}
@@ -13,6 +13,9 @@ pub mod config;
 // running config scope handlers
 pub mod run;
 
+// Prometheus metrics handlers
+pub mod metrics;
+
 // index() function
 pub fn index(state: State) -> (State, Response<Body>) {
     let body = r#"
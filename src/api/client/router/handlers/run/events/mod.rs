@@ -0,0 +1,54 @@
+//! Client API - virtual routers event stream handlers
+use super::*;
+
+// std
+use std::io;
+
+// hyper
+use hyper::header::{HeaderValue, CACHE_CONTROL};
+
+// events
+use crate::events;
+
+/// stream() handler function
+pub fn stream(state: State) -> (State, Response<Body>) {
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // the stream is closed with its session (logout, revocation or expiry)
+    let sess = read_session(&state);
+    let session = match sess.peer() {
+        Some(_) => None,
+        None => Some(sess.nonce()),
+    };
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::RunEvents(sess);
+    let answer = down.query(q);
+
+    // build the response from the answer
+    let htbody = {
+        match answer {
+            // stream the events as they are published (Server-Sent Events)
+            ClientAPIResponse::RunEvents => {
+                let rx = events::subscribe(session)
+                    .map_err(|_| io::Error::new(io::ErrorKind::Other, "event stream closed"));
+                let mut resp = create_response(
+                    &state,
+                    StatusCode::OK,
+                    mime::TEXT_EVENT_STREAM,
+                    Body::wrap_stream(rx),
+                );
+                resp.headers_mut()
+                    .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+                resp
+            }
+            ClientAPIResponse::Unauthorized => {
+                create_empty_response(&state, StatusCode::UNAUTHORIZED)
+            }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
+            _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    };
+    return (state, htbody);
+}
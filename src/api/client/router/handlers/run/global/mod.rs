@@ -6,28 +6,22 @@ pub fn all(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
+    // read the session
+    let sess = read_session(&state);
 
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
-
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::RunGlobalAll(sess);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer and set HTTP body (blocking)
+    // set HTTP body from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             // if a response is returned
             ClientAPIResponse::RunGlobalAll(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
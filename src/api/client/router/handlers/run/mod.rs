@@ -9,3 +9,6 @@ pub mod vrrp;
 
 // protocols running config
 pub mod protocols;
+
+// virtual routers event stream
+pub mod events;
@@ -1,33 +1,30 @@
 //! Client API - protocols running configuration handlers
 use super::*;
 
+// gotham
+use gotham::handler::HandlerFuture;
+
 /// all() handler function
 pub fn all(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
-
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
+    // read the session
+    let sess = read_session(&state);
 
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::RunProtoAll(sess);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer and set HTTP body (blocking)
+    // set HTTP body from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             // if a response is returned
             ClientAPIResponse::RunProtoAll(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
@@ -39,32 +36,55 @@ pub fn pstatic(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
+    // read the session
+    let sess = read_session(&state);
 
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
-
-    // send a query downstream
-    let sess = SessionToken::new();
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::RunProtoStatic(sess);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer and set HTTP body (blocking)
+    // set HTTP body from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             // if a response is returned
             ClientAPIResponse::RunProtoStatic(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
     return (state, htbody);
 }
+
+/// pstatic_add() handler function
+pub fn pstatic_add(state: State) -> Box<HandlerFuture> {
+    json_write_query(state, StatusCode::CREATED, |sess, st| {
+        ClientAPIQuery::RunProtoStaticAdd(sess, st)
+    })
+}
+
+/// pstatic_modify() handler function
+pub fn pstatic_modify(mut state: State) -> Box<HandlerFuture> {
+    let p = StaticRouteExtractor::take_from(&mut state);
+    json_write_query(state, StatusCode::OK, move |sess, st| {
+        ClientAPIQuery::RunProtoStaticModify(sess, p.route, p.mask, st)
+    })
+}
+
+/// pstatic_delete() handler function
+pub fn pstatic_delete(mut state: State) -> (State, Response<Body>) {
+    let p = StaticRouteExtractor::take_from(&mut state);
+
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::RunProtoStaticDelete(read_session(&state), p.route, p.mask);
+    let answer = down.query(q);
+
+    // build the response from the answer
+    let htbody = write_response(&state, answer, StatusCode::OK);
+    return (state, htbody);
+}
@@ -6,28 +6,22 @@ pub fn all(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
+    // read the session
+    let sess = read_session(&state);
 
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
-
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::RunVRRPAll(sess);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer and set HTTP body (blocking)
+    // set HTTP body from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             // if a response is returned
             ClientAPIResponse::RunVRRPAll(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
@@ -39,31 +33,25 @@ pub fn group(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
-
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
+    // read the session
+    let sess = read_session(&state);
 
     // extract group_id from GET path
     let path = GroupIdExtractor::borrow_from(&state);
     let gid = path.group_id;
 
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::RunVRRPGrp(sess, gid);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer and set HTTP body (blocking)
+    // set HTTP body from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             ClientAPIResponse::RunVRRPGrp(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
@@ -75,35 +63,110 @@ pub fn group_interface(state: State) -> (State, Response<Body>) {
     // borrow references to the Downstream API
     let down = DownstreamAPI::borrow_from(&state);
 
-    // retrieve session cookie
-    let (user, ts_since, nonce, token) = read_session_cookies(&state);
-
-    // create SessionToken
-    let mut sess = SessionToken::new();
-    sess.set_user(user);
-    sess.set_tssince(ts_since);
-    sess.set_nonce(nonce);
-    sess.set_token(token);
+    // read the session
+    let sess = read_session(&state);
 
     // extract group_id and interface from GET path
     let path = GroupIdInterfaceExtractor::borrow_from(&state);
     let gid = path.group_id;
     let intf = path.interface.clone();
 
-    // send a query downstream
+    // send a query downstream and wait for its answer (blocking)
     let q = ClientAPIQuery::RunVRRPGrpIntf(sess, gid, intf);
-    down.query(q);
+    let answer = down.query(q);
 
-    // read answer and set HTTP body (blocking)
+    // set HTTP body from the answer
     let htbody = {
-        match down.read() {
+        match answer {
             // if a response is returned
             ClientAPIResponse::RunVRRPGrpIntf(ans) => serialize_answer(&state, ans),
             ClientAPIResponse::Unauthorized => {
                 create_empty_response(&state, StatusCode::UNAUTHORIZED)
             }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
             _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
         }
     };
     return (state, htbody);
 }
+
+/// resign() handler function
+pub fn resign(state: State) -> (State, Response<Body>) {
+    admin(state, AdminAction::Resign)
+}
+
+/// maintenance() handler function
+pub fn maintenance(state: State) -> (State, Response<Body>) {
+    admin(state, AdminAction::Maintenance(true))
+}
+
+/// release() handler function
+pub fn release(state: State) -> (State, Response<Body>) {
+    admin(state, AdminAction::Maintenance(false))
+}
+
+/// takeover() handler function
+pub fn takeover(state: State) -> (State, Response<Body>) {
+    admin(state, AdminAction::Takeover)
+}
+
+// admin() function
+/// send an administrative action on a virtual router downstream
+fn admin(mut state: State, action: AdminAction) -> (State, Response<Body>) {
+    let p = GroupIdInterfaceExtractor::take_from(&mut state);
+
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::RunVRRPAdmin(read_session(&state), p.group_id, p.interface, action);
+    let answer = down.query(q);
+
+    // build the response from the answer, the action is carried out asynchronously by the worker
+    let htbody = write_response(&state, answer, StatusCode::ACCEPTED);
+    return (state, htbody);
+}
+
+/// stats() handler function
+pub fn stats(mut state: State) -> (State, Response<Body>) {
+    let p = GroupIdInterfaceExtractor::take_from(&mut state);
+
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::RunVRRPStats(read_session(&state), p.group_id, p.interface);
+    let answer = down.query(q);
+
+    // set HTTP body from the answer
+    let htbody = {
+        match answer {
+            ClientAPIResponse::RunVRRPStats(Some(ans)) => serialize_answer(&state, ans),
+            ClientAPIResponse::RunVRRPStats(None) => {
+                create_empty_response(&state, StatusCode::NOT_FOUND)
+            }
+            ClientAPIResponse::Unauthorized => {
+                create_empty_response(&state, StatusCode::UNAUTHORIZED)
+            }
+            ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
+            _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    };
+    return (state, htbody);
+}
+
+/// stats_reset() handler function
+pub fn stats_reset(mut state: State) -> (State, Response<Body>) {
+    let p = GroupIdInterfaceExtractor::take_from(&mut state);
+
+    // borrow references to the Downstream API
+    let down = DownstreamAPI::borrow_from(&state);
+
+    // send a query downstream and wait for its answer (blocking)
+    let q = ClientAPIQuery::RunVRRPStatsReset(read_session(&state), p.group_id, p.interface);
+    let answer = down.query(q);
+
+    // build the response from the answer
+    let htbody = write_response(&state, answer, StatusCode::NO_CONTENT);
+    return (state, htbody);
+}
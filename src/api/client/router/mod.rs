@@ -2,14 +2,20 @@
 use super::*;
 
 // std
-use std::net::ToSocketAddrs;
+use std::ffi::CString;
+use std::fs::{self, Permissions};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
 
 // future
-use futures::future::Future;
+use futures::future::{self, Future};
+use futures::Stream;
 
 // gotham
 extern crate gotham;
 use gotham::bind_server;
+use gotham::handler::{Handler, HandlerFuture, IntoHandlerError, IntoResponse};
 use gotham::helpers::http::response::{create_empty_response, create_response};
 use gotham::middleware::cookie::CookieParser;
 use gotham::middleware::state::StateMiddleware;
@@ -19,9 +25,17 @@ use gotham::router::builder::*;
 use gotham::router::Router;
 use gotham::state::{FromState, State};
 
+// debug
+use crate::debug::{print_debug, print_error, Verbose};
+
+// constants
+use crate::constants::{DEBUG_LEVEL_INFO, DEBUG_SRC_API};
+
 // hyper
 extern crate hyper;
 use hyper::header::SET_COOKIE;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
 use hyper::{Body, Response, StatusCode};
 
 // failure
@@ -36,7 +50,10 @@ use openssl::{
 };
 
 // tokio
-use tokio::{net::TcpListener, runtime::Runtime};
+use tokio::{
+    net::{TcpListener, UnixListener},
+    runtime::Runtime,
+};
 
 // tokio_openssl
 extern crate tokio_openssl;
@@ -50,15 +67,20 @@ extern crate cookie;
 use cookie::{Cookie, CookieJar, SameSite};
 
 // serde
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 // handlers
 mod handlers;
 
+// peer credentials authorization
+use super::sessions::auth::peer_user;
+
 // handlers constants
 const COOKIE_USER: &str = "user";
 const COOKIE_TIMESTAMP: &str = "ts";
 const COOKIE_NONCE: &str = "nonce";
+const COOKIE_LEVEL: &str = "level";
 const COOKIE_TOKEN: &str = "token";
 
 // Client API routing
@@ -73,35 +95,54 @@ const COOKIE_TOKEN: &str = "token";
 //     |  |  |_ / PUT     modify global configuration
 //     |  |_ vrouter/     virtual router(s) configuration
 //     |  |  |_ / GET     retrieve all virtual router configuration
-//     |  |  |_ / PUT     modify virtual router configuration
+//     |  |  |_ / POST    add a new virtual router (JSON body)
+//     |  |  |_ <group-id>/<interface>/
+//     |  |     |_ / PUT     modify a virtual router (JSON body)
+//     |  |     |_ / DELETE  remove a virtual router
 //     |  |_ protocols/   protocols configuration
 //     |     |_ / GET     retrieve all protocols configuration
 //     |     |_ / PUT     modify protocols configuration
 //     |_ run/            running configurations objects
-//        |_ global/      running global configuration
-//        |  |_ / GET     retrieve running global configuration
-//        |_ vrrp/
-//        |  |_ / GET      retrieve all VRRP information
-//        |  |_ / PUT      modify a specific virtual router (spec. grp/intf)
-//        |  |_ / POST     add a new VRRP virtual router (spec. grp/intf)
-//        |  |_ / DELETE   remove a specific virtual router (spec. grp/intf)
-//        |  |_ <group-id>/
-//        |     |_ / GET       retrieve group specific information
-//        |     |_ / PUT       modify specific virtual router (spec. intf)
-//        |     |_ / POST      add a new VRRP virtual router (spec. intf)
-//        |     |_ / DELETE    remove a specific virtual router (spec. intf)
-//        |     |_ /<interface>/
-//        |         |_ / GET       get specific virtual router information
-//        |         |_ / PUT       modify specific virtual router
-//        |         |_ / POST      add a new VRRP virtual router
-//        |         |_ / DELETE    remove a specific virtual router
-//        |_ protocols/
-//           |_ / GET          retrieve all protocols information
-//           |_ static/
-//              |_ / GET       retrieve all static routes
-//              |_ / POST      add a new static route (specify route)
-//              |_ / PUT       modify a static route (specify route)
-//              |_ / DELETE    remove a static route (specifc route)
+//     |  |_ global/      running global configuration
+//     |  |  |_ / GET     retrieve running global configuration
+//     |  |_ events/
+//     |  |  |_ / GET      stream the virtual routers' events (Server-Sent Events)
+//     |  |_ vrrp/
+//     |  |  |_ / GET      retrieve all VRRP information
+//     |  |  |_ / PUT      modify a specific virtual router (spec. grp/intf)
+//     |  |  |_ / POST     add a new VRRP virtual router (spec. grp/intf)
+//     |  |  |_ / DELETE   remove a specific virtual router (spec. grp/intf)
+//     |  |  |_ <group-id>/
+//     |  |     |_ / GET       retrieve group specific information
+//     |  |     |_ / PUT       modify specific virtual router (spec. intf)
+//     |  |     |_ / POST      add a new VRRP virtual router (spec. intf)
+//     |  |     |_ / DELETE    remove a specific virtual router (spec. intf)
+//     |  |     |_ /<interface>/
+//     |  |         |_ / GET       get specific virtual router information
+//     |  |         |_ / PUT       modify specific virtual router
+//     |  |         |_ / POST      add a new VRRP virtual router
+//     |  |         |_ / DELETE    remove a specific virtual router
+//     |  |         |_ resign/
+//     |  |         |  |_ / POST      leave the Master state (priority 0 advertisement)
+//     |  |         |_ maintenance/
+//     |  |         |  |_ / POST      pin the virtual router in Backup state
+//     |  |         |  |_ / DELETE    release the maintenance mode
+//     |  |         |_ takeover/
+//     |  |         |  |_ / POST      force the Master state
+//     |  |         |_ stats/
+//     |  |            |_ / GET       retrieve the statistics (RFC6527)
+//     |  |            |_ / DELETE    reset the statistics
+//     |  |_ protocols/
+//     |     |_ / GET          retrieve all protocols information
+//     |     |_ static/
+//     |        |_ / GET       retrieve all static routes
+//     |        |_ / POST      add a new static route (JSON body)
+//     |        |_ <route>/<mask>/
+//     |           |_ / PUT       modify a static route (JSON body)
+//     |           |_ / DELETE    remove a static route
+//     |_ metrics/        Prometheus metrics
+//        |_ / GET        retrieve the metrics (also served without authentication
+//                        on the optional metrics listener)
 //
 
 // router() function
@@ -126,10 +167,20 @@ fn router(down_api: &DownstreamAPI) -> Router {
         // index
         route.get_or_head("/").to(handlers::index);
 
+        // metrics
+        route.get("/metrics").to(handlers::metrics::scrape);
+
         // auth/ scope
         route.scope("/auth", |route| {
             // / (POST)
             route.post("/").to(handlers::auth::client);
+            // / (DELETE)
+            route.delete("/").to(handlers::auth::logout);
+            // sessions/<user>/ (DELETE)
+            route
+                .delete("/sessions/:user")
+                .with_path_extractor::<UserExtractor>()
+                .to(handlers::auth::revoke);
         });
 
         // config/ scope
@@ -141,10 +192,24 @@ fn router(down_api: &DownstreamAPI) -> Router {
             // vrouter/
             route.scope("/vrouter", |route| {
                 route.get("/").to(handlers::config::vrouter::all);
+                route.post("/").to(handlers::config::vrouter::create);
+                // <group-id>/<interface>/
+                route
+                    .put("/:group_id/:interface")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::config::vrouter::modify);
+                route
+                    .delete("/:group_id/:interface")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::config::vrouter::delete);
             });
             // protocols/
             route.scope("/protocols", |route| {
                 route.get("/").to(handlers::config::protocols::all);
+            });
+            // secrets/
+            route.scope("/secrets", |route| {
+                route.get("/").to(handlers::config::secrets::all);
             })
         });
 
@@ -154,6 +219,8 @@ fn router(down_api: &DownstreamAPI) -> Router {
             route.scope("/global", |route| {
                 route.get("/").to(handlers::run::global::all);
             });
+            // events/
+            route.get("/events").to(handlers::run::events::stream);
             // vrrp/ scope
             route.scope("/vrrp", |route| {
                 // /
@@ -168,6 +235,32 @@ fn router(down_api: &DownstreamAPI) -> Router {
                     .get("/:group_id/:interface")
                     .with_path_extractor::<GroupIdInterfaceExtractor>()
                     .to(handlers::run::vrrp::group_interface);
+                // <group-id>/<interface>/ administrative actions
+                route
+                    .post("/:group_id/:interface/resign")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::run::vrrp::resign);
+                route
+                    .post("/:group_id/:interface/maintenance")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::run::vrrp::maintenance);
+                route
+                    .delete("/:group_id/:interface/maintenance")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::run::vrrp::release);
+                route
+                    .post("/:group_id/:interface/takeover")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::run::vrrp::takeover);
+                // <group-id>/<interface>/ statistics
+                route
+                    .get("/:group_id/:interface/stats")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::run::vrrp::stats);
+                route
+                    .delete("/:group_id/:interface/stats")
+                    .with_path_extractor::<GroupIdInterfaceExtractor>()
+                    .to(handlers::run::vrrp::stats_reset);
             });
             // protocols/ scope
             route.scope("/protocols", |route| {
@@ -175,6 +268,18 @@ fn router(down_api: &DownstreamAPI) -> Router {
                 route.get("/").to(handlers::run::protocols::all);
                 // static/
                 route.get("/static").to(handlers::run::protocols::pstatic);
+                route
+                    .post("/static")
+                    .to(handlers::run::protocols::pstatic_add);
+                // static/<route>/<mask>/
+                route
+                    .put("/static/:route/:mask")
+                    .with_path_extractor::<StaticRouteExtractor>()
+                    .to(handlers::run::protocols::pstatic_modify);
+                route
+                    .delete("/static/:route/:mask")
+                    .with_path_extractor::<StaticRouteExtractor>()
+                    .to(handlers::run::protocols::pstatic_delete);
             });
         });
     })
@@ -213,6 +318,167 @@ pub fn start(down_api: DownstreamAPI, host: String, tls: bool, tls_key: String,
     }
 }
 
+// start_local() function
+/// serve the client API on a local Unix socket, the connections being
+/// authorized by their peer credentials instead of the session cookies
+pub fn start_local(
+    down_api: DownstreamAPI,
+    path: String,
+    mode: u32,
+    group: Option<String>,
+    debug: Verbose,
+) {
+    // remove the socket left by a previous instance
+    if let Err(e) = remove_stale_socket(&path) {
+        print_error(
+            &debug,
+            DEBUG_SRC_API,
+            format!("cannot bind the client API socket {}: {}", path, e),
+        );
+        return;
+    }
+    // the socket is only accessible to its owner until its permissions are set
+    let umask = unsafe { libc::umask(0o177) };
+    let listener = UnixListener::bind(&path);
+    unsafe { libc::umask(umask) };
+    let listener = match listener {
+        Ok(l) => l,
+        Err(e) => {
+            print_error(
+                &debug,
+                DEBUG_SRC_API,
+                format!("cannot bind the client API socket {}: {}", path, e),
+            );
+            return;
+        }
+    };
+    if let Err(e) = set_socket_permissions(&path, mode, group) {
+        print_error(
+            &debug,
+            DEBUG_SRC_API,
+            format!(
+                "cannot set the permissions of the client API socket {}: {}",
+                path, e
+            ),
+        );
+        return;
+    }
+    print_debug(
+        &debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_API,
+        format!("Client API Server listening on unix:{}", path),
+    );
+
+    // the gotham state requires a client address
+    let client_addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let router = router(&down_api);
+    let protocol = Arc::new(Http::new());
+    let server = listener
+        .incoming()
+        .map_err(move |e| {
+            print_error(
+                &debug,
+                DEBUG_SRC_API,
+                format!("client API socket error: {}", e),
+            )
+        })
+        .for_each(move |socket| {
+            let uid = match socket.peer_cred() {
+                Ok(cred) => cred.uid,
+                Err(e) => {
+                    print_error(
+                        &debug,
+                        DEBUG_SRC_API,
+                        format!("cannot read the client API peer credentials: {}", e),
+                    );
+                    return Ok(());
+                }
+            };
+            let router = router.clone();
+            let service = service_fn(move |req| {
+                let mut state = State::from_request(req, client_addr);
+                state.put(PeerCredentials { uid });
+                router
+                    .clone()
+                    .handle(state)
+                    .then(|r| -> Result<Response<Body>, hyper::Error> {
+                        Ok(match r {
+                            Ok((_, resp)) => resp,
+                            Err((state, e)) => e.into_response(&state),
+                        })
+                    })
+            });
+            let conn = protocol
+                .serve_connection(socket, service)
+                .map_err(move |e| {
+                    print_error(
+                        &debug,
+                        DEBUG_SRC_API,
+                        format!("client API connection error: {}", e),
+                    )
+                });
+            tokio::spawn(conn);
+            Ok(())
+        });
+    tokio::run(server);
+}
+
+// remove_stale_socket() function
+/// remove the socket left at the given path, refusing to remove any other file
+fn remove_stale_socket(path: &str) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(m) if m.file_type().is_socket() => fs::remove_file(path),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the path exists and is not a socket",
+        )),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// set_socket_permissions() function
+/// set the mode and the group owning the client API socket
+fn set_socket_permissions(path: &str, mode: u32, group: Option<String>) -> io::Result<()> {
+    fs::set_permissions(path, Permissions::from_mode(mode))?;
+    if let Some(group) = group {
+        let name = CString::new(group.as_bytes())?;
+        let gr = unsafe { libc::getgrnam(name.as_ptr()) };
+        if gr.is_null() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("unknown group {}", group),
+            ));
+        }
+        let path = CString::new(path)?;
+        // leave the owner unchanged
+        if unsafe { libc::chown(path.as_ptr(), !0, (*gr).gr_gid) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+// metrics_router() function
+/// router of the metrics listener, serving /metrics only
+fn metrics_router(vrs: &Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>) -> Router {
+    let source = MetricsSource {
+        vrouters: Arc::clone(vrs),
+    };
+    let pipeline = new_pipeline().add(StateMiddleware::new(source));
+    let (chain, pipelines) = single_pipeline(pipeline.build());
+    build_router(chain, pipelines, |route| {
+        route.get("/metrics").to(handlers::metrics::public);
+    })
+}
+
+// start_metrics() function
+pub fn start_metrics(vrs: Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>, host: String) {
+    println!("Metrics Server listening on http://{}", host);
+    gotham::start(host, metrics_router(&vrs))
+}
+
 // build_tls_acceptor() function
 fn build_tls_acceptor(keyfile: String, certfile: String) -> Result<SslAcceptor, Error> {
     // openssl req -new -x509 -sha256 -newkey rsa:2048 -nodes -keyout key.pem -days 365 -out cert.pem
@@ -239,6 +505,98 @@ fn serialize_answer<T: Serialize>(state: &State, ans: T) -> Response<Body> {
     )
 }
 
+// write_response() function
+/// build the response of a write query, using the given status code upon success
+fn write_response(state: &State, resp: ClientAPIResponse, success: StatusCode) -> Response<Body> {
+    match resp {
+        ClientAPIResponse::Done => create_empty_response(&state, success),
+        ClientAPIResponse::BadRequest(e) => error_response(&state, e),
+        ClientAPIResponse::Unauthorized => create_empty_response(&state, StatusCode::UNAUTHORIZED),
+        ClientAPIResponse::Forbidden => create_empty_response(&state, StatusCode::FORBIDDEN),
+        ClientAPIResponse::NotFound => create_empty_response(&state, StatusCode::NOT_FOUND),
+        ClientAPIResponse::NotImplemented => {
+            create_empty_response(&state, StatusCode::NOT_IMPLEMENTED)
+        }
+        _ => create_empty_response(&state, StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+// error_response() function
+/// build a bad request response detailing the error
+fn error_response(state: &State, e: ResponseError) -> Response<Body> {
+    create_response(
+        &state,
+        StatusCode::BAD_REQUEST,
+        mime::APPLICATION_JSON,
+        serde_json::to_vec(&e).expect("serialized response"),
+    )
+}
+
+// json_write_query() function
+/// read the JSON body of a request, send the write query built from it downstream
+/// and answer with its result
+fn json_write_query<T, F>(mut state: State, success: StatusCode, build: F) -> Box<HandlerFuture>
+where
+    T: DeserializeOwned,
+    F: FnOnce(SessionToken, T) -> ClientAPIQuery + Send + 'static,
+{
+    let f = Body::take_from(&mut state)
+        .concat2()
+        .then(move |body| match body {
+            Ok(valid_body) => {
+                // decode the JSON body
+                let obj: T = match serde_json::from_slice(&valid_body) {
+                    Ok(obj) => obj,
+                    Err(e) => {
+                        let e = ResponseError::new("Invalid JSON body", vec![e.to_string()]);
+                        let resp = error_response(&state, e);
+                        return future::ok((state, resp));
+                    }
+                };
+                // send the query downstream and read the answer (blocking)
+                let down = DownstreamAPI::borrow_from(&state);
+                let answer = down.query(build(read_session(&state), obj));
+                let resp = write_response(&state, answer, success);
+                future::ok((state, resp))
+            }
+            Err(e) => future::err((state, e.into_handler_error())),
+        });
+
+    Box::new(f)
+}
+
+// read_session() function
+/// build the SessionToken from the session cookies, or from the peer
+/// credentials of the local socket connections
+fn read_session(state: &State) -> SessionToken {
+    if let Some(peer) = PeerCredentials::try_borrow_from(&state) {
+        return SessionToken::from_peer(peer.uid, peer_user(peer.uid));
+    }
+    let (user, ts_since, nonce, level, token) = read_session_cookies(&state);
+    let mut sess = SessionToken::new();
+    sess.set_user(user);
+    sess.set_level(level);
+    sess.set_tssince(ts_since);
+    sess.set_nonce(nonce);
+    sess.set_token(token);
+    sess
+}
+
+// PeerCredentials structure
+/// credentials of a local socket peer
+#[derive(Clone, StateData)]
+struct PeerCredentials {
+    uid: u32,
+}
+
+// MetricsSource structure
+/// virtual routers read by the metrics listener, without going through
+/// the client API thread
+#[derive(Clone, StateData)]
+struct MetricsSource {
+    vrouters: Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>,
+}
+
 // GroupIdExtractor structure
 #[derive(Deserialize, StateData, StaticResponseExtender)]
 struct GroupIdExtractor {
@@ -252,8 +610,21 @@ struct GroupIdInterfaceExtractor {
     interface: String,
 }
 
+// UserExtractor structure
+#[derive(Deserialize, StateData, StaticResponseExtender)]
+struct UserExtractor {
+    user: String,
+}
+
+// StaticRouteExtractor structure
+#[derive(Deserialize, StateData, StaticResponseExtender)]
+struct StaticRouteExtractor {
+    route: String,
+    mask: String,
+}
+
 // read_session_cookies() function
-pub fn read_session_cookies(state: &State) -> (String, u64, u64, String) {
+pub fn read_session_cookies(state: &State) -> (String, u64, u64, u8, String) {
     // retrieve session cookies
     let c = CookieJar::borrow_from(&state);
     let user = {
@@ -271,12 +642,17 @@ pub fn read_session_cookies(state: &State) -> (String, u64, u64, String) {
             .map(|c| c.value().parse::<u64>().unwrap())
             .unwrap_or_else(|| 0)
     };
+    let level: u8 = {
+        c.get(COOKIE_LEVEL)
+            .and_then(|c| c.value().parse::<u8>().ok())
+            .unwrap_or_else(|| 0)
+    };
     let token = {
         c.get(COOKIE_TOKEN)
             .map(|c| c.value().to_owned())
             .unwrap_or_else(|| "null".to_string())
     };
-    (user, ts_since, nonce, token)
+    (user, ts_since, nonce, level, token)
 }
 
 #[cfg(test)]
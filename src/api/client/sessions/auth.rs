@@ -11,6 +11,9 @@ use token::SessionToken;
 // config
 use crate::config;
 
+// constants
+use crate::constants::API_ACCESS_LEVEL_ADMIN;
+
 // regex
 extern crate regex;
 use regex::Regex;
@@ -33,18 +36,18 @@ pub fn auth_api_client(
 ) -> Option<SessionToken> {
     // authenticate the API user
     let sess = match auth_user_from_cfg(cfg, user, passwd) {
-        Some(usr) => {
+        Some((usr, level)) => {
             // if authentication is succesful, create a new SessionToken
             let mut token = SessionToken::new();
-            // set authenticated user
+            // set authenticated user and its access level
             token.set_user(usr);
+            token.set_level(level);
             // generate the token
             match token.gen_token(cfg) {
                 // if succesfully generated
                 Ok(()) => {
                     // set session duration
-                    let timeout = time::Duration::new(60, 0);
-                    token.set_validfor(timeout.as_secs());
+                    token.set_validfor(cfg.api.as_ref().unwrap().session_lifetime());
                     // return SessionToken
                     Some(token)
                 }
@@ -64,10 +67,10 @@ pub fn auth_api_client(
 /// and for every user, compare the hashed passwords according
 /// to the configured hash function.
 ///
-/// return the user name String if sucessfully authenticated
-fn auth_user_from_cfg(cfg: &config::CConfig, user: String, passwd: String) -> Option<String> {
+/// return the user name String and its access level if sucessfully authenticated
+fn auth_user_from_cfg(cfg: &config::CConfig, user: String, passwd: String) -> Option<(String, u8)> {
     // initialize response
-    let res: Option<String> = None;
+    let res: Option<(String, u8)> = None;
 
     // access configuation api users
     if let Some(a) = &cfg.api {
@@ -76,7 +79,7 @@ fn auth_user_from_cfg(cfg: &config::CConfig, user: String, passwd: String) -> Op
                 Some(c) => {
                     let alg = c.get(1).unwrap().as_str().to_string();
                     let username = c.get(2).unwrap().as_str().to_string();
-                    let _access = c.get(3).unwrap().as_str().to_string();
+                    let access = c.get(3).unwrap().as_str().parse::<u8>().unwrap();
                     let salt = c.get(4).unwrap().as_str().to_string();
                     let hash = c.get(5).unwrap().as_str().to_string();
                     // if the username matches
@@ -100,13 +103,13 @@ fn auth_user_from_cfg(cfg: &config::CConfig, user: String, passwd: String) -> Op
                                 thread::sleep(time);
                                 // compare hashed values
                                 if hash == h2 {
-                                    return Some(username);
+                                    return Some((username, access));
                                 }
                             }
                             "SCRYPT" => {
                                 // check if password is matching the stored hash
                                 if scrypt_check(&passwd, &hash).is_ok() {
-                                    return Some(username);
+                                    return Some((username, access));
                                 }
                             }
                             // if alg doesn't match, continue
@@ -124,6 +127,50 @@ fn auth_user_from_cfg(cfg: &config::CConfig, user: String, passwd: String) -> Op
     res
 }
 
+/// user_access_level() function
+/// return the access level of a configured API user
+pub fn user_access_level(cfg: &config::CConfig, user: &str) -> Option<u8> {
+    if let Some(a) = &cfg.api {
+        for acc in a.users() {
+            if let Some(c) = regex_captures_apiuser(&acc) {
+                if c.get(2).unwrap().as_str() == user {
+                    return c.get(3).unwrap().as_str().parse::<u8>().ok();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// peer_access_level() function
+/// return the access level of a local socket peer, root having
+/// the administrative access level
+pub fn peer_access_level(cfg: &config::CConfig, uid: u32, user: &str) -> Option<u8> {
+    let cs = cfg.control_socket.as_ref()?;
+    if uid == 0 {
+        return Some(API_ACCESS_LEVEL_ADMIN);
+    }
+    cs.users()
+        .into_iter()
+        .find(|(name, _)| name == user)
+        .map(|(_, level)| level)
+}
+
+/// peer_user() function
+/// return the name of a local user (or its user id if unknown)
+pub fn peer_user(uid: u32) -> String {
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; 1024];
+    let mut res: *mut libc::passwd = std::ptr::null_mut();
+    let r = unsafe { libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut res) };
+    if r != 0 || res.is_null() {
+        return uid.to_string();
+    }
+    unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) }
+        .to_string_lossy()
+        .into_owned()
+}
+
 /// regex_captures_apiuser function
 fn regex_captures_apiuser(acc: &String) -> Option<regex::Captures> {
     // the API user account information is formatted as follow:
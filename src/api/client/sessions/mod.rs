@@ -5,3 +5,6 @@ pub mod auth;
 
 // session token
 pub mod token;
+
+// session registry
+pub mod registry;
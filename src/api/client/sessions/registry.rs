@@ -0,0 +1,125 @@
+//! Client API - session registry module
+
+// std
+use std::time::SystemTime;
+
+// session token
+use super::token::SessionToken;
+
+// access levels
+use super::auth::{peer_access_level, user_access_level};
+
+// config
+use crate::config;
+
+// events
+use crate::events;
+
+// constants
+use crate::constants::API_SESSION_DFLT_TIMEOUT;
+
+/// Registered session structure
+struct Registered {
+    sess: SessionToken, // issued session
+    last_seen: u64,     // time of the last request
+}
+
+/// SessionRegistry structure
+/// sessions issued by the client API, until they are closed, revoked or expired
+pub struct SessionRegistry {
+    sessions: Vec<Registered>,
+}
+
+/// SessionRegistry implementation
+impl SessionRegistry {
+    // new() method
+    pub fn new() -> SessionRegistry {
+        SessionRegistry {
+            sessions: Vec::new(),
+        }
+    }
+    // register() method
+    /// register a newly issued session
+    pub fn register(&mut self, cfg: &config::CConfig, sess: &SessionToken) {
+        self.expire(cfg);
+        self.sessions.push(Registered {
+            sess: sess.clone(),
+            last_seen: now(),
+        });
+    }
+    // access_level() method
+    /// validate a session and return its access level, resetting its idle timeout
+    pub fn access_level(&mut self, cfg: &config::CConfig, sess: &SessionToken) -> Option<u8> {
+        // the local socket peers are authorized by their credentials
+        if let Some(uid) = sess.peer() {
+            return peer_access_level(cfg, uid, &sess.user());
+        }
+        self.expire(cfg);
+        let now = now();
+        let r = self
+            .sessions
+            .iter_mut()
+            .find(|r| r.sess.nonce() == sess.nonce())?;
+        sess.validate(&r.sess)?;
+        // the access level is read from the running configuration, the sessions
+        // of the users removed from it (e.g. by a reload) are closed
+        match user_access_level(cfg, &r.sess.user()) {
+            Some(level) => {
+                r.last_seen = now;
+                Some(level)
+            }
+            None => {
+                self.remove(sess);
+                None
+            }
+        }
+    }
+    // remove() method
+    /// close a (validated) session
+    pub fn remove(&mut self, sess: &SessionToken) {
+        let nonce = sess.nonce();
+        self.close(|r| r.sess.nonce() == nonce);
+    }
+    // revoke_user() method
+    /// close all the sessions of a user, return their number
+    pub fn revoke_user(&mut self, user: &str) -> usize {
+        self.close(|r| r.sess.user() == user)
+    }
+    // expire() method
+    /// close the sessions past their lifetime or idle timeout,
+    /// the sessions with an open event stream are in use
+    pub fn expire(&mut self, cfg: &config::CConfig) {
+        let now = now();
+        let timeout = match &cfg.api {
+            Some(a) => a.session_timeout(),
+            None => API_SESSION_DFLT_TIMEOUT,
+        };
+        for r in self.sessions.iter_mut() {
+            if events::streaming(&r.sess.nonce()) {
+                r.last_seen = now;
+            }
+        }
+        self.close(|r| now >= r.sess.valid_until() || now >= r.last_seen + timeout);
+    }
+    // close() method
+    /// remove the matching sessions along with their event streams,
+    /// return their number
+    fn close<F: Fn(&Registered) -> bool>(&mut self, matches: F) -> usize {
+        let (closed, kept): (Vec<Registered>, Vec<Registered>) =
+            self.sessions.drain(..).partition(|r| matches(r));
+        self.sessions = kept;
+        for r in &closed {
+            events::unsubscribe(&r.sess.nonce());
+        }
+        closed.len()
+    }
+}
+
+// now() function
+/// current system time (in seconds since the Unix Epoch)
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
@@ -20,6 +20,7 @@ use std::time;
 use crate::config;
 
 /// SessionTroken structure
+#[derive(Clone)]
 pub struct SessionToken {
     user: String,
     ts_since: u64,
@@ -27,6 +28,8 @@ pub struct SessionToken {
     nonce: u64,
     token: String,
     secure: bool,
+    level: u8,         // access level of the user
+    peer: Option<u32>, // user id of the local socket peer
 }
 
 /// SessionToken implementation
@@ -40,12 +43,26 @@ impl SessionToken {
             nonce: 0,
             token: "null".to_string(),
             secure: false,
+            level: 0,
+            peer: None,
         }
     }
+    // from_peer() method
+    /// session of a local socket peer, authorized by its credentials
+    pub fn from_peer(uid: u32, user: String) -> SessionToken {
+        let mut sess = SessionToken::new();
+        sess.user = user;
+        sess.peer = Some(uid);
+        sess
+    }
     // set_user() setter
     pub fn set_user(&mut self, user: String) {
         self.user = user;
     }
+    // set_level() setter
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level;
+    }
     // set_tssince() setter
     pub fn set_tssince(&mut self, ts: u64) {
         self.ts_since = ts;
@@ -78,11 +95,9 @@ impl SessionToken {
         let nonce: u64 = rng.gen();
         // set the nonce
         self.nonce = nonce;
-        // concatenate the user and time with the nonce
-        let utn = format!("{}{}{}", self.user, self.ts_since, self.nonce);
-        // hash the above elements
+        // hash the user, access level, time and nonce
         let secret = cfg.api.as_ref().unwrap().secret();
-        let token = gen_hmac_string(&utn, secret);
+        let token = gen_hmac_string(&self.hmac_input(), secret);
         // set the hashed token
         self.token = token;
         // set the token's 'secure' flag if tls is enabled
@@ -108,27 +123,50 @@ impl SessionToken {
     pub fn token(&self) -> String {
         self.token.clone()
     }
+    // level() method
+    pub fn level(&self) -> String {
+        format!("{}", self.level)
+    }
     // secure() method
     pub fn secure(&self) -> bool {
         self.secure
     }
+    // valid_until() getter
+    /// time at which the session expires (in seconds since the Unix Epoch)
+    pub fn valid_until(&self) -> u64 {
+        self.ts_valid
+    }
+    // peer() getter
+    /// user id of the local socket peer (if any)
+    pub fn peer(&self) -> Option<u32> {
+        self.peer
+    }
+    // hmac_input() method
+    /// the authenticated elements of the token, separated so that
+    /// neither the user nor the access level can be shifted
+    fn hmac_input(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.user, self.level, self.ts_since, self.nonce
+        )
+    }
     // validate() method
-    // check the integrity of the token
-    pub fn validate(&self, cfg: &config::CConfig) -> Option<String> {
-        // concatenate the user and time with the nonce
-        let utn = format!("{}{}{}", self.user, self.ts_since, self.nonce);
-        // hash the above elements
-        let secret = cfg.api.as_ref().unwrap().secret();
-        let token = gen_hmac_string(&utn, secret);
-        // compare the stored (or passed) hash with the recomputed hash/token above
+    // check the passed token against the issued session, return its access level
+    // (the issued token is kept, so that it does not depend on the current API secret)
+    pub fn validate(&self, issued: &SessionToken) -> Option<u8> {
         // make sure the comparison time is randomized or constant to avoid timing attacks
         let mut rng = rand::thread_rng();
         let rdelay = rng.gen_range(10, 40);
         let time = time::Duration::from_millis(rdelay);
         thread::sleep(time);
-        // return the token if they match
-        if self.token == token {
-            Some(token)
+        // return the access level if they match
+        if self.user == issued.user
+            && self.level == issued.level
+            && self.ts_since == issued.ts_since
+            && self.nonce == issued.nonce
+            && self.token == issued.token
+        {
+            Some(issued.level)
         } else {
             None
         }
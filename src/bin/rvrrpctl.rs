@@ -0,0 +1,592 @@
+//! # rvrrpctl
+//!
+//! `rvrrpctl` is the command-line client of the rVRRPd Client API.
+
+// getopts
+use getopts::Options;
+
+// std
+use std::env;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+// openssl
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+
+// serde_json
+use serde_json::Value;
+
+// default Client API address
+const DFLT_HOST: &str = "127.0.0.1:7080";
+
+// default session file (in the home directory)
+const DFLT_SESSION_FILE: &str = ".rvrrpctl-session";
+
+/// MyError Type
+#[derive(Debug)]
+struct MyError(String);
+
+impl std::fmt::Display for MyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl Error for MyError {}
+
+// err() function
+fn err<T>(msg: String) -> Result<T, Box<dyn Error>> {
+    Err(Box::new(MyError(msg)))
+}
+
+/// HTTP Response Structure
+struct Response {
+    status: u16,
+    cookies: Vec<String>,
+    body: Vec<u8>,
+}
+
+/// Client Structure
+struct Client {
+    host: String,           // Client API address
+    socket: Option<String>, // Client API local socket (authorized by the peer credentials)
+    tls: bool,              // use HTTPS
+    insecure: bool,         // do not verify the server certificate
+    session_file: PathBuf,  // session cookies storage
+    json: bool,             // print the JSON responses
+}
+
+// Client Type Implementation
+impl Client {
+    // request() method
+    /// send an HTTP request to the Client API, with the session cookies
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+    ) -> Result<Response, Box<dyn Error>> {
+        let mut req = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: rvrrpctl\r\nConnection: close\r\n",
+            method, path, self.host
+        );
+        if let Ok(cookies) = fs::read_to_string(&self.session_file) {
+            if !cookies.trim().is_empty() {
+                req += &format!("Cookie: {}\r\n", cookies.trim());
+            }
+        }
+        let body = body.unwrap_or("");
+        if !body.is_empty() {
+            req += "Content-Type: application/x-www-form-urlencoded\r\n";
+        }
+        req += &format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+
+        // send the request and read the response until the connection is closed
+        let mut buf = Vec::new();
+        if let Some(socket) = &self.socket {
+            let mut stream = UnixStream::connect(socket)?;
+            stream.write_all(req.as_bytes())?;
+            read_all(&mut stream, &mut buf)?;
+            return parse_response(&buf);
+        }
+        let stream = TcpStream::connect(&self.host)?;
+        if self.tls {
+            let mut connector = SslConnector::builder(SslMethod::tls())?;
+            if self.insecure {
+                connector.set_verify(SslVerifyMode::NONE);
+            }
+            let domain = match self.host.rfind(':') {
+                Some(i) => &self.host[..i],
+                None => &self.host[..],
+            };
+            let mut stream = match connector.build().connect(domain, stream) {
+                Ok(s) => s,
+                Err(e) => return err(format!("TLS error: {}", e)),
+            };
+            stream.write_all(req.as_bytes())?;
+            read_all(&mut stream, &mut buf)?;
+        } else {
+            let mut stream = stream;
+            stream.write_all(req.as_bytes())?;
+            read_all(&mut stream, &mut buf)?;
+        }
+        parse_response(&buf)
+    }
+    // login() method
+    /// authenticate and store the session cookies
+    fn login(&self, user: &str, passwd: &str) -> Result<(), Box<dyn Error>> {
+        let body = format!("user={} passwd={}", user, passwd);
+        let resp = self.request("POST", "/auth", Some(&body))?;
+        match resp.status {
+            200 => (),
+            401 => return err(format!("authentication failed")),
+            s => return err(status_error(s, &resp.body)),
+        }
+        let cookies: Vec<&str> = resp
+            .cookies
+            .iter()
+            .map(|c| c.split(';').next().unwrap_or("").trim())
+            .collect();
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&self.session_file)?;
+        writeln!(f, "{}", cookies.join("; "))?;
+        Ok(())
+    }
+    // logout() method
+    /// close the session and remove the session cookies
+    fn logout(&self) -> Result<(), Box<dyn Error>> {
+        if !self.session_file.exists() {
+            return Ok(());
+        }
+        let resp = self.request("DELETE", "/auth", None);
+        // the session cookies are removed even if the API cannot be reached
+        fs::remove_file(&self.session_file)?;
+        match resp?.status {
+            // an expired session is already closed
+            200..=299 | 401 => Ok(()),
+            s => err(status_error(s, &[])),
+        }
+    }
+    // get() method
+    /// read a Client API resource
+    fn get(&self, path: &str) -> Result<Value, Box<dyn Error>> {
+        let resp = self.request("GET", path, None)?;
+        match resp.status {
+            200 if resp.body.is_empty() => Ok(Value::Null),
+            200 => Ok(serde_json::from_slice(&resp.body)?),
+            s => err(status_error(s, &resp.body)),
+        }
+    }
+    // action() method
+    /// send an administrative action
+    fn action(&self, method: &str, path: &str) -> Result<(), Box<dyn Error>> {
+        let resp = self.request(method, path, None)?;
+        match resp.status {
+            200..=299 => Ok(()),
+            s => err(status_error(s, &resp.body)),
+        }
+    }
+}
+
+// read_all() function
+/// read a response until the connection is closed, a TLS connection
+/// closed without notification is not considered as an error
+fn read_all<R: Read>(r: &mut R, buf: &mut Vec<u8>) -> io::Result<()> {
+    match r.read_to_end(buf) {
+        Ok(_) => Ok(()),
+        Err(_) if !buf.is_empty() => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// parse_response() function
+/// parse the status, cookies and body of an HTTP response
+fn parse_response(buf: &[u8]) -> Result<Response, Box<dyn Error>> {
+    let end = match buf.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(i) => i,
+        None => return err(format!("malformed HTTP response")),
+    };
+    let head = String::from_utf8_lossy(&buf[..end]);
+    let mut lines = head.split("\r\n");
+    let status = match lines.next().and_then(|l| l.split(' ').nth(1)) {
+        Some(s) => s.parse::<u16>()?,
+        None => return err(format!("malformed HTTP status line")),
+    };
+    let mut cookies = Vec::new();
+    let mut chunked = false;
+    for line in lines {
+        let mut kv = line.splitn(2, ':');
+        let name = kv.next().unwrap_or("").trim().to_lowercase();
+        let value = kv.next().unwrap_or("").trim();
+        match &name[..] {
+            "set-cookie" => cookies.push(value.to_string()),
+            "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+            _ => (),
+        }
+    }
+    let mut body = buf[end + 4..].to_vec();
+    if chunked {
+        body = decode_chunked(&body)?;
+    }
+    Ok(Response {
+        status,
+        cookies,
+        body,
+    })
+}
+
+// decode_chunked() function
+/// decode a body sent with the chunked transfer encoding
+fn decode_chunked(mut buf: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut body = Vec::new();
+    loop {
+        let eol = match buf.windows(2).position(|w| w == b"\r\n") {
+            Some(i) => i,
+            None => return err(format!("malformed chunked body")),
+        };
+        let size = String::from_utf8_lossy(&buf[..eol]);
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16)?;
+        if size == 0 || buf.len() < eol + 2 + size {
+            return Ok(body);
+        }
+        body.extend_from_slice(&buf[eol + 2..eol + 2 + size]);
+        buf = &buf[(eol + 4 + size).min(buf.len())..];
+    }
+}
+
+// status_error() function
+/// error message of an unsuccessful response
+fn status_error(status: u16, body: &[u8]) -> String {
+    match status {
+        400 => match serde_json::from_slice::<Value>(body) {
+            Ok(v) => {
+                let mut msg = v["error"].as_str().unwrap_or("Bad request").to_string();
+                for d in v["details"].as_array().into_iter().flatten() {
+                    msg += &format!("\n  {}", d.as_str().unwrap_or(""));
+                }
+                msg
+            }
+            Err(_) => format!("bad request"),
+        },
+        401 => format!("not authenticated, please login first (rvrrpctl login <user>)"),
+        403 => format!("permission denied"),
+        404 => format!("no such virtual router or static route"),
+        501 => format!("not supported on this platform"),
+        s => format!("unexpected HTTP status {}", s),
+    }
+}
+
+// read_password() function
+/// read the password from the terminal, without echo
+fn read_password() -> io::Result<String> {
+    eprint!("Password: ");
+    io::stderr().flush()?;
+    let mut term: libc::termios = unsafe { std::mem::zeroed() };
+    let tty = unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut term) } == 0;
+    if tty {
+        let mut noecho = term;
+        noecho.c_lflag &= !libc::ECHO;
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &noecho) };
+    }
+    let mut passwd = String::new();
+    let r = io::stdin().lock().read_line(&mut passwd);
+    if tty {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &term) };
+        eprintln!();
+    }
+    r?;
+    Ok(passwd.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
+// cell() function
+/// text of a table cell
+fn cell(v: &Value) -> String {
+    match v {
+        Value::Null => format!("-"),
+        Value::String(s) => s.clone(),
+        Value::Array(a) => a.iter().map(cell).collect::<Vec<String>>().join(", "),
+        v => v.to_string(),
+    }
+}
+
+// print_table() function
+/// print rows of objects as a table with the given (header, key) columns
+fn print_table(columns: &[(&str, &str)], rows: &[Value]) {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|r| columns.iter().map(|(_, k)| cell(&r[*k])).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, (h, _))| cells.iter().map(|c| c[i].len()).fold(h.len(), usize::max))
+        .collect();
+    let line = |c: Vec<String>| {
+        let fields: Vec<String> = c
+            .iter()
+            .zip(&widths)
+            .map(|(s, w)| format!("{:<width$}", s, width = w))
+            .collect();
+        println!("{}", fields.join("  ").trim_end());
+    };
+    line(columns.iter().map(|(h, _)| h.to_string()).collect());
+    for c in cells {
+        line(c);
+    }
+}
+
+// print_detail() function
+/// print an object as indented 'key: value' lines
+fn print_detail(v: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match v {
+        Value::Object(m) => {
+            for (k, v) in m {
+                match v {
+                    Value::Object(_) => {
+                        println!("{}{}:", pad, k);
+                        print_detail(v, indent + 2);
+                    }
+                    Value::Array(a) if a.iter().any(|e| e.is_object()) => {
+                        println!("{}{}:", pad, k);
+                        for e in a {
+                            print_detail(e, indent + 2);
+                            println!();
+                        }
+                    }
+                    v => println!("{}{}: {}", pad, k, cell(v)),
+                }
+            }
+        }
+        v => println!("{}{}", pad, cell(v)),
+    }
+}
+
+// output() function
+/// print a response as JSON or with the given formatter
+fn output<F: Fn(&Value)>(client: &Client, v: &Value, human: F) -> Result<(), Box<dyn Error>> {
+    if client.json {
+        println!("{}", serde_json::to_string_pretty(v)?);
+    } else {
+        human(v);
+    }
+    Ok(())
+}
+
+// vrrp table columns
+const VRRP_COLUMNS: [(&str, &str); 6] = [
+    ("GROUP", "group"),
+    ("INTERFACE", "interface"),
+    ("VIRTUAL IP", "virtual_ip"),
+    ("PRIORITY", "priority"),
+    ("PREEMPT", "preempt"),
+    ("STATE", "state"),
+];
+
+// statistics table columns
+const STATS_COLUMNS: [(&str, &str); 11] = [
+    ("GROUP", "group"),
+    ("INTERFACE", "interface"),
+    ("MASTER", "master_transitions"),
+    ("REASON", "new_master_reason"),
+    ("RCVD", "rcvd_advertisements"),
+    ("PRI0 RCVD", "rcvd_pri_zero_packets"),
+    ("PRI0 SENT", "sent_pri_zero_packets"),
+    ("ADVINT ERR", "adv_interval_errors"),
+    ("TTL ERR", "ip_ttl_errors"),
+    ("ADDR ERR", "address_list_errors"),
+    ("LEN ERR", "packet_length_errors"),
+];
+
+// static routes table columns
+const ROUTES_COLUMNS: [(&str, &str); 5] = [
+    ("DESTINATION", "destination"),
+    ("MASK", "mask"),
+    ("NEXT HOP", "next_hop"),
+    ("METRIC", "metric"),
+    ("MTU", "mtu"),
+];
+
+// vrouter_path() function
+/// resource path of a virtual router
+fn vrouter_path(args: &[String]) -> Result<String, Box<dyn Error>> {
+    match (args.get(0), args.get(1)) {
+        (Some(group), Some(iface)) => Ok(format!("/run/vrrp/{}/{}", group.parse::<u8>()?, iface)),
+        _ => err(format!(
+            "please specify the virtual router's group and interface"
+        )),
+    }
+}
+
+// show() function
+fn show(client: &Client, args: &[String]) -> Result<(), Box<dyn Error>> {
+    match args.first().map(|s| &s[..]) {
+        Some("vrrp") => match &args[1..] {
+            [] => output(client, &client.get("/run/vrrp")?, |v| {
+                print_table(&VRRP_COLUMNS, v.as_array().unwrap_or(&Vec::new()))
+            }),
+            [group] => {
+                let path = format!("/run/vrrp/{}", group.parse::<u8>()?);
+                output(client, &client.get(&path)?, |v| {
+                    print_table(&VRRP_COLUMNS, v.as_array().unwrap_or(&Vec::new()))
+                })
+            }
+            rest => output(client, &client.get(&vrouter_path(rest)?)?, |v| {
+                print_detail(v, 0)
+            }),
+        },
+        Some("config") => {
+            // the secrets are only revealed to the administrators
+            let path = match args.get(1).map(|s| &s[..]) {
+                None => "/config/global",
+                Some("secrets") => "/config/secrets",
+                Some(_) => return err(format!("please show config [secrets]")),
+            };
+            output(client, &client.get(path)?, |v| print_detail(v, 0))
+        }
+        Some("routes") => output(client, &client.get("/run/protocols/static")?, |v| {
+            print_table(&ROUTES_COLUMNS, v.as_array().unwrap_or(&Vec::new()))
+        }),
+        Some("stats") => {
+            if args.len() > 1 {
+                let path = vrouter_path(&args[1..])? + "/stats";
+                return output(client, &client.get(&path)?, |v| print_detail(v, 0));
+            }
+            // statistics of all the virtual routers
+            let mut rows = Vec::new();
+            for vr in client.get("/run/vrrp")?.as_array().unwrap_or(&Vec::new()) {
+                let path = format!("/run/vrrp/{}/{}/stats", vr["group"], cell(&vr["interface"]));
+                let mut stats = client.get(&path)?;
+                stats["group"] = vr["group"].clone();
+                stats["interface"] = vr["interface"].clone();
+                rows.push(stats);
+            }
+            output(client, &Value::Array(rows), |v| {
+                print_table(&STATS_COLUMNS, v.as_array().unwrap_or(&Vec::new()))
+            })
+        }
+        _ => err(format!("please show vrrp, config, routes or stats")),
+    }
+}
+
+// print_usage() function
+fn print_usage(program: &str, opts: Options) {
+    let commands = format!(
+        "\
+    Commands:
+    login <user> [password]             authenticate and store the session
+    logout                              close and remove the stored session
+    revoke <user>                       close all the sessions of a user
+    show vrrp [group [interface]]       show the virtual routers
+    show config [secrets]               show the running configuration
+                                        (with its secrets)
+    show routes                         show the static routes
+    show stats [group interface]        show the virtual routers' statistics
+    resign <group> <interface>          leave the Master state
+    maintenance <group> <interface> [on|off]
+                                        pin (or release) a router in Backup state
+    takeover <group> <interface>        force the Master state
+    clear stats <group> <interface>     reset the statistics\
+    "
+    );
+    let usage = format!("Usage: {} [options] <command>\n\n{}", program, commands);
+    print!("{}", opts.usage(&usage));
+}
+
+// run() function
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let program = args[0].clone();
+    let mut opts = Options::new();
+
+    opts.optflag("h", "help", "display help information");
+    opts.optopt(
+        "H",
+        "host",
+        "Client API address (default to 127.0.0.1:7080)",
+        "HOST:PORT",
+    );
+    opts.optopt(
+        "U",
+        "socket",
+        "Client API local socket (no login required)",
+        "PATH",
+    );
+    opts.optflag("s", "tls", "connect using HTTPS");
+    opts.optflag("k", "insecure", "do not verify the server certificate");
+    opts.optopt(
+        "f",
+        "session-file",
+        "session cookies file:\n (default to ~/.rvrrpctl-session)",
+        "FILE",
+    );
+    opts.optflag("j", "json", "print the JSON responses");
+
+    let matches = match opts.parse(&args[1..]) {
+        Ok(m) => m,
+        Err(f) => return err(f.to_string()),
+    };
+
+    // help command-line option
+    if matches.opt_present("help") || matches.free.is_empty() {
+        print_usage(&program, opts);
+        std::process::exit(1);
+    }
+
+    let session_file = match matches.opt_str("session-file") {
+        Some(f) => PathBuf::from(f),
+        None => {
+            let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(DFLT_SESSION_FILE)
+        }
+    };
+    let client = Client {
+        host: matches
+            .opt_str("host")
+            .unwrap_or_else(|| DFLT_HOST.to_string()),
+        socket: matches.opt_str("socket"),
+        tls: matches.opt_present("tls"),
+        insecure: matches.opt_present("insecure"),
+        session_file,
+        json: matches.opt_present("json"),
+    };
+
+    let cmd = &matches.free;
+    match &cmd[0][..] {
+        "login" => {
+            let user = match cmd.get(1) {
+                Some(u) => u,
+                None => return err(format!("please specify the user name")),
+            };
+            let passwd = match cmd.get(2) {
+                Some(p) => p.clone(),
+                None => read_password()?,
+            };
+            client.login(user, &passwd)
+        }
+        "logout" => client.logout(),
+        "revoke" => match cmd.get(1) {
+            Some(user) => client.action("DELETE", &format!("/auth/sessions/{}", user)),
+            None => err(format!("please specify the user name")),
+        },
+        "show" => show(&client, &cmd[1..]),
+        "resign" => client.action("POST", &(vrouter_path(&cmd[1..])? + "/resign")),
+        "takeover" => client.action("POST", &(vrouter_path(&cmd[1..])? + "/takeover")),
+        "maintenance" => {
+            let path = vrouter_path(&cmd[1..])? + "/maintenance";
+            match cmd.get(3).map(|s| &s[..]) {
+                None | Some("on") => client.action("POST", &path),
+                Some("off") => client.action("DELETE", &path),
+                Some(m) => err(format!(
+                    "unknown maintenance mode {}, please use on or off",
+                    m
+                )),
+            }
+        }
+        "clear" => match cmd.get(1).map(|s| &s[..]) {
+            Some("stats") => client.action("DELETE", &(vrouter_path(&cmd[2..])? + "/stats")),
+            _ => err(format!("please clear stats")),
+        },
+        c => err(format!("unknown command {}", c)),
+    }
+}
+
+// main() function
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    match run(&args) {
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        Ok(_) => std::process::exit(0),
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! `rVRRPd` is aimed to be a fast, secure and multi-platform VRRPv2 implementation.
 extern crate rVRRPd;
-use rVRRPd::{listen_ip_pkts, Config};
+use rVRRPd::{check_config, listen_ip_pkts, Config};
 
 // getopts
 use getopts::Options;
@@ -75,6 +75,11 @@ fn parse_cli_opts(args: &[String]) -> Result<Config, Box<dyn Error>> {
         "configuration format: toml(default), json",
         "FORMAT",
     );
+    opts.optflag(
+        "",
+        "check-config",
+        "validate the configuration file, print its errors\nand warnings, then exit",
+    );
 
     let matches = match opts.parse(&args[1..]) {
         Ok(m) => m,
@@ -87,6 +92,21 @@ fn parse_cli_opts(args: &[String]) -> Result<Config, Box<dyn Error>> {
         std::process::exit(1);
     }
 
+    // check-config command-line option
+    if matches.opt_present("check-config") {
+        let cfg = Config::new(
+            None,
+            1,
+            matches.opt_str("conf"),
+            None,
+            matches.opt_str("cfg-format"),
+        );
+        match check_config(&cfg) {
+            true => std::process::exit(0),
+            false => std::process::exit(1),
+        }
+    }
+
     // mode command-line option
     let mode = matches.opt_str("mode");
     let mode = match mode {
@@ -75,3 +75,118 @@ pub fn one_complement_sum(data: &[u8], pos: Option<usize>) -> u16 {
 
     !sum as u16
 }
+
+// ipv4_pseudo_header() function
+/// build the IPv4 pseudo-header used by upper-layer protocols checksums
+/// (such as the VRRPv3 checksum, see RFC5798 5.2.8)
+pub fn ipv4_pseudo_header(src: &[u8; 4], dst: &[u8; 4], proto: u8, len: usize) -> Vec<u8> {
+    let mut hdr = Vec::with_capacity(12);
+    hdr.extend_from_slice(src);
+    hdr.extend_from_slice(dst);
+    // zero-padding and upper-layer protocol
+    hdr.push(0);
+    hdr.push(proto);
+    // upper-layer packet length
+    hdr.push((len >> 8) as u8);
+    hdr.push(len as u8);
+    hdr
+}
+
+// ipv6_pseudo_header() function
+/// build the IPv6 pseudo-header used by upper-layer protocols checksums
+/// (such as the VRRPv3 and ICMPv6 checksums, see RFC2460 8.1)
+pub fn ipv6_pseudo_header(src: &[u8; 16], dst: &[u8; 16], nexthdr: u8, len: usize) -> Vec<u8> {
+    let mut hdr = Vec::with_capacity(40);
+    hdr.extend_from_slice(src);
+    hdr.extend_from_slice(dst);
+    // upper-layer packet length (32 bits)
+    hdr.push((len >> 24) as u8);
+    hdr.push((len >> 16) as u8);
+    hdr.push((len >> 8) as u8);
+    hdr.push(len as u8);
+    // zero-padding and next header
+    hdr.push(0);
+    hdr.push(0);
+    hdr.push(0);
+    hdr.push(nexthdr);
+    hdr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // VRRPv3 advertisement (RFC5798 5.1) for group 1, priority 100, 1s interval
+    // and a single address, its checksum computed over the IPv4 pseudo-header
+    const VRRP_V3_ADVERT: [u8; 12] = [
+        0x31, 0x01, 0x64, 0x01, 0x00, 0x64, 0x08, 0xae, 192, 168, 0, 1,
+    ];
+
+    // same advertisement over IPv6, with a single global address
+    const VRRP_V3_ADVERT6: [u8; 24] = [
+        0x31, 0x01, 0x64, 0x01, 0x00, 0x64, 0x3e, 0xb7, 0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0x01,
+    ];
+
+    #[test]
+    fn ipv4_pseudo_header_layout() {
+        let hdr = ipv4_pseudo_header(&[192, 168, 0, 10], &[224, 0, 0, 18], 112, 12);
+        assert_eq!(hdr, vec![192, 168, 0, 10, 224, 0, 0, 18, 0, 112, 0, 12]);
+    }
+
+    #[test]
+    fn ipv6_pseudo_header_layout() {
+        let mut src = [0u8; 16];
+        src[0] = 0xfe;
+        src[1] = 0x80;
+        src[15] = 0x0a;
+        let hdr = ipv6_pseudo_header(&src, &[0xff; 16], 112, 0x0102_0304);
+        assert_eq!(hdr.len(), 40);
+        assert_eq!(&hdr[..16], &src[..]);
+        assert_eq!(&hdr[16..32], &[0xff; 16][..]);
+        assert_eq!(&hdr[32..], &[0x01, 0x02, 0x03, 0x04, 0, 0, 0, 112][..]);
+    }
+
+    #[test]
+    fn vrrp_v3_checksum_ipv4() {
+        let mut data = ipv4_pseudo_header(
+            &[192, 168, 0, 10],
+            &[224, 0, 0, 18],
+            112,
+            VRRP_V3_ADVERT.len(),
+        );
+        let pseudo_len = data.len();
+        data.extend_from_slice(&VRRP_V3_ADVERT);
+        // computed while skipping the carried checksum
+        assert_eq!(one_complement_sum(&data, Some(pseudo_len + 6)), 0x08ae);
+        // and verified as received
+        assert_eq!(rfc1071(&data), 0xffff);
+    }
+
+    #[test]
+    fn vrrp_v3_checksum_ipv6() {
+        let mut src = [0u8; 16];
+        src[0] = 0xfe;
+        src[1] = 0x80;
+        src[15] = 0x0a;
+        let mut dst = [0u8; 16];
+        dst[0] = 0xff;
+        dst[1] = 0x02;
+        dst[15] = 0x12;
+        let mut data = ipv6_pseudo_header(&src, &dst, 112, VRRP_V3_ADVERT6.len());
+        let pseudo_len = data.len();
+        data.extend_from_slice(&VRRP_V3_ADVERT6);
+        assert_eq!(one_complement_sum(&data, Some(pseudo_len + 6)), 0x3eb7);
+        assert_eq!(rfc1071(&data), 0xffff);
+    }
+
+    #[test]
+    fn vrrp_v3_checksum_mismatch() {
+        // a corrupted priority no longer verifies
+        let mut advert = VRRP_V3_ADVERT;
+        advert[2] = 0x65;
+        let mut data = ipv4_pseudo_header(&[192, 168, 0, 10], &[224, 0, 0, 18], 112, advert.len());
+        data.extend_from_slice(&advert);
+        assert_ne!(rfc1071(&data), 0xffff);
+    }
+}
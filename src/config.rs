@@ -3,17 +3,83 @@
 use super::*;
 
 // std
+use std::collections::HashMap;
 use std::net::IpAddr;
 
 // rand
 use rand::Rng;
 
+// virtual router
+use crate::vrouter::Vip;
+
 /// CfgType Enumerator
 pub enum CfgType {
     Toml, // TOML
     Json, // JSON
 }
 
+/// Severity Enumerator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,   // the daemon cannot run this configuration
+    Warning, // the configuration is likely not the intended one
+}
+
+/// Configuration Issue Structure
+///
+/// Error or warning reported by the configuration validation
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub vrouter: Option<usize>, // index of the virtual router (if any)
+    pub field: String,          // faulty directive
+    pub message: String,
+}
+
+// Configuration Issue Implementation
+impl ConfigIssue {
+    // error() method
+    fn error(vrouter: Option<usize>, field: &str, message: String) -> ConfigIssue {
+        ConfigIssue {
+            severity: Severity::Error,
+            vrouter,
+            field: field.to_string(),
+            message,
+        }
+    }
+    // warning() method
+    fn warning(vrouter: Option<usize>, field: &str, message: String) -> ConfigIssue {
+        ConfigIssue {
+            severity: Severity::Warning,
+            vrouter,
+            field: field.to_string(),
+            message,
+        }
+    }
+    // is_error() method
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+    // detail() method
+    /// the issue without its severity, e.g. "vrouter 0: priority: message"
+    pub fn detail(&self) -> String {
+        match self.vrouter {
+            Some(i) => format!("vrouter {}: {}: {}", i, self.field, self.message),
+            None => format!("{}: {}", self.field, self.message),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}(config): {}", severity, self.detail())
+    }
+}
+
 /// Main Configuration Structure
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CConfig {
@@ -25,12 +91,281 @@ pub struct CConfig {
     pub main_log: Option<String>,
     pub error_log: Option<String>,
     pub vrouter: Option<Vec<VRConfig>>,
+    pub track_script: Option<Vec<TrackScript>>,
+    pub sync_group: Option<Vec<SyncGroupConfig>>,
+    pub notify: Option<String>,
+    pub notify_timeout: Option<u32>,
     pub protocols: Option<Protocols>,
     pub client_api: Option<String>,
     pub api: Option<API>,
+    pub control_socket: Option<ControlSocket>,
+    pub snmp: Option<SNMP>,
+    pub logging: Option<Logging>,
 }
 
 impl CConfig {
+    // validate() method
+    /// validate the whole configuration and return every error and warning found,
+    /// the getters panic on the errors reported here
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues: Vec<ConfigIssue> = Vec::new();
+
+        // global directives
+        check(
+            &mut issues,
+            None,
+            "notify_timeout",
+            self.try_notify_timeout(),
+        );
+
+        // control socket
+        if let Some(cs) = &self.control_socket {
+            check(&mut issues, None, "control_socket.mode", cs.try_mode());
+            check(&mut issues, None, "control_socket.users", cs.try_users());
+        }
+
+        // logging
+        if let Some(l) = &self.logging {
+            check(&mut issues, None, "logging.backend", l.try_backend());
+            check(&mut issues, None, "logging.facility", l.try_facility());
+            check(&mut issues, None, "logging.levels", l.try_levels());
+        }
+
+        // client API sessions
+        if let Some(a) = &self.api {
+            check(
+                &mut issues,
+                None,
+                "api.session_lifetime",
+                a.try_session_lifetime(),
+            );
+            check(
+                &mut issues,
+                None,
+                "api.session_timeout",
+                a.try_session_timeout(),
+            );
+        }
+
+        // track scripts
+        let scripts = self.track_scripts();
+        for (i, s) in scripts.iter().enumerate() {
+            let field = format!("track_script[{}]", i);
+            check(
+                &mut issues,
+                None,
+                &(field.clone() + ".interval"),
+                s.try_interval(),
+            );
+            check(
+                &mut issues,
+                None,
+                &(field.clone() + ".timeout"),
+                s.try_timeout(),
+            );
+            check(
+                &mut issues,
+                None,
+                &(field.clone() + ".weight"),
+                s.try_weight(),
+            );
+            if scripts[..i].iter().any(|o| o.name == s.name) {
+                issues.push(ConfigIssue::error(
+                    None,
+                    &(field + ".name"),
+                    format!("Duplicate track script {}", s.name),
+                ));
+            }
+        }
+
+        // static routes
+        let routes = match &self.protocols {
+            Some(p) => p.r#static.clone().unwrap_or_default(),
+            None => Vec::new(),
+        };
+        for (i, st) in routes.iter().enumerate() {
+            let field = format!("protocols.static[{}]", i);
+            let route = check(
+                &mut issues,
+                None,
+                &(field.clone() + ".route"),
+                parse_static_addr(&st.route, "route"),
+            );
+            let mask = check(
+                &mut issues,
+                None,
+                &(field.clone() + ".mask"),
+                parse_static_addr(&st.mask, "mask"),
+            );
+            let nh = check(
+                &mut issues,
+                None,
+                &(field.clone() + ".nh"),
+                parse_static_addr(&st.nh, "next-hop"),
+            );
+            // the mask and next-hop must be of the route's address family
+            if let Some(route) = route {
+                for (name, addr) in &[("mask", mask), ("nh", nh)] {
+                    match addr {
+                        Some(a) if a.is_ipv6() != route.is_ipv6() => {
+                            issues.push(ConfigIssue::error(
+                                None,
+                                &(field.clone() + "." + name),
+                                format!("Address {} is not of the family of route {}", a, st.route),
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if let Some(mask) = mask {
+                let m = addr_bits(&mask);
+                // the host part of the mask must be contiguous
+                if !m & (!m).wrapping_add(1) != 0 {
+                    issues.push(ConfigIssue::error(
+                        None,
+                        &(field + ".mask"),
+                        format!("Non-contiguous mask {}", st.mask),
+                    ));
+                } else if let Some(route) = route.filter(|r| r.is_ipv6() == mask.is_ipv6()) {
+                    if addr_bits(&route) & !m != 0 {
+                        issues.push(ConfigIssue::error(
+                            None,
+                            &(field + ".route"),
+                            format!("Route {} has host bits set for mask {}", st.route, st.mask),
+                        ));
+                    } else if let Some(j) = routes[..i]
+                        .iter()
+                        .position(|o| o.is_route(&st.route, &st.mask))
+                    {
+                        issues.push(ConfigIssue::error(
+                            None,
+                            &(field + ".route"),
+                            format!(
+                                "Duplicate static route {}/{} (see protocols.static[{}])",
+                                st.route, st.mask, j
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // virtual routers
+        let vrs: &[VRConfig] = match &self.vrouter {
+            Some(v) => v,
+            None => &[],
+        };
+        if vrs.is_empty() {
+            issues.push(ConfigIssue::error(
+                None,
+                "vrouter",
+                format!("No virtual router configured"),
+            ));
+        }
+        for (i, vr) in vrs.iter().enumerate() {
+            let idx = Some(i);
+            check(&mut issues, idx, "group", vr.try_group());
+            check(&mut issues, idx, "priority", vr.try_priority());
+            check(&mut issues, idx, "auth_type", vr.try_auth_type());
+            check(
+                &mut issues,
+                idx,
+                "track_interface",
+                vr.try_track_interface(),
+            );
+            // the directives below depend on the virtual IPs' address family
+            let vips = check(&mut issues, idx, "vips", vr.try_vips());
+            if vips.is_some() {
+                if check(&mut issues, idx, "version", vr.try_version()).is_some() {
+                    check(&mut issues, idx, "timers", vr.try_timer_advert());
+                }
+                check(&mut issues, idx, "netdrv", vr.try_netdrv());
+                if check(&mut issues, idx, "unicast_peers", vr.try_unicast_peers()).is_some() {
+                    check(&mut issues, idx, "unicast_src", vr.try_unicast_src());
+                }
+            }
+            for name in vr.track_script() {
+                if !scripts.iter().any(|s| s.name == name) {
+                    issues.push(ConfigIssue::error(
+                        idx,
+                        "track_script",
+                        format!("Unknown track script {}", name),
+                    ));
+                }
+            }
+            if let Some(j) = vrs[..i]
+                .iter()
+                .position(|o| o.group == vr.group && o.interface == vr.interface)
+            {
+                issues.push(ConfigIssue::error(
+                    idx,
+                    "interface",
+                    format!(
+                        "Duplicate virtual router for group {} on interface {} (see vrouter {})",
+                        vr.group, vr.interface, j
+                    ),
+                ));
+            }
+            if let Some(vips) = vips {
+                check_vips_subnet(&mut issues, i, &vr.interface, &vips);
+            }
+        }
+
+        // sync groups
+        let sgs: &[SyncGroupConfig] = match &self.sync_group {
+            Some(v) => v,
+            None => &[],
+        };
+        for (k, sg) in sgs.iter().enumerate() {
+            let field = format!("sync_group[{}].vrouters", k);
+            if sg.vrouters.len() < 2 {
+                issues.push(ConfigIssue::error(
+                    None,
+                    &field,
+                    format!(
+                        "Please configure at least two virtual routers in sync group {}",
+                        sg.name
+                    ),
+                ));
+            }
+            for m in &sg.vrouters {
+                let i = match vrs
+                    .iter()
+                    .position(|vr| vr.group == m.group && vr.interface == m.interface)
+                {
+                    Some(i) => i,
+                    None => {
+                        issues.push(ConfigIssue::error(
+                            None,
+                            &field,
+                            format!(
+                                "Unknown virtual router (group {}, interface {}) in sync group {}",
+                                m.group, m.interface, sg.name
+                            ),
+                        ));
+                        continue;
+                    }
+                };
+                if let Some(other) = sgs[..k].iter().find(|o| {
+                    o.vrouters
+                        .iter()
+                        .any(|om| om.group == m.group && om.interface == m.interface)
+                }) {
+                    issues.push(ConfigIssue::error(
+                        Some(i),
+                        "sync_group",
+                        format!(
+                            "Virtual router belongs to sync groups {} and {}",
+                            other.name, sg.name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        issues
+    }
     // debug() getter
     pub fn debug(&self) -> u8 {
         match self.debug {
@@ -89,6 +424,72 @@ impl CConfig {
             None => RVRRPD_DFLT_ELOGFILE.to_string(),
         }
     }
+    // track_scripts() getter
+    pub fn track_scripts(&self) -> Vec<TrackScript> {
+        match &self.track_script {
+            Some(v) => v.clone(),
+            None => Vec::new(),
+        }
+    }
+    // track_script_weight() method
+    /// weight of the named track script
+    pub fn track_script_weight(&self, name: &str) -> u8 {
+        match self.track_scripts().iter().find(|s| s.name == name) {
+            Some(s) => s.weight(),
+            None => panic!("error(config): Unknown track script {}", name),
+        }
+    }
+    // sync_groups() getter
+    /// names of the sync groups
+    pub fn sync_groups(&self) -> Vec<String> {
+        match &self.sync_group {
+            Some(v) => v.iter().map(|g| g.name.clone()).collect(),
+            None => Vec::new(),
+        }
+    }
+    // sync_group_of() method
+    /// name of the sync group a virtual router belongs to
+    pub fn sync_group_of(&self, group: u8, interface: &str) -> Option<String> {
+        self.sync_group
+            .iter()
+            .flatten()
+            .find(|sg| {
+                sg.vrouters
+                    .iter()
+                    .any(|m| m.group == group && m.interface == interface)
+            })
+            .map(|sg| sg.name.clone())
+    }
+    // notify() getter
+    /// global notify hook, run upon every virtual router state transition
+    pub fn notify(&self) -> Option<String> {
+        self.notify.clone()
+    }
+    // notify_timeout() getter
+    /// time after which a running notify hook is killed (in seconds)
+    pub fn notify_timeout(&self) -> u32 {
+        unwrap_cfg(self.try_notify_timeout())
+    }
+    // try_notify_timeout() method
+    fn try_notify_timeout(&self) -> Result<u32, String> {
+        match self.notify_timeout {
+            Some(0) => Err(format!("Please configure a notify timeout higher than 0")),
+            Some(v) => Ok(v),
+            None => Ok(10),
+        }
+    }
+    // redacted() method
+    /// copy of the configuration without its secrets (API secret, users
+    /// password hashes and VRRP authentication secrets)
+    pub fn redacted(&self) -> CConfig {
+        let mut c = self.clone();
+        c.vrouter = self
+            .vrouter
+            .as_ref()
+            .map(|v| v.iter().map(|vr| vr.redacted()).collect());
+        c.api = self.api.as_ref().map(|a| a.redacted());
+        c
+    }
     // client_api() method
     pub fn client_api(&self) -> bool {
         match &self.client_api {
@@ -107,56 +508,140 @@ pub struct VRConfig {
     group: u8,
     interface: String,
     vip: Option<String>,
+    vips: Option<Vec<VipConfig>>,
     priority: Option<u8>,
     preemption: Option<bool>,
+    preempt_delay: Option<u32>,
+    startup_delay: Option<u32>,
     auth_type: Option<String>,
     auth_secret: Option<String>,
     timers: Option<Timers>,
     rfc3768: Option<bool>,
+    version: Option<u8>,
     netdrv: Option<String>,
     iftype: Option<String>,
     vifname: Option<String>,
     socket_filter: Option<bool>,
+    unicast_peers: Option<Vec<String>>,
+    unicast_src: Option<String>,
+    check_ttl: Option<bool>,
+    track_interface: Option<Vec<TrackInterface>>,
+    track_script: Option<Vec<String>>,
+    notify: Option<String>,
 }
 impl VRConfig {
     // group() getter
     pub fn group(&self) -> u8 {
+        unwrap_cfg(self.try_group())
+    }
+    // try_group() method
+    fn try_group(&self) -> Result<u8, String> {
         if self.group < 1 {
-            panic!("error(config): Please configure a group id between 1 and 255")
+            return Err(format!("Please configure a group id between 1 and 255"));
         }
-        self.group
+        Ok(self.group)
     }
     // interface() getter
     pub fn interface(&self) -> &String {
         &self.interface
     }
-    // vip() getter
-    pub fn vip(&self) -> [u8; 4] {
-        match &self.vip {
-            Some(ip) => match ip.parse::<IpAddr>().unwrap() {
-                IpAddr::V4(ip) => ip.octets(),
-                IpAddr::V6(_ipv6) => panic!("error(config): Only IPv4 addresses are supported"),
-            },
-            None => panic!("error(config): No virtual IP specified"),
+    // vips() getter
+    /// virtual IP addresses, from either the 'vip' or the 'vips' directive
+    pub fn vips(&self) -> Vec<Vip> {
+        unwrap_cfg(self.try_vips())
+    }
+    // try_vips() method
+    fn try_vips(&self) -> Result<Vec<Vip>, String> {
+        let vips = match (&self.vip, &self.vips) {
+            (Some(_), Some(_)) => {
+                return Err(format!("Please configure either 'vip' or 'vips', not both"))
+            }
+            (Some(ip), None) => vec![parse_vip(ip, None)?],
+            (None, Some(v)) if !v.is_empty() => v
+                .iter()
+                .map(|c| match c {
+                    VipConfig::Address(ip) => parse_vip(ip, None),
+                    VipConfig::Labeled { ip, label } => parse_vip(ip, label.clone()),
+                })
+                .collect::<Result<Vec<Vip>, String>>()?,
+            _ => return Err(format!("No virtual IP specified")),
+        };
+        // all the virtual addresses must be of the same address family
+        if vips
+            .iter()
+            .any(|v| v.ip().is_ipv6() != vips[0].ip().is_ipv6())
+        {
+            return Err(format!(
+                "Please configure virtual IPs of a single address family"
+            ));
         }
+        Ok(vips)
+    }
+    // is_ipv6() method
+    pub fn is_ipv6(&self) -> bool {
+        unwrap_cfg(self.try_is_ipv6())
+    }
+    // try_is_ipv6() method
+    fn try_is_ipv6(&self) -> Result<bool, String> {
+        Ok(self.try_vips()?[0].ip().is_ipv6())
     }
     // timer_advert() getter
-    pub fn timer_advert(&self) -> u8 {
-        match &self.timers {
-            Some(t) => t.advert,
-            None => 1,
+    /// advertisement interval in milliseconds, either from 'advert' (seconds)
+    /// or from 'advert_ms' (milliseconds)
+    pub fn timer_advert(&self) -> u32 {
+        unwrap_cfg(self.try_timer_advert())
+    }
+    // try_timer_advert() method
+    fn try_timer_advert(&self) -> Result<u32, String> {
+        let ms = match &self.timers {
+            Some(t) => match (t.advert, t.advert_ms) {
+                (Some(_), Some(_)) => {
+                    return Err(format!(
+                        "Please configure either 'advert' or 'advert_ms' timers, not both"
+                    ))
+                }
+                (Some(s), None) => s as u32 * 1000,
+                (None, Some(ms)) => ms,
+                (None, None) => 1000,
+            },
+            None => 1000,
+        };
+        if ms == 0 {
+            return Err(format!("The advertisement interval cannot be zero"));
+        }
+        match self.try_version()? {
+            // VRRPv3 carries the advertisement interval in centiseconds (12 bits)
+            3 => {
+                if ms % 10 != 0 || ms > VRRP_V3_MAX_ADVERINT as u32 * 10 {
+                    return Err(format!(
+                        "Please configure a VRRPv3 advertisement interval between 10ms and {}ms, in steps of 10ms",
+                        VRRP_V3_MAX_ADVERINT as u32 * 10
+                    ));
+                }
+            }
+            // VRRPv2 carries the advertisement interval in seconds (8 bits)
+            _ => {
+                if ms % 1000 != 0 || ms > 255 * 1000 {
+                    return Err(format!(
+                        "VRRPv2 advertisement intervals must be whole seconds between 1 and 255s, sub-second intervals require VRRP version 3"
+                    ));
+                }
+            }
         }
+        Ok(ms)
     }
     // priority() getter
     pub fn priority(&self) -> u8 {
+        unwrap_cfg(self.try_priority())
+    }
+    // try_priority() method
+    fn try_priority(&self) -> Result<u8, String> {
         match self.priority {
-            Some(v) => {
-                if v < 1 || v > 254 {
-                    panic!("error(config): Please configure a priority between 1 and 254");
-                }
-                v
+            Some(v) if v < 1 || v > 254 => {
+                Err(format!("Please configure a priority between 1 and 254"))
             }
-            None => VRRP_V2_DEFAULT_PRIORITY,
+            Some(v) => Ok(v),
+            None => Ok(VRRP_V2_DEFAULT_PRIORITY),
         }
     }
     // preemption() getter
@@ -166,16 +651,30 @@ impl VRConfig {
             None => false,
         }
     }
+    // preempt_delay() getter
+    /// time a Backup router waits before preempting a lower priority master (in seconds)
+    pub fn preempt_delay(&self) -> u32 {
+        self.preempt_delay.unwrap_or(0)
+    }
+    // startup_delay() getter
+    /// time the virtual router waits before starting up (in seconds)
+    pub fn startup_delay(&self) -> u32 {
+        self.startup_delay.unwrap_or(0)
+    }
     // auth_type() method
     pub fn auth_type(&self) -> u8 {
+        unwrap_cfg(self.try_auth_type())
+    }
+    // try_auth_type() method
+    fn try_auth_type(&self) -> Result<u8, String> {
         match &self.auth_type {
             Some(s) => match &s[..] {
-                "rfc2338-simple" => AUTH_TYPE_SIMPLE,
-                "p0-t8-sha256" => AUTH_TYPE_P0,
-                "p1-b8-shake256" => AUTH_TYPE_P1,
-                _ => panic!("error(config): authentication type {} is not supported", s),
+                "rfc2338-simple" => Ok(AUTH_TYPE_SIMPLE),
+                "p0-t8-sha256" => Ok(AUTH_TYPE_P0),
+                "p1-b8-shake256" => Ok(AUTH_TYPE_P1),
+                _ => Err(format!("authentication type {} is not supported", s)),
             },
-            None => 0,
+            None => Ok(0),
         }
     }
     // auth_secret() method
@@ -196,6 +695,15 @@ impl VRConfig {
             None => Option::None,
         }
     }
+    // redacted() method
+    /// copy of the virtual router configuration without its authentication secret
+    pub fn redacted(&self) -> VRConfig {
+        let mut c = self.clone();
+        if c.auth_secret.is_some() {
+            c.auth_secret = Some(API_REDACTED_SECRET.to_string());
+        }
+        c
+    }
     // rfc3768() getter
     pub fn rfc3768(&self) -> bool {
         // if auth_type is 'p0-t8-sha256', or 'p1-b8-shake256',
@@ -218,26 +726,56 @@ impl VRConfig {
             None => true,
         }
     }
+    // version() getter
+    pub fn version(&self) -> u8 {
+        unwrap_cfg(self.try_version())
+    }
+    // try_version() method
+    fn try_version(&self) -> Result<u8, String> {
+        let ipv6 = self.try_is_ipv6()?;
+        match self.version {
+            Some(v) => match v {
+                // IPv6 virtual routers are only supported by VRRPv3 (RFC5798)
+                2 if ipv6 => Err(format!("IPv6 virtual routers require VRRP version 3")),
+                2 | 3 => Ok(v),
+                _ => Err(format!("VRRP version {} is not supported", v)),
+            },
+            None if ipv6 => Ok(3),
+            None => Ok(VRRP_DEFAULT_VERSION),
+        }
+    }
     // netdrv() method
     pub fn netdrv(&self) -> NetDrivers {
+        unwrap_cfg(self.try_netdrv())
+    }
+    // try_netdrv() method
+    fn try_netdrv(&self) -> Result<NetDrivers, String> {
         // if os is Linux
         if cfg!(target_os = "linux") {
             // if macvlan is configured, return libnl
             match self.iftype() {
-                IfTypes::macvlan => return NetDrivers::libnl,
+                IfTypes::macvlan => return Ok(NetDrivers::libnl),
                 _ => {}
             }
+            // IPv6 addresses are only supported by the libnl driver
+            if self.try_is_ipv6()? {
+                return Ok(NetDrivers::libnl);
+            }
             match &self.netdrv {
                 Some(s) => match &s[..] {
-                    "ioctl" => NetDrivers::ioctl,
-                    _ => NetDrivers::libnl,
+                    // ioctls replace the interface's address, only one VIP can be set
+                    "ioctl" if self.try_vips()?.len() > 1 => Err(format!(
+                        "Multiple virtual IPs require the libnl network driver"
+                    )),
+                    "ioctl" => Ok(NetDrivers::ioctl),
+                    _ => Ok(NetDrivers::libnl),
                 },
-                None => NetDrivers::libnl,
+                None => Ok(NetDrivers::libnl),
             }
         }
         // unspecified OSes
         else {
-            NetDrivers::ioctl
+            Ok(NetDrivers::ioctl)
         }
     }
     // iftype() method
@@ -269,16 +807,388 @@ impl VRConfig {
             None => true,
         }
     }
+    // unicast_peers() getter
+    /// peers the advertisements are sent to by unicast, instead of multicast
+    pub fn unicast_peers(&self) -> Vec<[u8; 4]> {
+        unwrap_cfg(self.try_unicast_peers())
+    }
+    // try_unicast_peers() method
+    fn try_unicast_peers(&self) -> Result<Vec<[u8; 4]>, String> {
+        match &self.unicast_peers {
+            Some(v) => {
+                if cfg!(not(target_os = "linux")) && !v.is_empty() {
+                    return Err(format!("Unicast peers are only supported on Linux"));
+                }
+                if self.try_is_ipv6()? && !v.is_empty() {
+                    return Err(format!(
+                        "Unicast peers are only supported by IPv4 virtual routers"
+                    ));
+                }
+                v.iter()
+                    .map(|p| match p.parse::<std::net::Ipv4Addr>() {
+                        Ok(ip) => Ok(ip.octets()),
+                        Err(_) => Err(format!("Cannot parse unicast peer {}", p)),
+                    })
+                    .collect()
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+    // unicast_src() getter
+    /// source address of the unicast advertisements
+    pub fn unicast_src(&self) -> Option<[u8; 4]> {
+        unwrap_cfg(self.try_unicast_src())
+    }
+    // try_unicast_src() method
+    fn try_unicast_src(&self) -> Result<Option<[u8; 4]>, String> {
+        match &self.unicast_src {
+            Some(s) => {
+                if self.try_unicast_peers()?.is_empty() {
+                    return Err(format!(
+                        "Please configure unicast_peers along with unicast_src"
+                    ));
+                }
+                match s.parse::<std::net::Ipv4Addr>() {
+                    Ok(ip) => Ok(Some(ip.octets())),
+                    Err(_) => Err(format!("Cannot parse unicast source {}", s)),
+                }
+            }
+            None => Ok(None),
+        }
+    }
+    // check_ttl() getter
+    /// verify the received advertisements' TTL is 255
+    pub fn check_ttl(&self) -> bool {
+        match self.check_ttl {
+            Some(b) => b,
+            None => true,
+        }
+    }
+    // track_interface() getter
+    /// tracked interfaces' name and priority decrement
+    pub fn track_interface(&self) -> Vec<(String, u8)> {
+        unwrap_cfg(self.try_track_interface())
+    }
+    // try_track_interface() method
+    fn try_track_interface(&self) -> Result<Vec<(String, u8)>, String> {
+        match &self.track_interface {
+            Some(v) => {
+                if cfg!(not(target_os = "linux")) && !v.is_empty() {
+                    return Err(format!("Interface tracking is only supported on Linux"));
+                }
+                v.iter()
+                    .map(|t| {
+                        if t.decrement < 1 {
+                            return Err(format!(
+                                "Please configure a priority decrement higher than 0 for tracked interface {}",
+                                t.name
+                            ));
+                        }
+                        Ok((t.name.clone(), t.decrement))
+                    })
+                    .collect()
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+    // notify() getter
+    /// virtual router's notify hook, run upon its state transitions
+    pub fn notify(&self) -> Option<String> {
+        self.notify.clone()
+    }
+    // track_script() getter
+    /// names of the track scripts followed by the virtual router
+    pub fn track_script(&self) -> Vec<String> {
+        match &self.track_script {
+            Some(v) => v.clone(),
+            None => Vec::new(),
+        }
+    }
+    // updated() method
+    /// return a copy of the configuration with the given parameters changed
+    pub fn updated(&self, u: &VRConfigUpdate) -> VRConfig {
+        let mut c = self.clone();
+        if u.priority.is_some() {
+            c.priority = u.priority;
+        }
+        if u.preemption.is_some() {
+            c.preemption = u.preemption;
+        }
+        if u.preempt_delay.is_some() {
+            c.preempt_delay = u.preempt_delay;
+        }
+        if u.startup_delay.is_some() {
+            c.startup_delay = u.startup_delay;
+        }
+        if u.timers.is_some() {
+            c.timers = u.timers.clone();
+        }
+        if u.auth_type.is_some() {
+            c.auth_type = u.auth_type.clone();
+        }
+        if u.auth_secret.is_some() {
+            c.auth_secret = u.auth_secret.clone();
+        }
+        c
+    }
+}
+
+/// Virtual-Routers Configuration Update Structure
+/// (parameters changeable at runtime through the client API)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VRConfigUpdate {
+    priority: Option<u8>,
+    preemption: Option<bool>,
+    preempt_delay: Option<u32>,
+    startup_delay: Option<u32>,
+    timers: Option<Timers>,
+    auth_type: Option<String>,
+    auth_secret: Option<String>,
+}
+
+/// Track Script Configuration Structure
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TrackScript {
+    name: String,
+    script: String,
+    interval: Option<u32>,
+    timeout: Option<u32>,
+    rise: Option<u32>,
+    fall: Option<u32>,
+    weight: u8,
+}
+impl TrackScript {
+    // name() getter
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    // script() getter
+    pub fn script(&self) -> &String {
+        &self.script
+    }
+    // interval() getter
+    /// interval between two runs of the script (in seconds)
+    pub fn interval(&self) -> u32 {
+        unwrap_cfg(self.try_interval())
+    }
+    // try_interval() method
+    fn try_interval(&self) -> Result<u32, String> {
+        match self.interval {
+            Some(0) => Err(format!(
+                "Please configure an interval higher than 0 for track script {}",
+                self.name
+            )),
+            Some(v) => Ok(v),
+            None => Ok(2),
+        }
+    }
+    // timeout() getter
+    /// time after which a running script is killed and considered failed (in seconds)
+    pub fn timeout(&self) -> u32 {
+        unwrap_cfg(self.try_timeout())
+    }
+    // try_timeout() method
+    fn try_timeout(&self) -> Result<u32, String> {
+        match self.timeout {
+            Some(0) => Err(format!(
+                "Please configure a timeout higher than 0 for track script {}",
+                self.name
+            )),
+            Some(v) => Ok(v),
+            None => self.try_interval(),
+        }
+    }
+    // rise() getter
+    /// number of consecutive successes before the script is considered healthy
+    pub fn rise(&self) -> u32 {
+        match self.rise {
+            Some(v) if v > 0 => v,
+            _ => 1,
+        }
+    }
+    // fall() getter
+    /// number of consecutive failures before the script is considered failed
+    pub fn fall(&self) -> u32 {
+        match self.fall {
+            Some(v) if v > 0 => v,
+            _ => 1,
+        }
+    }
+    // weight() getter
+    /// priority decrement of the tracking virtual routers when the script is failed
+    pub fn weight(&self) -> u8 {
+        unwrap_cfg(self.try_weight())
+    }
+    // try_weight() method
+    fn try_weight(&self) -> Result<u8, String> {
+        if self.weight < 1 {
+            return Err(format!(
+                "Please configure a weight higher than 0 for track script {}",
+                self.name
+            ));
+        }
+        Ok(self.weight)
+    }
+}
+
+/// Sync Group Configuration Structure
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SyncGroupConfig {
+    name: String,
+    vrouters: Vec<SyncMember>,
+}
+
+/// SyncMember Option Type
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct SyncMember {
+    group: u8,
+    interface: String,
+}
+
+/// TrackInterface Option Type
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct TrackInterface {
+    name: String,
+    decrement: u8,
+}
+
+/// VipConfig Option Type
+/// either an address string ("10.0.0.1/24") or a table with an optional label
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+enum VipConfig {
+    Address(String),
+    Labeled { ip: String, label: Option<String> },
+}
+
+// parse_vip() function
+/// parse a virtual IP address string with an optional prefix length
+fn parse_vip(s: &str, label: Option<String>) -> Result<Vip, String> {
+    let mut parts = s.splitn(2, '/');
+    let ip = match parts.next().unwrap().parse::<IpAddr>() {
+        Ok(ip) => ip,
+        Err(_) => return Err(format!("Invalid virtual IP {}", s)),
+    };
+    let prefixlen = match parts.next() {
+        Some(p) => match p.parse::<u8>() {
+            Ok(p) if (ip.is_ipv4() && p <= 32) || (ip.is_ipv6() && p <= 128) => Some(p),
+            _ => return Err(format!("Invalid prefix length for virtual IP {}", s)),
+        },
+        None => None,
+    };
+    Ok(Vip::new(ip, prefixlen, label))
+}
+
+// parse_static_addr() function
+/// parse a static route's IPv4 or IPv6 address
+fn parse_static_addr(s: &str, what: &str) -> Result<IpAddr, String> {
+    match s.parse::<IpAddr>() {
+        Ok(ip) => Ok(ip),
+        Err(_) => Err(format!("Invalid {} {}", what, s)),
+    }
+}
+
+// addr_bits() function
+/// bits of an address, the IPv4 addresses being aligned on the most significant bits
+fn addr_bits(ip: &IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => (u32::from(*ip) as u128) << 96,
+        IpAddr::V6(ip) => u128::from(*ip),
+    }
+}
+
+// check() function
+/// record the error of a directive, return its value if valid
+fn check<T>(
+    issues: &mut Vec<ConfigIssue>,
+    vrouter: Option<usize>,
+    field: &str,
+    r: Result<T, String>,
+) -> Option<T> {
+    match r {
+        Ok(v) => Some(v),
+        Err(e) => {
+            issues.push(ConfigIssue::error(vrouter, field, e));
+            None
+        }
+    }
+}
+
+// check_vips_subnet() function
+/// warn about IPv4 virtual addresses outside the subnets of the interface
+fn check_vips_subnet(issues: &mut Vec<ConfigIssue>, i: usize, ifname: &String, vips: &[Vip]) {
+    if vips[0].ip().is_ipv6() {
+        return;
+    }
+    let mut v4addrs: Vec<[u8; 4]> = Vec::new();
+    let mut v4masks: Vec<[u8; 4]> = Vec::new();
+    let _r = os::multi::libc::get_addrlist(ifname, &mut v4addrs, &mut v4masks);
+    if v4masks.is_empty() {
+        issues.push(ConfigIssue::warning(
+            Some(i),
+            "interface",
+            format!("Interface {} not found or without IPv4 address", ifname),
+        ));
+        return;
+    }
+    for v in vips {
+        let ip = match v.ip() {
+            IpAddr::V4(ip) => u32::from(ip),
+            IpAddr::V6(_) => continue,
+        };
+        let inside = v4addrs.iter().zip(v4masks.iter()).any(|(a, m)| {
+            let m = u32::from_be_bytes(*m);
+            u32::from_be_bytes(*a) & m == ip & m
+        });
+        if !inside {
+            issues.push(ConfigIssue::warning(
+                Some(i),
+                "vips",
+                format!(
+                    "Virtual IP {} is outside the subnets of interface {}",
+                    v.ip(),
+                    ifname
+                ),
+            ));
+        }
+    }
+}
+
+// unwrap_cfg() function
+/// unwrap a directive checked by CConfig::validate()
+fn unwrap_cfg<T>(r: Result<T, String>) -> T {
+    match r {
+        Ok(v) => v,
+        Err(e) => panic!("error(config): {}", e),
+    }
+}
+
+// report_issues() function
+/// print the configuration errors and warnings, return true if there is no error
+pub fn report_issues(issues: &[ConfigIssue]) -> bool {
+    for i in issues {
+        if i.is_error() {
+            eprintln!("{}", i);
+        } else {
+            println!("{}", i);
+        }
+    }
+    !issues.iter().any(|i| i.is_error())
 }
 
 /// Timers Option Type
 #[derive(Debug, Deserialize, Serialize, Clone)]
 struct Timers {
-    advert: u8,
+    advert: Option<u8>,
+    advert_ms: Option<u32>,
 }
 impl Default for Timers {
     fn default() -> Self {
-        Timers { advert: 1 }
+        Timers {
+            advert: Some(1),
+            advert_ms: None,
+        }
     }
 }
 
@@ -301,26 +1211,17 @@ pub struct Static {
 // Static Option Implementation
 impl Static {
     // route() getter
-    // convert IPv4 String to array of four 8-bits unsigned integers
-    pub fn route(&self) -> [u8; 4] {
-        match self.route.parse::<IpAddr>().unwrap() {
-            IpAddr::V4(ip) => ip.octets(),
-            IpAddr::V6(_ipv6) => panic!("error(config-static): Only IPv4 routes are supported"),
-        }
+    // convert the IPv4 or IPv6 String to an IP address
+    pub fn route(&self) -> IpAddr {
+        unwrap_cfg(parse_static_addr(&self.route, "route"))
     }
     // mask() getter
-    pub fn mask(&self) -> [u8; 4] {
-        match self.mask.parse::<IpAddr>().unwrap() {
-            IpAddr::V4(ip) => ip.octets(),
-            IpAddr::V6(_ipv6) => panic!("error(config-static): Only IPv4 masks are supported"),
-        }
+    pub fn mask(&self) -> IpAddr {
+        unwrap_cfg(parse_static_addr(&self.mask, "mask"))
     }
     // nh() getter
-    pub fn nh(&self) -> [u8; 4] {
-        match self.nh.parse::<IpAddr>().unwrap() {
-            IpAddr::V4(ip) => ip.octets(),
-            IpAddr::V6(_ipv6) => panic!("error(config-static): Only IPv4 next-hops are supported"),
-        }
+    pub fn nh(&self) -> IpAddr {
+        unwrap_cfg(parse_static_addr(&self.nh, "next-hop"))
     }
     // metric() getter
     pub fn metric(&self) -> i16 {
@@ -336,44 +1237,241 @@ impl Static {
             None => 0,
         }
     }
+    // is_route() method
+    /// check if the static route is the one to the given destination and mask
+    pub fn is_route(&self, route: &str, mask: &str) -> bool {
+        match (
+            parse_static_addr(&self.route, "route"),
+            parse_static_addr(&self.mask, "mask"),
+            parse_static_addr(route, "route"),
+            parse_static_addr(mask, "mask"),
+        ) {
+            (Ok(r1), Ok(m1), Ok(r2), Ok(m2)) => r1 == r2 && m1 == m2,
+            _ => false,
+        }
+    }
 }
 
 // decode_config() function
 /// read and decode configuration file
 pub fn decode_config(filename: String, cfgtype: CfgType) -> CConfig {
-    let file: std::string::String = match std::fs::read_to_string(filename) {
-        Ok(s) => s,
+    match read_config(filename, cfgtype) {
+        Ok(c) => c,
         Err(e) => {
-            eprintln!(
-                "error(config): Cannot read rVRRPd configuration file: {}",
-                e
-            );
+            eprintln!("error(config): {}", e);
             std::process::exit(1);
         }
+    }
+}
+
+// read_config() function
+/// read and parse the configuration file, without exiting on errors
+pub fn read_config(filename: String, cfgtype: CfgType) -> Result<CConfig, String> {
+    let file: std::string::String = match std::fs::read_to_string(filename) {
+        Ok(s) => s,
+        Err(e) => return Err(format!("Cannot read rVRRPd configuration file: {}", e)),
     };
     match cfgtype {
         // TOML
-        CfgType::Toml => {
-            let config: CConfig = match toml::from_str(&file) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("error(config): Cannot parse TOML configuration file: {}", e);
-                    std::process::exit(1);
-                }
-            };
-            return config;
-        }
+        CfgType::Toml => match toml::from_str(&file) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(format!("Cannot parse TOML configuration file: {}", e)),
+        },
         // JSON
-        CfgType::Json => {
-            let config: CConfig = match serde_json::from_str(&file) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("error(config): Cannot parse JSON configuration file: {}", e);
-                    std::process::exit(1);
+        CfgType::Json => match serde_json::from_str(&file) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(format!("Cannot parse JSON configuration file: {}", e)),
+        },
+    }
+}
+
+/// Logging structure
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Logging {
+    backend: Option<String>,
+    facility: Option<String>,
+    syslog_socket: Option<String>,
+    json_file: Option<String>,
+    levels: Option<HashMap<String, u8>>,
+}
+
+// Logging structure implementation
+impl Logging {
+    // backend() getter
+    /// logging backend (stdout, syslog, journald or json)
+    pub fn backend(&self) -> String {
+        unwrap_cfg(self.try_backend())
+    }
+    // try_backend() method
+    fn try_backend(&self) -> Result<String, String> {
+        match &self.backend {
+            Some(s) => match &s[..] {
+                "stdout" | "syslog" | "journald" | "json" => Ok(s.clone()),
+                _ => Err(format!(
+                    "Unknown logging backend {}, please use stdout, syslog, journald or json",
+                    s
+                )),
+            },
+            None => Ok(format!("stdout")),
+        }
+    }
+    // facility() getter
+    /// syslog facility code
+    pub fn facility(&self) -> u8 {
+        unwrap_cfg(self.try_facility())
+    }
+    // try_facility() method
+    fn try_facility(&self) -> Result<u8, String> {
+        let name = match &self.facility {
+            Some(s) => &s[..],
+            None => LOG_DFLT_FACILITY,
+        };
+        match name {
+            "kern" => Ok(0),
+            "user" => Ok(1),
+            "mail" => Ok(2),
+            "daemon" => Ok(3),
+            "auth" => Ok(4),
+            "syslog" => Ok(5),
+            "lpr" => Ok(6),
+            "news" => Ok(7),
+            "uucp" => Ok(8),
+            "cron" => Ok(9),
+            "authpriv" => Ok(10),
+            "ftp" => Ok(11),
+            "local0" => Ok(16),
+            "local1" => Ok(17),
+            "local2" => Ok(18),
+            "local3" => Ok(19),
+            "local4" => Ok(20),
+            "local5" => Ok(21),
+            "local6" => Ok(22),
+            "local7" => Ok(23),
+            _ => Err(format!("Unknown syslog facility {}", name)),
+        }
+    }
+    // syslog_socket() getter
+    /// local Unix socket of the syslog daemon
+    pub fn syslog_socket(&self) -> String {
+        match &self.syslog_socket {
+            Some(s) => s.clone(),
+            None => LOG_DFLT_SYSLOG_SOCKET.to_string(),
+        }
+    }
+    // json_file() getter
+    /// file the JSON records are appended to
+    pub fn json_file(&self) -> String {
+        match &self.json_file {
+            Some(s) => s.clone(),
+            None => LOG_DFLT_JSON_FILE.to_string(),
+        }
+    }
+    // levels() getter
+    /// debug levels of the sources, overriding the global debug level
+    pub fn levels(&self) -> Vec<(String, u8)> {
+        unwrap_cfg(self.try_levels())
+    }
+    // try_levels() method
+    fn try_levels(&self) -> Result<Vec<(String, u8)>, String> {
+        let mut levels = Vec::new();
+        for (src, level) in self.levels.iter().flatten() {
+            if !DEBUG_SOURCES.contains(&&src[..]) {
+                return Err(format!(
+                    "Unknown debug source {}, please use one of {}",
+                    src,
+                    DEBUG_SOURCES.join(", ")
+                ));
+            }
+            levels.push((src.clone(), *level));
+        }
+        Ok(levels)
+    }
+}
+
+/// SNMP structure
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SNMP {
+    agentx_socket: Option<String>,
+}
+
+// SNMP structure implementation
+impl SNMP {
+    // agentx_socket() method
+    /// Unix socket of the AgentX master agent
+    pub fn agentx_socket(&self) -> String {
+        match &self.agentx_socket {
+            Some(s) => s.clone(),
+            None => RVRRPD_CFG_DFLT_AGENTX_SOCKET.to_string(),
+        }
+    }
+}
+
+/// Control Socket structure
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ControlSocket {
+    path: Option<String>,
+    mode: Option<String>,
+    group: Option<String>,
+    users: Option<Vec<String>>,
+}
+
+// Control Socket structure implementation
+impl ControlSocket {
+    // path() getter
+    /// path of the client API Unix socket
+    pub fn path(&self) -> String {
+        match &self.path {
+            Some(s) => s.clone(),
+            None => RVRRPD_CFG_DFLT_CONTROL_SOCKET.to_string(),
+        }
+    }
+    // mode() getter
+    /// file permissions of the socket
+    pub fn mode(&self) -> u32 {
+        unwrap_cfg(self.try_mode())
+    }
+    // try_mode() method
+    fn try_mode(&self) -> Result<u32, String> {
+        match &self.mode {
+            Some(s) => match u32::from_str_radix(s, 8) {
+                Ok(m) if m <= 0o777 => Ok(m),
+                _ => Err(format!(
+                    "Invalid socket mode {}, please use an octal mode such as 0660",
+                    s
+                )),
+            },
+            None => Ok(0o600),
+        }
+    }
+    // group() getter
+    /// group owning the socket
+    pub fn group(&self) -> Option<String> {
+        self.group.clone()
+    }
+    // users() getter
+    /// access levels of the local users (other than root)
+    pub fn users(&self) -> Vec<(String, u8)> {
+        unwrap_cfg(self.try_users())
+    }
+    // try_users() method
+    fn try_users(&self) -> Result<Vec<(String, u8)>, String> {
+        let mut users = Vec::new();
+        for u in self.users.iter().flatten() {
+            let mut f = u.splitn(2, ':');
+            let name = f.next().unwrap_or("");
+            match f.next().map(|l| l.parse::<u8>()) {
+                Some(Ok(level)) if !name.is_empty() && level <= API_ACCESS_LEVEL_ADMIN => {
+                    users.push((name.to_string(), level))
                 }
-            };
-            return config;
+                _ => {
+                    return Err(format!(
+                        "Invalid user {}, please use <user-name>:<access-level>",
+                        u
+                    ))
+                }
+            }
         }
+        Ok(users)
     }
 }
 
@@ -386,6 +1484,9 @@ pub struct API {
     tls: Option<bool>,
     tls_key: Option<String>,
     tls_cert: Option<String>,
+    metrics_host: Option<String>,
+    session_lifetime: Option<u64>,
+    session_timeout: Option<u64>,
 }
 
 // API structure implementation
@@ -431,6 +1532,58 @@ impl API {
             None => RVRRPD_CFG_DFLT_TLSCERT.to_string(),
         }
     }
+    // metrics_host() method
+    /// address of the unauthenticated Prometheus metrics listener (if any)
+    pub fn metrics_host(&self) -> Option<String> {
+        self.metrics_host.clone()
+    }
+    // session_lifetime() getter
+    /// time after which a session expires, even if active (in seconds)
+    pub fn session_lifetime(&self) -> u64 {
+        unwrap_cfg(self.try_session_lifetime())
+    }
+    // try_session_lifetime() method
+    fn try_session_lifetime(&self) -> Result<u64, String> {
+        match self.session_lifetime {
+            Some(0) => Err(format!("Please configure a session lifetime higher than 0")),
+            Some(v) => Ok(v),
+            None => Ok(API_SESSION_DFLT_LIFETIME),
+        }
+    }
+    // session_timeout() getter
+    /// time after which an idle session expires (in seconds)
+    pub fn session_timeout(&self) -> u64 {
+        unwrap_cfg(self.try_session_timeout())
+    }
+    // try_session_timeout() method
+    fn try_session_timeout(&self) -> Result<u64, String> {
+        match self.session_timeout {
+            Some(0) => Err(format!("Please configure a session timeout higher than 0")),
+            Some(v) => Ok(v),
+            None => Ok(API_SESSION_DFLT_TIMEOUT),
+        }
+    }
+    // redacted() method
+    /// copy of the API section without the secret and the users password hashes
+    fn redacted(&self) -> API {
+        let mut a = self.clone();
+        if a.secret.is_some() {
+            a.secret = Some(API_REDACTED_SECRET.to_string());
+        }
+        // keep the hashing algorithm, user name and access level of the accounts
+        a.users = self
+            .users
+            .iter()
+            .map(|u| {
+                let f: Vec<&str> = u.splitn(3, ':').collect();
+                match f.len() {
+                    3 => format!("{}:{}:{}", f[0], f[1], API_REDACTED_SECRET),
+                    _ => API_REDACTED_SECRET.to_string(),
+                }
+            })
+            .collect();
+        a
+    }
 }
 
 // gen_runtime_secret() function
@@ -446,3 +1599,306 @@ fn gen_runtime_secret() -> String {
     }
     SECRET.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // validate a TOML configuration and return its errors
+    fn errors(cfg: &str) -> Vec<ConfigIssue> {
+        let config: CConfig = toml::from_str(cfg).unwrap();
+        config
+            .validate()
+            .into_iter()
+            .filter(|i| i.is_error())
+            .collect()
+    }
+
+    // check that an error was reported for the given directive
+    fn has_error(issues: &[ConfigIssue], vrouter: Option<usize>, field: &str, msg: &str) -> bool {
+        issues
+            .iter()
+            .any(|i| i.vrouter == vrouter && i.field == field && i.message.contains(msg))
+    }
+
+    #[test]
+    fn valid_config() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+
+            [[vrouter]]
+            group = 2
+            interface = "lo"
+            vip = "10.0.1.254"
+            track_script = ["chk"]
+
+            [[track_script]]
+            name = "chk"
+            script = "true"
+            weight = 10
+
+            [[sync_group]]
+            name = "sg"
+            vrouters = [ { group = 1, interface = "lo" }, { group = 2, interface = "lo" } ]
+
+            [protocols]
+            [[protocols.static]]
+            route = "10.1.0.0"
+            mask = "255.255.0.0"
+            nh = "10.0.0.1"
+            "#,
+        );
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn no_vrouter() {
+        let issues = errors("debug = 1");
+        assert!(has_error(&issues, None, "vrouter", "No virtual router"));
+    }
+
+    #[test]
+    fn duplicate_vrouter() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.1.254"
+            "#,
+        );
+        assert!(has_error(
+            &issues,
+            Some(1),
+            "interface",
+            "Duplicate virtual router for group 1 on interface lo (see vrouter 0)"
+        ));
+        assert!(!has_error(&issues, Some(0), "interface", "Duplicate"));
+    }
+
+    #[test]
+    fn static_route_mask() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+
+            [protocols]
+            [[protocols.static]]
+            route = "10.1.0.0"
+            mask = "255.0.255.0"
+            nh = "10.0.0.1"
+
+            [[protocols.static]]
+            route = "10.1.1.0"
+            mask = "255.255.0.0"
+            nh = "10.0.0.1"
+
+            [[protocols.static]]
+            route = "2001:db8::"
+            mask = "ffff:ffff:ffff::"
+            nh = "fe80::1"
+
+            [[protocols.static]]
+            route = "2001:db8::1"
+            mask = "ffff:ffff::"
+            nh = "fe80::1"
+
+            [[protocols.static]]
+            route = "10.2.0.0"
+            mask = "ffff:ffff::"
+            nh = "10.0.0.1"
+            "#,
+        );
+        assert!(has_error(
+            &issues,
+            None,
+            "protocols.static[0].mask",
+            "Non-contiguous mask 255.0.255.0"
+        ));
+        assert!(has_error(
+            &issues,
+            None,
+            "protocols.static[1].route",
+            "Route 10.1.1.0 has host bits set for mask 255.255.0.0"
+        ));
+        assert!(!issues
+            .iter()
+            .any(|i| i.field.starts_with("protocols.static[2]")));
+        assert!(has_error(
+            &issues,
+            None,
+            "protocols.static[3].route",
+            "has host bits set"
+        ));
+        assert!(has_error(
+            &issues,
+            None,
+            "protocols.static[4].mask",
+            "is not of the family of route 10.2.0.0"
+        ));
+    }
+
+    #[test]
+    fn duplicate_static_route() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+
+            [protocols]
+            [[protocols.static]]
+            route = "10.1.0.0"
+            mask = "255.255.0.0"
+            nh = "10.0.0.1"
+
+            [[protocols.static]]
+            route = "10.1.0.0"
+            mask = "255.255.0.0"
+            nh = "10.0.0.2"
+            "#,
+        );
+        assert!(has_error(
+            &issues,
+            None,
+            "protocols.static[1].route",
+            "(see protocols.static[0])"
+        ));
+    }
+
+    #[test]
+    fn unknown_track_script() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+            track_script = ["chk", "missing"]
+
+            [[track_script]]
+            name = "chk"
+            script = "true"
+            weight = 10
+            "#,
+        );
+        assert!(has_error(
+            &issues,
+            Some(0),
+            "track_script",
+            "Unknown track script missing"
+        ));
+        assert!(!has_error(
+            &issues,
+            Some(0),
+            "track_script",
+            "Unknown track script chk"
+        ));
+    }
+
+    #[test]
+    fn sync_group_errors() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+
+            [[vrouter]]
+            group = 2
+            interface = "lo"
+            vip = "10.0.1.254"
+
+            [[sync_group]]
+            name = "single"
+            vrouters = [ { group = 1, interface = "lo" } ]
+
+            [[sync_group]]
+            name = "unknown"
+            vrouters = [ { group = 2, interface = "lo" }, { group = 3, interface = "lo" } ]
+
+            [[sync_group]]
+            name = "overlap"
+            vrouters = [ { group = 1, interface = "lo" }, { group = 2, interface = "lo" } ]
+            "#,
+        );
+        assert!(has_error(
+            &issues,
+            None,
+            "sync_group[0].vrouters",
+            "at least two virtual routers in sync group single"
+        ));
+        assert!(has_error(
+            &issues,
+            None,
+            "sync_group[1].vrouters",
+            "Unknown virtual router (group 3, interface lo) in sync group unknown"
+        ));
+        assert!(has_error(
+            &issues,
+            Some(0),
+            "sync_group",
+            "belongs to sync groups single and overlap"
+        ));
+        assert!(has_error(
+            &issues,
+            Some(1),
+            "sync_group",
+            "belongs to sync groups unknown and overlap"
+        ));
+    }
+
+    #[test]
+    fn advert_timers() {
+        let issues = errors(
+            r#"
+            [[vrouter]]
+            group = 1
+            interface = "lo"
+            vip = "10.0.0.254"
+            version = 3
+            timers = { advert = 1, advert_ms = 500 }
+
+            [[vrouter]]
+            group = 2
+            interface = "lo"
+            vip = "10.0.1.254"
+            timers = { advert_ms = 500 }
+
+            [[vrouter]]
+            group = 3
+            interface = "lo"
+            vip = "10.0.2.254"
+            version = 3
+            timers = { advert_ms = 500 }
+            "#,
+        );
+        assert!(has_error(
+            &issues,
+            Some(0),
+            "timers",
+            "either 'advert' or 'advert_ms'"
+        ));
+        assert!(has_error(
+            &issues,
+            Some(1),
+            "timers",
+            "sub-second intervals require VRRP version 3"
+        ));
+        assert!(!issues.iter().any(|i| i.vrouter == Some(2)));
+    }
+}
@@ -15,6 +15,8 @@ pub const RVRRPD_VERSION_STRING: &str = "0.1.3";
 // Config Constants
 pub const RVRRPD_CFG_DFLT_TLSKEY: &str = "/etc/rvrrpd/ssl/key.pem";
 pub const RVRRPD_CFG_DFLT_TLSCERT: &str = "/etc/rvrrpd/ssl/cert.pem";
+pub const RVRRPD_CFG_DFLT_AGENTX_SOCKET: &str = "/var/agentx/master";
+pub const RVRRPD_CFG_DFLT_CONTROL_SOCKET: &str = "/run/rvrrpd.sock";
 
 // Debug Constants
 pub const DEBUG_LEVEL_INFO: u8 = 0;
@@ -40,13 +42,73 @@ pub const DEBUG_SRC_IP: &str = "ip";
 pub const DEBUG_SRC_AUTH: &str = "auth";
 pub const DEBUG_SRC_MACVLAN: &str = "macvlan";
 pub const DEBUG_SRC_BPF: &str = "bpf";
+pub const DEBUG_SRC_SCRIPT: &str = "script";
+pub const DEBUG_SRC_HOOK: &str = "hook";
+pub const DEBUG_SRC_RELOAD: &str = "reload";
+pub const DEBUG_SRC_SNMP: &str = "snmp";
+pub const DEBUG_SRC_API: &str = "api";
+pub const DEBUG_SOURCES: [&str; 23] = [
+    DEBUG_SRC_INFO,
+    DEBUG_SRC_PROTO,
+    DEBUG_SRC_VR,
+    DEBUG_SRC_MAIN,
+    DEBUG_SRC_MAC,
+    DEBUG_SRC_ROUTE,
+    DEBUG_SRC_PACKET,
+    DEBUG_SRC_ARP,
+    DEBUG_SRC_THREAD,
+    DEBUG_SRC_THREADP,
+    DEBUG_SRC_FSM,
+    DEBUG_SRC_WORKER,
+    DEBUG_SRC_WORKERG,
+    DEBUG_SRC_TIMER,
+    DEBUG_SRC_IP,
+    DEBUG_SRC_AUTH,
+    DEBUG_SRC_MACVLAN,
+    DEBUG_SRC_BPF,
+    DEBUG_SRC_SCRIPT,
+    DEBUG_SRC_HOOK,
+    DEBUG_SRC_RELOAD,
+    DEBUG_SRC_SNMP,
+    DEBUG_SRC_API,
+];
+
+// Logging Constants
+pub const LOG_DFLT_SYSLOG_SOCKET: &str = "/dev/log";
+pub const LOG_DFLT_JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+pub const LOG_DFLT_JSON_FILE: &str = "/var/log/rvrrpd.json";
+pub const LOG_DFLT_FACILITY: &str = "daemon";
+pub const LOG_APP_NAME: &str = "rvrrpd";
+pub const LOG_SD_ID: &str = "rvrrpd@32473"; // RFC5424 structured data ID (RFC5612 example PEN)
+pub const LOG_SEVERITY_ERR: u8 = 3;
+pub const LOG_SEVERITY_INFO: u8 = 6;
+pub const LOG_SEVERITY_DEBUG: u8 = 7;
+
+// client API access levels
+pub const API_ACCESS_LEVEL_READ: u8 = 0; // running state and non-sensitive configuration
+pub const API_ACCESS_LEVEL_WRITE: u8 = 1; // runtime configuration changes
+pub const API_ACCESS_LEVEL_ADMIN: u8 = 2; // configuration secrets
+pub const API_REDACTED_SECRET: &str = "<redacted>"; // secrets in the redacted configuration
+
+// client API sessions (in seconds)
+pub const API_SESSION_DFLT_LIFETIME: u64 = 3600; // absolute lifetime
+pub const API_SESSION_DFLT_TIMEOUT: u64 = 600; // idle timeout
+pub const API_SESSION_EXPIRY_INTERVAL: u64 = 10; // interval between the expiry checks
+
+// Link Monitoring Constants
+pub const LINK_MONITOR_BACKOFF_MS: u64 = 100; // delay after a receive error, doubled upon each error
+pub const LINK_MONITOR_MAX_ERRORS: u32 = 10; // consecutive receive errors before giving up
 
 // Ethernet Constants
 pub const ETHER_P_IP: u16 = 0x0800; // IPv4 (/usr/include/linux/if_ether.h)
 pub const ETHER_P_ARP: u16 = 0x0806;
+pub const ETHER_P_IPV6: u16 = 0x86DD; // IPv6 (/usr/include/linux/if_ether.h)
 pub const ETHER_VRRP_IPADDR_POS: usize = 42; // Position of the IP addresses variable-length field
 pub const ETHER_VRRP_V2_SRC_MAC: [u8; 6] = [0x00, 0x00, 0x5e, 0x00, 0x01, 0x00];
 pub const ETHER_VRRP_V2_DST_MAC: [u8; 6] = [0x01, 0x00, 0x5e, 0x00, 0x00, 0x12];
+pub const ETHER_VRRP_V3_IPV6_SRC_MAC: [u8; 6] = [0x00, 0x00, 0x5e, 0x00, 0x02, 0x00];
+pub const ETHER_VRRP_V3_IPV6_DST_MAC: [u8; 6] = [0x33, 0x33, 0x00, 0x00, 0x00, 0x12];
+pub const ETHER_IPV6_ALLNODES_DST_MAC: [u8; 6] = [0x33, 0x33, 0x00, 0x00, 0x00, 0x01];
 pub const ETHER_ARP_DST_MAC: [u8; 6] = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
 pub const ETHER_FRAME_SIZE: usize = 14;
 
@@ -60,6 +122,18 @@ pub const IP_V4_VERSION: u8 = 0x45;
 pub const IP_UPPER_PROTO_VRRP: u8 = 112;
 pub const IP_TTL_VRRP_MINTTL: u8 = 255;
 pub const IP_DSCP_CS6: u8 = 0xc0;
+pub const IP_V6_VERSION: u8 = 0x60;
+pub const IP_V6_HDR_SIZE: usize = 40;
+pub const IP_V6_HOP_LIMIT_VRRP: u8 = 255;
+pub const IP_V6_NEXTHDR_ICMPV6: u8 = 58;
+pub const IP_V6_ALLNODES_MCAST_DST: [u8; 16] =
+    [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01];
+pub const IP_V6_LINKLOCAL_PREFIXLEN: u8 = 64;
+
+// ICMPv6 Constants
+pub const ICMPV6_TYPE_NEIGHBOR_ADVERT: u8 = 136;
+pub const ICMPV6_NA_FLAGS_ROUTER_OVERRIDE: u8 = 0xa0; // R and O flags
+pub const ICMPV6_OPT_TARGET_LLADDR: u8 = 2;
 
 // VRRP Constants
 pub const VRRP_V2_FRAME_OFFSET: usize = 34;
@@ -68,6 +142,14 @@ pub const VRRP_V2_VER_TYPE_AUTHMSG: u8 = 0x21;
 pub const VRRP_V2_IP_MCAST_DST: [u8; 4] = [224, 0, 0, 18];
 pub const VRRP_V2_ADVERT_VERSION_TYPE: u8 = 0x21;
 pub const VRRP_V2_DEFAULT_PRIORITY: u8 = 100;
+pub const VRRP_V3_VER_TYPE_ADVERT: u8 = 0x31;
+pub const VRRP_V3_MAX_ADVERINT: u16 = 4095; // 12 bits (centiseconds)
+pub const VRRP_V3_IPV6_FRAME_OFFSET: usize = 54;
+pub const VRRP_V3_IPV6_ADDR_POS: usize = 62; // Position of the IPv6 addresses variable-length field
+pub const VRRP_V3_IPV6_MCAST_DST: [u8; 16] =
+    [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x12];
+pub const VRRP_V3_IPV6_VIP_PREFIXLEN: u8 = 64;
+pub const VRRP_DEFAULT_VERSION: u8 = 2;
 
 // Authentication Constants
 pub const AUTH_TYPE_SIMPLE: u8 = 1;
@@ -2,13 +2,18 @@
 //! This module provides debugging related functions.
 use super::*;
 
+// std
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::net::UnixDatagram;
+
 // chrono
 use chrono::{DateTime, Local, Utc};
 
 // Verbose Structure
 #[derive(Clone, Copy)]
 pub struct Verbose {
-    level: u8,
+    levels: [u8; DEBUG_SOURCES.len()], // debug level of each source
     time_zone: u8,
     time_format: u8,
 }
@@ -18,42 +23,340 @@ impl Verbose {
     // new() method
     pub fn new(level: u8, time_zone: u8, time_format: u8) -> Verbose {
         Verbose {
-            level,
+            levels: [level; DEBUG_SOURCES.len()],
             time_zone,
             time_format,
         }
     }
+    // set_level() method
+    /// set the debug level of a single source
+    pub fn set_level(&mut self, src: &str, level: u8) {
+        if let Some(i) = DEBUG_SOURCES.iter().position(|s| *s == src) {
+            self.levels[i] = level;
+        }
+    }
+    // level() getter
+    /// debug level of a source (informational messages only for an unknown source)
+    pub fn level(&self, src: &str) -> u8 {
+        match DEBUG_SOURCES.iter().position(|s| *s == src) {
+            Some(i) => self.levels[i],
+            None => DEBUG_LEVEL_INFO,
+        }
+    }
+}
+
+/// Log Fields Structure
+/// the virtual router fields carried by a log record
+#[derive(Debug, Default, Serialize)]
+pub struct Fields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interface: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vip: Option<String>,
+}
+
+// Fields Type Implementation
+impl Fields {
+    // vr() method
+    /// fields of a virtual router, in the given state
+    fn vr(vr: &VirtualRouter, state: &str) -> Fields {
+        Fields {
+            group: Some(vr.parameters.vrid()),
+            interface: Some(vr.parameters.interface()),
+            state: Some(state.to_string()),
+            vip: Some(vr.parameters.attr_vip()),
+        }
+    }
+}
+
+/// JSON Record Structure
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    timestamp: String,
+    level: u8,
+    severity: u8,
+    source: &'a str,
+    #[serde(flatten)]
+    fields: &'a Fields,
+    message: &'a str,
+}
+
+/// Logging Backend Enumerator
+pub enum Backend {
+    Stdout,                           // formatted messages, to the standard output (or main log)
+    Syslog(UnixDatagram, u8, String), // RFC5424 messages, to the syslog socket with the given facility
+    Journald(UnixDatagram),           // native journal protocol messages
+    Json(File),                       // JSON lines, appended to a file
+}
+
+// Backend Type Implementation
+impl Backend {
+    // syslog() method
+    pub fn syslog(socket: &str, facility: u8) -> io::Result<Backend> {
+        Ok(Backend::Syslog(
+            UnixDatagram::unbound()?,
+            facility,
+            socket.to_string(),
+        ))
+    }
+    // journald() method
+    pub fn journald() -> io::Result<Backend> {
+        Ok(Backend::Journald(UnixDatagram::unbound()?))
+    }
+    // json() method
+    pub fn json(path: &str) -> io::Result<Backend> {
+        Ok(Backend::Json(
+            OpenOptions::new().create(true).append(true).open(path)?,
+        ))
+    }
+}
+
+// current logging backend
+lazy_static! {
+    static ref BACKEND: Mutex<Backend> = Mutex::new(Backend::Stdout);
+}
+
+// host name, sent in the syslog messages
+lazy_static! {
+    static ref HOSTNAME: String = {
+        let mut buf = [0u8; 256];
+        match unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) } {
+            0 => {
+                let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+                String::from_utf8_lossy(&buf[..len]).into_owned()
+            }
+            _ => "-".to_string(),
+        }
+    };
+}
+
+// set_backend() function
+/// send the log records to the given backend
+pub fn set_backend(backend: Backend) {
+    *BACKEND.lock().unwrap() = backend;
 }
 
 // print_debug() function
 /// This function simply print debugging information according to the specified level
 pub fn print_debug(debug: &Verbose, msg_level: u8, src: &str, msg: String) {
+    log(debug, msg_level, src, &Fields::default(), msg);
+}
+
+// print_vr() function
+/// print information about a virtual router in its current state
+pub fn print_vr(debug: &Verbose, vr: &VirtualRouter, msg: String) {
+    let fields = Fields::vr(vr, vr.current_state());
+    log(debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, &fields, msg);
+}
+
+// print_vr_state() function
+/// print information about a virtual router entering the given state
+pub fn print_vr_state(debug: &Verbose, vr: &VirtualRouter, state: &str, msg: String) {
+    let fields = Fields::vr(vr, state);
+    log(debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, &fields, msg);
+}
+
+// print_error() function
+/// print an error message, whatever the debug level of its source
+pub fn print_error(debug: &Verbose, src: &str, msg: String) {
+    emit(
+        debug,
+        DEBUG_LEVEL_INFO,
+        LOG_SEVERITY_ERR,
+        src,
+        &Fields::default(),
+        msg,
+    );
+}
+
+// log() function
+/// send a log record to the current backend, according to the level of its source
+fn log(debug: &Verbose, msg_level: u8, src: &str, fields: &Fields, msg: String) {
+    if debug.level(src) < msg_level {
+        return;
+    }
+    emit(debug, msg_level, severity(msg_level), src, fields, msg);
+}
+
+// emit() function
+/// send a record of the given syslog severity to the current backend
+fn emit(debug: &Verbose, msg_level: u8, sev: u8, src: &str, fields: &Fields, msg: String) {
+    let mut backend = BACKEND.lock().unwrap();
+    let r = match &mut *backend {
+        Backend::Stdout => Ok(()),
+        Backend::Syslog(sock, facility, path) => {
+            let rec = syslog_record(debug, *facility, sev, src, fields, &msg);
+            sock.send_to(rec.as_bytes(), &path[..]).map(|_| ())
+        }
+        Backend::Journald(sock) => {
+            let rec = journald_record(sev, src, fields, &msg);
+            sock.send_to(&rec, LOG_DFLT_JOURNALD_SOCKET).map(|_| ())
+        }
+        Backend::Json(file) => {
+            let rec = JsonRecord {
+                timestamp: timestamp(debug),
+                level: msg_level,
+                severity: sev,
+                source: src,
+                fields,
+                message: &msg,
+            };
+            match serde_json::to_string(&rec) {
+                Ok(s) => writeln!(file, "{}", s),
+                Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            }
+        }
+    };
+    // the records that cannot reach their backend are printed to the standard output
+    match (&*backend, r) {
+        (Backend::Stdout, _) | (_, Err(_)) => print_text(debug, sev, src, fields, msg),
+        _ => {}
+    }
+}
+
+// print_text() function
+/// print a formatted log record to the standard output (or error output for the errors)
+fn print_text(debug: &Verbose, sev: u8, src: &str, fields: &Fields, msg: String) {
     // set debug header
     let mut hdr = format!("");
-    if src != DEBUG_SRC_INFO {
+    if sev <= LOG_SEVERITY_ERR {
+        hdr = format!("error({}): ", src);
+    } else if src != DEBUG_SRC_INFO {
         hdr = format!("debug({}): ", src);
     }
 
+    // prefix the virtual router's messages
+    let msg = match (&fields.vip, fields.group, &fields.interface) {
+        (Some(vip), Some(group), Some(interface)) => format!(
+            "VR {} for group {} on interface {} - {}",
+            vip, group, interface, msg
+        ),
+        _ => msg,
+    };
+
     // print debugging information with date and time
-    if debug.level >= msg_level {
-        match debug.time_zone {
-            1 => {
-                // UTC
-                let now: DateTime<Utc> = Utc::now();
-                match debug.time_format {
-                    1 => println!("[{}] {}{}", now.format(RVRRPD_DFLT_DATE_FORMAT), hdr, msg),
-                    2 => println!("[{}] {}{}", now.to_rfc2822(), hdr, msg),
-                    _ => println!("{}{}", hdr, msg),
-                }
-            } // local time
-            _ => {
-                let now: DateTime<Local> = Local::now();
-                match debug.time_format {
-                    1 => println!("[{}] {}{}", now.format(RVRRPD_DFLT_DATE_FORMAT), hdr, msg),
-                    2 => println!("[{}] {}{}", now.to_rfc2822(), hdr, msg),
-                    _ => println!("{}{}", hdr, msg),
-                }
+    let line = match debug.time_zone {
+        1 => {
+            // UTC
+            let now: DateTime<Utc> = Utc::now();
+            match debug.time_format {
+                1 => format!("[{}] {}{}", now.format(RVRRPD_DFLT_DATE_FORMAT), hdr, msg),
+                2 => format!("[{}] {}{}", now.to_rfc2822(), hdr, msg),
+                _ => format!("{}{}", hdr, msg),
             }
+        } // local time
+        _ => {
+            let now: DateTime<Local> = Local::now();
+            match debug.time_format {
+                1 => format!("[{}] {}{}", now.format(RVRRPD_DFLT_DATE_FORMAT), hdr, msg),
+                2 => format!("[{}] {}{}", now.to_rfc2822(), hdr, msg),
+                _ => format!("{}{}", hdr, msg),
+            }
+        }
+    };
+    if sev <= LOG_SEVERITY_ERR {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+// timestamp() function
+/// RFC3339 timestamp of the structured records
+fn timestamp(debug: &Verbose) -> String {
+    match debug.time_zone {
+        1 => Utc::now().to_rfc3339(),
+        _ => Local::now().to_rfc3339(),
+    }
+}
+
+// severity() function
+/// syslog severity of a debug level (informational or debug)
+fn severity(msg_level: u8) -> u8 {
+    match msg_level {
+        DEBUG_LEVEL_INFO => LOG_SEVERITY_INFO,
+        _ => LOG_SEVERITY_DEBUG,
+    }
+}
+
+// syslog_record() function
+/// format an RFC5424 syslog message, the fields being sent as structured data
+fn syslog_record(
+    debug: &Verbose,
+    facility: u8,
+    sev: u8,
+    src: &str,
+    fields: &Fields,
+    msg: &str,
+) -> String {
+    // escape the structured data parameter values (RFC5424 6.3.3)
+    let esc = |v: &str| {
+        v.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace(']', "\\]")
+    };
+    let mut sd = format!("[{} source=\"{}\"", LOG_SD_ID, esc(src));
+    if let Some(g) = fields.group {
+        sd += &format!(" group=\"{}\"", g);
+    }
+    for (name, value) in &[
+        ("interface", &fields.interface),
+        ("state", &fields.state),
+        ("vip", &fields.vip),
+    ] {
+        if let Some(v) = value {
+            sd += &format!(" {}=\"{}\"", name, esc(v));
+        }
+    }
+    sd += "]";
+    format!(
+        "<{}>1 {} {} {} {} {} {} {}",
+        facility as u16 * 8 + sev as u16,
+        timestamp(debug),
+        *HOSTNAME,
+        LOG_APP_NAME,
+        std::process::id(),
+        src,
+        sd,
+        msg
+    )
+}
+
+// journald_record() function
+/// encode a message with the native journal protocol
+fn journald_record(sev: u8, src: &str, fields: &Fields, msg: &str) -> Vec<u8> {
+    let mut rec = Vec::new();
+    let mut field = |name: &str, value: &str| {
+        rec.extend_from_slice(name.as_bytes());
+        // the values spanning several lines are sent with their size
+        if value.contains('\n') {
+            rec.push(b'\n');
+            rec.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            rec.push(b'=');
         }
+        rec.extend_from_slice(value.as_bytes());
+        rec.push(b'\n');
+    };
+    field("MESSAGE", msg);
+    field("PRIORITY", &sev.to_string());
+    field("SYSLOG_IDENTIFIER", LOG_APP_NAME);
+    field("RVRRPD_SOURCE", src);
+    if let Some(g) = fields.group {
+        field("RVRRPD_GROUP", &g.to_string());
+    }
+    if let Some(v) = &fields.interface {
+        field("RVRRPD_INTERFACE", v);
+    }
+    if let Some(v) = &fields.state {
+        field("RVRRPD_STATE", v);
+    }
+    if let Some(v) = &fields.vip {
+        field("RVRRPD_VIP", v);
     }
+    rec
 }
@@ -0,0 +1,141 @@
+//! events module
+//! This module broadcasts the virtual routers' events to the client API's event streams
+//! and to the other listeners (e.g. the SNMP subagent).
+use super::*;
+
+// std
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// futures
+use futures::sync::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+
+// serde
+use serde::Serialize;
+
+/// Event Kind Enumerator (Serialize-able)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    // finite state machine transition
+    Transition { from: String, to: String },
+    // advertisement received from a new master
+    NewMaster { master: String, priority: u8 },
+    // received advertisement failing the authentication
+    AuthFailure { source: String, reason: String },
+    // running priority change
+    Priority { from: u8, to: u8 },
+}
+
+impl EventKind {
+    // name() getter
+    /// name of the event, as sent in the event stream
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Transition { .. } => "transition",
+            EventKind::NewMaster { .. } => "new_master",
+            EventKind::AuthFailure { .. } => "auth_failure",
+            EventKind::Priority { .. } => "priority",
+        }
+    }
+}
+
+/// Virtual Router Event Structure (Serialize-able)
+#[derive(Debug, Clone, Serialize)]
+pub struct VREvent {
+    timestamp: u64,
+    group: u8,
+    interface: String,
+    #[serde(flatten)]
+    kind: EventKind,
+}
+
+// VREvent Type Implementation
+impl VREvent {
+    // group() getter
+    pub fn group(&self) -> u8 {
+        self.group
+    }
+    // interface() getter
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+    // kind() getter
+    pub fn kind(&self) -> &EventKind {
+        &self.kind
+    }
+}
+
+lazy_static! {
+    // event streams subscribers, with the session (nonce) that opened them
+    static ref SUBSCRIBERS: Mutex<Vec<(Option<String>, UnboundedSender<String>)>> =
+        Mutex::new(Vec::new());
+    // events listeners
+    static ref LISTENERS: Mutex<Vec<Sender<VREvent>>> = Mutex::new(Vec::new());
+}
+
+// subscribe() function
+/// register a new event stream, receiving the events formatted as Server-Sent Events
+/// (the streams of the local socket peers have no session)
+pub fn subscribe(session: Option<String>) -> UnboundedReceiver<String> {
+    let (tx, rx) = unbounded();
+    SUBSCRIBERS.lock().unwrap().push((session, tx));
+    rx
+}
+
+// unsubscribe() function
+/// close the event streams opened by a session
+pub fn unsubscribe(session: &str) {
+    SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .retain(|(s, _)| s.as_ref().map(|s| &s[..]) != Some(session));
+}
+
+// streaming() function
+/// check if a session has an open event stream
+pub fn streaming(session: &str) -> bool {
+    SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(s, tx)| s.as_ref().map(|s| &s[..]) == Some(session) && !tx.is_closed())
+}
+
+// listen() function
+/// register a new listener, receiving the events as they are published
+pub fn listen() -> Receiver<VREvent> {
+    let (tx, rx) = crossbeam::unbounded();
+    LISTENERS.lock().unwrap().push(tx);
+    rx
+}
+
+// publish() function
+/// send a virtual router's event to every event stream and listener
+pub fn publish(group: u8, interface: String, kind: EventKind) {
+    let mut subscribers = SUBSCRIBERS.lock().unwrap();
+    let mut listeners = LISTENERS.lock().unwrap();
+    if subscribers.is_empty() && listeners.is_empty() {
+        return;
+    }
+    let name = kind.name();
+    let ev = VREvent {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        group,
+        interface,
+        kind,
+    };
+    listeners.retain(|tx| tx.send(ev.clone()).is_ok());
+    if subscribers.is_empty() {
+        return;
+    }
+    let msg = format!(
+        "event: {}\ndata: {}\n\n",
+        name,
+        serde_json::to_string(&ev).expect("serialized event")
+    );
+    // the streams of disconnected clients are dropped
+    subscribers.retain(|(_, tx)| tx.unbounded_send(msg.clone()).is_ok());
+}
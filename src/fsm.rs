@@ -8,12 +8,19 @@ use std::sync::{Arc, Mutex};
 // threads
 use std::thread;
 
+// std
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
 // debugging
-use crate::debug::Verbose;
+use crate::debug::{print_error, print_vr, print_vr_state, Verbose};
 
 // operating system drivers
 use crate::os::drivers::Operation;
 
+// statistics
+use crate::stats::NewMasterReason;
+
 // address resolution protocol
 #[cfg(target_os = "linux")]
 use os::linux::arp::open_raw_socket_arp;
@@ -43,42 +50,73 @@ impl States {
 #[derive(Debug)]
 pub struct Timers {
     master_down: f32, // Timer that fires when ADVERTISEMENT has not been heared for 'master_down'
-    advert: u8,       // Timer that fires sending of ADVERTISEMENT every 'advertint' interval
+    advert: Option<u32>, // Timer sending an ADVERTISEMENT every 'advertint' interval (ms), None if stopped
+    epoch: u32,          // Timers generation, running timers stop when it changes
+    startup_until: Option<Instant>, // End of the startup delay
+    preempt_until: Option<Instant>, // End of the preemption delay
 }
 
 // Timers Type Implementation
-impl Timers {
-    // new() method
-    pub fn new(master_down: f32, advert: u8) -> Timers {
+// (the timers are stopped until the virtual router's startup)
+impl Default for Timers {
+    fn default() -> Self {
         Timers {
-            master_down,
-            advert,
+            master_down: -1.0,
+            advert: None,
+            epoch: 0,
+            startup_until: None,
+            preempt_until: None,
         }
     }
+}
+impl Timers {
     // master_down() getter
     pub fn master_down(&self) -> f32 {
         self.master_down
     }
     // advert() getter
-    pub fn advert(&self) -> u8 {
-        // make sure the advertisement timer is higher than zero
-        //assert!(self.advert > 0);
+    /// advertisement interval (in milliseconds), none if the timer is stopped
+    pub fn advert(&self) -> Option<u32> {
         self.advert
     }
+    // epoch() getter
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+    // renew() method
+    // start a new timers generation, the running timers will stop on their next expiry
+    pub fn renew(&mut self) {
+        self.epoch = self.epoch.wrapping_add(1);
+    }
+    // startup_delay_remaining() method
+    // remaining startup delay (in seconds), if any
+    pub fn startup_delay_remaining(&self) -> Option<f32> {
+        remaining(self.startup_until)
+    }
+    // preempt_delay_remaining() method
+    // remaining preemption delay (in seconds), if any
+    pub fn preempt_delay_remaining(&self) -> Option<f32> {
+        remaining(self.preempt_until)
+    }
 }
 
 /// Flags Structure
 #[derive(Debug)]
 pub struct Flags {
-    down: u8,    // down flag
-    rtset: bool, // routes set
+    down: u8,          // down flag
+    rtset: bool,       // routes set
+    maintenance: bool, // pinned in Backup state (maintenance mode)
 }
 
 // Flags Type Implementation
 impl Flags {
     // new() method
     pub fn new(down: u8) -> Flags {
-        Flags { down, rtset: false }
+        Flags {
+            down,
+            rtset: false,
+            maintenance: false,
+        }
     }
     // get_down_flag() method
     pub fn get_down_flag(&self) -> u8 {
@@ -107,18 +145,34 @@ impl Flags {
     pub fn clear_rtset(&mut self) {
         self.rtset = false;
     }
+    // maintenance() getter
+    pub fn maintenance(&self) -> bool {
+        self.maintenance
+    }
+    // set_maintenance() setter
+    pub fn set_maintenance(&mut self, maintenance: bool) {
+        self.maintenance = maintenance;
+    }
 }
 
 /// Event Enumerator
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Event {
     Startup,
     Shutdown, // Internal Shutdown Event
     Terminate,
-    MasterDown,          // internal master down notification
-    MasterDownExpiry,    // internal master_down timer expiry notification
-    Advert([u8; 4], u8), // got ADVERTISEMENT message (carrying priority)
-    GenAdvert,           // generate an ADVERTISEMENT message
+    MasterDown,                // internal master down notification
+    MasterDownExpiry,          // internal master_down timer expiry notification
+    Advert(IpAddr, u8, u16),   // got ADVERTISEMENT message (carrying priority and interval)
+    GenAdvert,                 // generate an ADVERTISEMENT message
+    LinkState(String, bool),   // tracked interface link state change (name, up)
+    ScriptState(String, bool), // track script state change (name, healthy)
+    SyncMaster,                // sync group member left Backup for Master
+    SyncBackup,                // sync group member left Master for Backup
+    Reconfigure,               // parameters updated by a configuration reload
+    Resign(String),            // administrative resignation (API user)
+    Maintenance(String, bool), // maintenance mode change (API user, enabled)
+    Takeover(String),          // administrative takeover (API user)
 }
 
 // fsm_run() function
@@ -224,6 +278,75 @@ pub fn fsm_run(
             format!("worker thread {} write lock acquired", id),
         );
 
+        // tracked interfaces' link state and track scripts' state changes
+        // are handled in every state
+        match &event {
+            Event::LinkState(ifname, up) => {
+                if vr.parameters.set_link_state(ifname, *up) {
+                    let state = format!(
+                        "Tracked interface {} is {}",
+                        ifname,
+                        if *up { "up" } else { "down" }
+                    );
+                    handle_track_change(&mut vr, &state, fd, debug);
+                }
+                continue;
+            }
+            Event::ScriptState(name, healthy) => {
+                if vr.parameters.set_script_state(name, *healthy) {
+                    let state = format!(
+                        "Track script {} is {}",
+                        name,
+                        if *healthy { "healthy" } else { "failed" }
+                    );
+                    handle_track_change(&mut vr, &state, fd, debug);
+                }
+                continue;
+            }
+            Event::Reconfigure => {
+                handle_reconfigure(&mut vr, timer_tx, timer_vr, fd, debug);
+                continue;
+            }
+            Event::Advert(ipsrc, prio, _) => {
+                if vr.parameters.set_last_master(*ipsrc, *prio) {
+                    vr.publish(events::EventKind::NewMaster {
+                        master: ipsrc.to_string(),
+                        priority: *prio,
+                    });
+                }
+            }
+            Event::Maintenance(user, enabled) => {
+                if *enabled != vr.flags.maintenance() {
+                    vr.flags.set_maintenance(*enabled);
+                    // print information
+                    print_vr(
+                        debug,
+                        &vr,
+                        format!(
+                            "Maintenance mode {} by API user {}",
+                            if *enabled { "enabled" } else { "released" },
+                            user
+                        ),
+                    );
+                }
+                // a Master entering the maintenance mode resigns
+                let master = match vr.states {
+                    States::Master => true,
+                    _ => false,
+                };
+                if !(*enabled && master) {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        // transitions forced by a sync group member are not propagated back
+        let forced = match &event {
+            Event::SyncMaster | Event::SyncBackup => true,
+            _ => false,
+        };
+
         // evaluate virtual router's current state
         let st = match &vr.get_states() {
             States::Down => {
@@ -241,36 +364,35 @@ pub fn fsm_run(
                             format!("got Startup event on thread {}", id),
                         );
 
+                        // delay the startup, the Startup event is sent again once the delay expired
+                        let delay = vr.parameters.startup_delay();
+                        if delay > 0 && vr.timers.startup_until.is_none() {
+                            vr.timers.startup_until =
+                                Some(Instant::now() + Duration::from_secs(delay as u64));
+                            // print information
+                            print_vr(debug, &vr, format!("Delaying startup for {}s", delay));
+                            let startup_tx = Arc::clone(&tx);
+                            thread::spawn(move || {
+                                thread::sleep(Duration::from_secs(delay as u64));
+                                let _r = startup_tx.lock().unwrap().send(Event::Startup);
+                            });
+                            continue;
+                        }
+
                         // print information
-                        let vip = vr.parameters.vip();
+                        let vip = vr.parameters.attr_vip();
                         print_debug(debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                            "Starting VRRP Virtual Router ({}.{}.{}.{}) for group {}, on interface {} (thread: {})",
-                            vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface(), id
+                            "Starting VRRP Virtual Router ({}) for group {}, on interface {} (thread: {})",
+                            vip, vr.parameters.vrid(), vr.parameters.interface(), id
                         ));
 
-                        // print debugging information
-                        print_debug(
-                            debug,
-                            DEBUG_LEVEL_EXTENSIVE,
-                            DEBUG_SRC_WORKER,
-                            format!("starting timer thread from worker thread {}", id),
-                        );
-
-                        // starting timer thread(s)
-                        // and clone debug structure of type Verbose
-                        let d = debug.clone();
-                        let _timer_thread = thread::spawn(move || {
-                            timers::start_timers(timer_tx, timer_vr, &d);
-                        });
-
                         // if the virtual router is the owner of the virtual ip address
                         // OR the priority has been configured at 255
-                        if vr.is_owner_vip(&vr.parameters.vip()) || vr.parameters.prio() == 255 {
+                        if vr.is_owner_vip() || vr.parameters.prio() == 255 {
                             // force the priority to 255
                             vr.parameters.set_prio(255);
                             // set VRRP virtual mac address
-                            let mut vmac = ETHER_VRRP_V2_SRC_MAC;
-                            vmac[5] = vr.parameters.vrid();
+                            let vmac = vr.parameters.vmac();
 
                             // --- Linux specific interface tyoe handling
                             #[cfg(target_os = "linux")]
@@ -311,19 +433,37 @@ pub fn fsm_run(
                             // send an ADVERTISEMENT message
                             match vr.send_advertisement(fd, &debug) {
                                 Ok(_) => (),
-                                Err(e) => eprintln!(
-                                    "error(fsm): error while sending VRRP advertisement on interface {}: {}",
-                                    vr.parameters.interface(),
-                                    e
+                                Err(e) => print_error(
+                                    debug,
+                                    DEBUG_SRC_FSM,
+                                    format!(
+                                        "error while sending VRRP advertisement on interface {}: {}",
+                                        vr.parameters.interface(),
+                                        e
+                                    ),
                                 ),
                             }
 
                             // --- Linux specific ARP handling
                             #[cfg(target_os = "linux")]
                             {
-                                // send gratuitious ARP requests
-                                let arp_sockfd = open_raw_socket_arp().unwrap();
-                                vr.broadcast_gratuitious_arp(arp_sockfd, debug).unwrap();
+                                if vr.parameters.is_ipv6() {
+                                    // send unsolicited neighbor advertisements
+                                    if let Err(e) = vr.send_unsolicited_na(fd, debug) {
+                                        print_error(
+                                            debug,
+                                            DEBUG_SRC_FSM,
+                                            format!(
+                                                "error while sending neighbor advertisements: {}",
+                                                e
+                                            ),
+                                        );
+                                    }
+                                } else {
+                                    // send gratuitious ARP requests
+                                    let arp_sockfd = open_raw_socket_arp().unwrap();
+                                    vr.broadcast_gratuitious_arp(arp_sockfd, debug).unwrap();
+                                }
                             }
                             // END Linux specific ARP handling
 
@@ -336,7 +476,7 @@ pub fn fsm_run(
                             // END FreeBSD specific ARP handling
 
                             // set advertisement interval
-                            vr.timers.advert = vr.parameters.adverint();
+                            vr.timers.advert = Some(vr.parameters.adverint());
                             // cancel master_down timer
                             vr.timers.master_down = -1.0;
                             // print debugging information
@@ -344,25 +484,36 @@ pub fn fsm_run(
                                 &debug,
                                 DEBUG_LEVEL_EXTENSIVE,
                                 DEBUG_SRC_FSM,
-                                format!("the advertisement interval is now {}s", vr.timers.advert),
+                                format!(
+                                    "the advertisement interval is now {}ms",
+                                    vr.parameters.adverint()
+                                ),
                             );
+                            // starting timer thread(s)
+                            restart_timers(&mut vr, timer_tx, timer_vr, debug);
                             // print information
-                            let vip = vr.parameters.vip();
-                            print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                                "VR {}.{}.{}.{} for group {} on interface {} - Changed from Init to Master",
-                                vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                            ));
+                            print_vr_state(
+                                &debug,
+                                &vr,
+                                "Master",
+                                format!("Changed from Init to Master"),
+                            );
+                            // the owner of the virtual addresses becomes Master
+                            vr.stats.set_new_master_reason(NewMasterReason::Priority);
                             // transition to Master state
                             fsm::States::Master
                         } else {
                             // set master_down timer
                             vr.timers.master_down = vr.parameters.master_down();
+                            // starting timer thread(s)
+                            restart_timers(&mut vr, timer_tx, timer_vr, debug);
                             // print information
-                            let vip = vr.parameters.vip();
-                            print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                                "VR {}.{}.{}.{} for group {} on interface {} - Changed from Init to Backup",
-                                vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                            ));
+                            print_vr_state(
+                                &debug,
+                                &vr,
+                                "Backup",
+                                format!("Changed from Init to Backup"),
+                            );
                             // transition to Backup state
                             States::Backup
                         }
@@ -370,11 +521,7 @@ pub fn fsm_run(
                     // event: if Shutdown event is received
                     Event::Shutdown => {
                         // print information
-                        let vip = vr.parameters.vip();
-                        print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                            "VR {}.{}.{}.{} for group {} on interface {} - Changed from Init to Down",
-                            vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                        ));
+                        print_vr_state(&debug, &vr, "Down", format!("Changed from Init to Down"));
                         // transition to Down state
                         States::Down
                     }
@@ -393,7 +540,7 @@ pub fn fsm_run(
             States::Backup => {
                 match event {
                     // event: If ADVERTISEMENT message is received
-                    Event::Advert(_ipsrc, prio) => {
+                    Event::Advert(_ipsrc, prio, adverint) => {
                         // if the priority is zero then set the master_down timer to skew_time
                         if prio == 0 {
                             // set master_down interval to skew_time
@@ -401,6 +548,27 @@ pub fn fsm_run(
                         } else {
                             // if priority is greater than or equal to the local priority OR preempt is false
                             if vr.parameters.preempt() == false || prio >= vr.parameters.prio() {
+                                // VRRPv3: learn the master advertisement interval (RFC5798 6.4.2)
+                                if vr.parameters.version() == 3
+                                    && adverint != vr.parameters.master_adverint()
+                                {
+                                    // recompute the master_down interval
+                                    vr.parameters.set_master_adverint(adverint);
+                                    vr.timers.master_down = vr.parameters.master_down();
+                                    // print debugging information
+                                    print_debug(
+                                        debug,
+                                        DEBUG_LEVEL_HIGH,
+                                        DEBUG_SRC_FSM,
+                                        format!(
+                                            "learned master advertisement interval {}cs, master_down is now {}s",
+                                            adverint,
+                                            vr.parameters.master_down()
+                                        ),
+                                    );
+                                    // restart the timers with the new master_down interval
+                                    restart_timers(&mut vr, timer_tx, timer_vr, debug);
+                                }
                                 // clear down flag (signal master is alive)
                                 vr.flags.clear_down_flag();
                                 // print debugging information
@@ -410,6 +578,33 @@ pub fn fsm_run(
                                     DEBUG_SRC_FSM,
                                     format!("down flag cleared in Backup state"),
                                 );
+                                // no lower priority master to preempt
+                                vr.timers.preempt_until = None;
+                            } else if vr.parameters.preempt_delay() > 0 {
+                                // the lower priority master is preempted once the delay expired
+                                let now = Instant::now();
+                                if vr.timers.preempt_until.is_none() {
+                                    let delay = vr.parameters.preempt_delay();
+                                    vr.timers.preempt_until =
+                                        Some(now + Duration::from_secs(delay as u64));
+                                    // print information
+                                    print_vr(
+                                        debug,
+                                        &vr,
+                                        format!("Delaying preemption for {}s", delay),
+                                    );
+                                }
+                                if vr.timers.preempt_delay_remaining().is_some() {
+                                    // clear down flag (the master is kept until the delay expired)
+                                    vr.flags.clear_down_flag();
+                                    // print debugging information
+                                    print_debug(
+                                        debug,
+                                        DEBUG_LEVEL_HIGH,
+                                        DEBUG_SRC_FSM,
+                                        format!("down flag cleared in Backup state, preemption is delayed"),
+                                    );
+                                }
                             }
                         }
                         continue;
@@ -427,27 +622,39 @@ pub fn fsm_run(
                         );
                         continue;
                     }
+                    // event: the maintenance mode pins the virtual router in Backup state
+                    Event::MasterDown | Event::SyncMaster | Event::Takeover(_)
+                        if vr.flags.maintenance() =>
+                    {
+                        continue;
+                    }
                     // event: If the Timers::master_down reached zero
-                    Event::MasterDown => {
+                    // OR a sync group member became Master
+                    // OR a takeover has been requested
+                    Event::MasterDown | Event::SyncMaster | Event::Takeover(_) => {
                         // print information
-                        let vip = vr.parameters.vip();
-                        print_debug(
-                            debug,
-                            DEBUG_LEVEL_INFO,
-                            DEBUG_SRC_INFO,
-                            format!(
-                                "VR {}.{}.{}.{} for group {} on interface {} - Master VR is down",
-                                vip[0],
-                                vip[1],
-                                vip[2],
-                                vip[3],
-                                vr.parameters.vrid(),
-                                vr.parameters.interface()
-                            ),
-                        );
+                        let reason = match &event {
+                            Event::SyncMaster => format!("Sync group member became Master"),
+                            Event::Takeover(user) => {
+                                format!("Takeover requested by API user {}", user)
+                            }
+                            _ => format!("Master VR is down"),
+                        };
+                        print_vr(debug, &vr, reason);
+                        // a lower priority master is preempted, otherwise it stopped responding
+                        let lower = vr.parameters.last_master_prio() != 0
+                            && vr.parameters.last_master_prio() < vr.parameters.prio();
+                        vr.stats.set_new_master_reason(match &event {
+                            Event::Takeover(_) => NewMasterReason::Preempted,
+                            Event::MasterDown if vr.parameters.preempt() && lower => {
+                                NewMasterReason::Preempted
+                            }
+                            _ => NewMasterReason::MasterNoResponse,
+                        });
+                        // clear the preemption delay
+                        vr.timers.preempt_until = None;
                         // set VRRP virtual mac address
-                        let mut vmac = ETHER_VRRP_V2_SRC_MAC;
-                        vmac[5] = vr.parameters.vrid();
+                        let vmac = vr.parameters.vmac();
 
                         // --- Linux specific interface type handling
                         #[cfg(target_os = "linux")]
@@ -505,9 +712,23 @@ pub fn fsm_run(
                         // --- Linux specific ARP handling
                         #[cfg(target_os = "linux")]
                         {
-                            // send gratuitious ARP requests
-                            let arp_sockfd = open_raw_socket_arp().unwrap();
-                            vr.broadcast_gratuitious_arp(arp_sockfd, debug).unwrap();
+                            if vr.parameters.is_ipv6() {
+                                // send unsolicited neighbor advertisements
+                                if let Err(e) = vr.send_unsolicited_na(fd, debug) {
+                                    print_error(
+                                        debug,
+                                        DEBUG_SRC_FSM,
+                                        format!(
+                                            "error while sending neighbor advertisements: {}",
+                                            e
+                                        ),
+                                    );
+                                }
+                            } else {
+                                // send gratuitious ARP requests
+                                let arp_sockfd = open_raw_socket_arp().unwrap();
+                                vr.broadcast_gratuitious_arp(arp_sockfd, debug).unwrap();
+                            }
                         }
                         // END Linux specific ARP handling
 
@@ -521,52 +742,41 @@ pub fn fsm_run(
                         }
                         // END FreeBSD specific interface tyoe handling
 
-                        // if the master_down and advert timers have been canceled, restart them.
-                        if (vr.timers.master_down <= 0.0) && (vr.timers.advert <= 0) {
-                            // re-init timers
-                            vr.timers.master_down = vr.parameters.master_down();
-                            vr.timers.advert = vr.parameters.adverint();
-
-                            // starting timer thread(s)
-                            // and clone debug structure of type Verbose
-                            let d = debug.clone();
-                            let _timer_thread = thread::spawn(move || {
-                                timers::start_timers(timer_tx, timer_vr, &d);
-                            });
-                        }
-
                         // set advertisement timer
-                        vr.timers.advert = vr.parameters.adverint();
+                        vr.timers.advert = Some(vr.parameters.adverint());
                         // cancel master_down timer
                         vr.timers.master_down = -1.0;
+                        // restart the timers with the configured advertisement interval
+                        restart_timers(&mut vr, timer_tx, timer_vr, debug);
                         // send ADVERTISEMENT
                         match vr.send_advertisement(fd, &debug) {
                             Ok(_) => (),
-                            Err(e) => eprintln!(
-                                "error(fsm): error while sending VRRP advertisement on interface {}: {}",
-                                vr.parameters.interface(),
-                                e
+                            Err(e) => print_error(
+                                debug,
+                                DEBUG_SRC_FSM,
+                                format!(
+                                    "error while sending VRRP advertisement on interface {}: {}",
+                                    vr.parameters.interface(),
+                                    e
+                                ),
                             ),
                         }
                         // print information
-                        let vip = vr.parameters.vip();
-                        print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                            "VR {}.{}.{}.{} for group {} on interface {} - Changed from Backup to Master",
-                            vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                        ));
+                        print_vr_state(
+                            &debug,
+                            &vr,
+                            "Master",
+                            format!("Changed from Backup to Master"),
+                        );
                         // transition to Master state
                         States::Master
                     }
                     // event: if Shutdown event is received
                     Event::Shutdown => {
                         // print information
-                        let vip = vr.parameters.vip();
-                        print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                            "VR {}.{}.{}.{} for group {} on interface {} - Changed from Backup to Down",
-                            vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                        ));
+                        print_vr_state(&debug, &vr, "Down", format!("Changed from Backup to Down"));
                         // cancel advertisement timer
-                        vr.timers.advert = 0;
+                        vr.timers.advert = None;
                         // cancel master_down timer
                         vr.timers.master_down = -1.0;
                         // transition to Down state
@@ -584,31 +794,39 @@ pub fn fsm_run(
                         // send ADVERTISEMENT message
                         match vr.send_advertisement(fd, &debug) {
                             Ok(_) => (),
-                            Err(e) => eprintln!(
-                                "error(fsm): error while sending VRRP advertisement on interface {}: {}",
-                                vr.parameters.interface(),
-                                e
+                            Err(e) => print_error(
+                                debug,
+                                DEBUG_SRC_FSM,
+                                format!(
+                                    "error while sending VRRP advertisement on interface {}: {}",
+                                    vr.parameters.interface(),
+                                    e
+                                ),
                             ),
                         }
                         // reset the advertisement timer to advertisement interval
-                        vr.timers.advert = vr.parameters.adverint();
+                        vr.timers.advert = Some(vr.parameters.adverint());
                         continue;
                     }
                     // event: we got an ADVERTISEMENT message
-                    Event::Advert(ipsrc, prio) => {
+                    Event::Advert(ipsrc, prio, adverint) => {
                         // if priority is zero
                         if prio == 0 {
                             // send an ADVERTISEMENT message
                             match vr.send_advertisement(fd, &debug) {
                                 Ok(_) => (),
-                                Err(e) => eprintln!(
-                                    "error(fsm): error while sending VRRP advertisement on interface {}: {}",
-                                    vr.parameters.interface(),
-                                    e
+                                Err(e) => print_error(
+                                    debug,
+                                    DEBUG_SRC_FSM,
+                                    format!(
+                                        "error while sending VRRP advertisement on interface {}: {}",
+                                        vr.parameters.interface(),
+                                        e
+                                    ),
                                 ),
                             }
                             // reset the advertisement timer to advertisement interval
-                            vr.timers.advert = vr.parameters.adverint();
+                            vr.timers.advert = Some(vr.parameters.adverint());
                             // state doesn't change
                             continue;
                         } else {
@@ -617,22 +835,17 @@ pub fn fsm_run(
                             // local address)
                             if prio > vr.parameters.prio()
                                 || (prio == vr.parameters.prio()
-                                    && is_primary_higher(&ipsrc, &vr.parameters.ipaddrs()[0]))
+                                    && is_primary_higher(&ipsrc, &vr.parameters.primary_ipaddr()))
                             {
                                 // cancel advertisement timer
-                                vr.timers.advert = 255;
-                                // if the master_down has been canceled, init and restart it.
-                                if vr.timers.master_down <= 0.0 {
-                                    // re-init timers
-                                    vr.timers.master_down = vr.parameters.master_down();
-
-                                    // starting timer thread(s)
-                                    // and clone debug structure of type Verbose
-                                    let d = debug.clone();
-                                    let _timer_thread = thread::spawn(move || {
-                                        timers::start_timers(timer_tx, timer_vr, &d);
-                                    });
+                                vr.timers.advert = None;
+                                // VRRPv3: learn the master advertisement interval (RFC5798 6.4.3)
+                                if vr.parameters.version() == 3 {
+                                    vr.parameters.set_master_adverint(adverint);
                                 }
+                                // init and restart the master_down timer
+                                vr.timers.master_down = vr.parameters.master_down();
+                                restart_timers(&mut vr, timer_tx, timer_vr, debug);
                                 // clear down flag (mark master alive)
                                 vr.flags.clear_down_flag();
                                 // print debugging information
@@ -643,60 +856,16 @@ pub fn fsm_run(
                                     format!("down flag cleared in Master state"),
                                 );
 
-                                // --- Linux specific interface tyoe handling
-                                #[cfg(target_os = "linux")]
-                                // restore primary or delete vip on vr's interface
-                                match vr.parameters.iftype() {
-                                    IfTypes::macvlan => {
-                                        // removes macvlan interface
-                                        vr.setup_macvlan_link(
-                                            vr.parameters.ifmac(),
-                                            Operation::Rem,
-                                            debug,
-                                        );
-                                        // restore back vif and physical interfaces
-                                        let vif = vr.parameters.interface();
-                                        let phys = vr.parameters.vifname();
-                                        vr.parameters.set_vifname(vif);
-                                        vr.parameters.set_interface(phys);
-                                        // remove added routes
-                                        vr.set_ip_routes(fd, Operation::Rem, debug);
-                                    }
-                                    _ => {
-                                        // restore interface's MAC address
-                                        vr.set_mac_addresses(fd, vr.parameters.ifmac(), debug);
-                                        match vr.parameters.netdrv() {
-                                            NetDrivers::ioctl => {
-                                                // restore primary IP
-                                                #[cfg(target_os = "linux")]
-                                                vr.set_ip_addresses(fd, Operation::Rem, debug);
-                                                // re-set routes
-                                                vr.set_ip_routes(fd, Operation::Add, debug);
-                                            }
-                                            NetDrivers::libnl => {
-                                                // delete vip
-                                                vr.delete_ip_addresses(fd, debug);
-                                            }
-                                        }
-                                    }
-                                }
-                                // END Linux specific interface type handling
-
-                                // -- FreeBSD specific interface type handling
-                                #[cfg(target_os = "freebsd")]
-                                {
-                                    // we don't have to re-set the mac address here
-                                    // delete the VIP
-                                    vr.delete_ip_addresses(fd, debug);
-                                }
-                                // END FreeBSD specific interface type handling
+                                // release the virtual router's addresses
+                                release_master(&mut vr, fd, debug);
 
                                 // print information
-                                let vip = vr.parameters.vip();
-                                print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                                    "VR {}.{}.{}.{} for group {} on interface {} - Changed from Master to Backup",
-                                    vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                                ));
+                                print_vr_state(
+                                    &debug,
+                                    &vr,
+                                    "Backup",
+                                    format!("Changed from Master to Backup"),
+                                );
                                 // transition to Backup state
                                 States::Backup
                             } else {
@@ -715,26 +884,98 @@ pub fn fsm_run(
                         );
                         continue;
                     }
+                    // event: a sync group member became Backup
+                    Event::SyncBackup => {
+                        // cancel advertisement timer
+                        vr.timers.advert = None;
+                        // init and restart the master_down timer
+                        vr.timers.master_down = vr.parameters.master_down();
+                        restart_timers(&mut vr, timer_tx, timer_vr, debug);
+                        // clear down flag (give the new master a full master_down interval)
+                        vr.flags.clear_down_flag();
+
+                        // release the virtual router's addresses
+                        release_master(&mut vr, fd, debug);
+
+                        // print information
+                        print_vr_state(
+                            &debug,
+                            &vr,
+                            "Backup",
+                            format!(
+                                "Sync group member became Backup, changed from Master to Backup"
+                            ),
+                        );
+                        // transition to Backup state
+                        States::Backup
+                    }
+                    // event: resignation requested, or maintenance mode enabled
+                    Event::Resign(ref user) | Event::Maintenance(ref user, true) => {
+                        // send ADVERTISEMENT with priority equal 0 (RFC3768 6.4.3)
+                        // so that a Backup takes over without waiting for master_down
+                        let prio = vr.parameters.prio();
+                        vr.parameters.set_prio(0);
+                        match vr.send_advertisement(fd, &debug) {
+                            Ok(_) => (),
+                            Err(e) => print_error(
+                                debug,
+                                DEBUG_SRC_FSM,
+                                format!(
+                                    "error while sending VRRP advertisement on interface {}: {}",
+                                    vr.parameters.interface(),
+                                    e
+                                ),
+                            ),
+                        }
+                        vr.parameters.set_prio(prio);
+                        // cancel advertisement timer
+                        vr.timers.advert = None;
+                        // init and restart the master_down timer
+                        vr.timers.master_down = vr.parameters.master_down();
+                        restart_timers(&mut vr, timer_tx, timer_vr, debug);
+                        // clear down flag (give the new master a full master_down interval)
+                        vr.flags.clear_down_flag();
+
+                        // release the virtual router's addresses
+                        release_master(&mut vr, fd, debug);
+
+                        // print information
+                        let action = match &event {
+                            Event::Maintenance(..) => "Maintenance mode enabled",
+                            _ => "Resigned",
+                        };
+                        print_vr_state(
+                            &debug,
+                            &vr,
+                            "Backup",
+                            format!(
+                                "{} by API user {}, changed from Master to Backup",
+                                action, user
+                            ),
+                        );
+                        // transition to Backup state
+                        States::Backup
+                    }
                     // event: if Shutdown event is received
                     Event::Shutdown => {
                         // print information
-                        let vip = vr.parameters.vip();
-                        print_debug(&debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, format!(
-                            "VR {}.{}.{}.{} for group {} on interface {} - Changed from Master to Down",
-                            vip[0], vip[1], vip[2], vip[3], vr.parameters.vrid(), vr.parameters.interface()
-                        ));
+                        print_vr_state(&debug, &vr, "Down", format!("Changed from Master to Down"));
                         // cancel the 'advert' timer
-                        vr.timers.advert = 0;
+                        vr.timers.advert = None;
                         // cancel master_down timer
                         vr.timers.master_down = -1.0;
                         // send ADVERTISEMENT with priority equal 0
                         vr.parameters.set_prio(0);
                         match vr.send_advertisement(fd, &debug) {
                             Ok(_) => (),
-                            Err(e) => eprintln!(
-                                "error(fsm): error while sending VRRP advertisement on interface {}: {}",
-                                vr.parameters.interface(),
-                                e
+                            Err(e) => print_error(
+                                debug,
+                                DEBUG_SRC_FSM,
+                                format!(
+                                    "error while sending VRRP advertisement on interface {}: {}",
+                                    vr.parameters.interface(),
+                                    e
+                                ),
                             ),
                         }
 
@@ -793,7 +1034,20 @@ pub fn fsm_run(
             }
         };
         // set end-of-loop state
+        let old_state = vr.current_state().to_string();
         vr.set_states(st);
+        // run the notify hooks upon state transitions
+        if vr.current_state() != old_state {
+            vr.notify_transition(&old_state);
+            // sync group members follow the Master/Backup transitions
+            if !forced {
+                if let Some(sg) = vr.parameters.sync_group() {
+                    if let Some(ev) = sync_event(&old_state, vr.current_state()) {
+                        sg.notify(tx, ev, debug);
+                    }
+                }
+            }
+        }
         // print debugging information
         print_debug(
             debug,
@@ -804,6 +1058,190 @@ pub fn fsm_run(
     }
 }
 
+// sync_event() function
+/// event sent to the sync group members upon a state transition, the members
+/// leave Master as soon as one of them does (to Backup, Init or Down)
+fn sync_event(from: &str, to: &str) -> Option<Event> {
+    match (from, to) {
+        ("Backup", "Master") => Some(Event::SyncMaster),
+        ("Master", _) => Some(Event::SyncBackup),
+        _ => None,
+    }
+}
+
+// remaining() function
+/// time remaining (in seconds) until a deadline, if it has not passed yet
+fn remaining(deadline: Option<Instant>) -> Option<f32> {
+    match deadline {
+        Some(t) => {
+            let now = Instant::now();
+            if t > now {
+                Some((t - now).as_millis() as f32 / 1000.0)
+            } else {
+                None
+            }
+        }
+        None => None,
+    }
+}
+
+// release_master() function
+/// restore the interface's MAC address and addresses of a virtual router leaving the Master state
+fn release_master(vr: &mut VirtualRouter, fd: i32, debug: &Verbose) {
+    // --- Linux specific interface tyoe handling
+    #[cfg(target_os = "linux")]
+    // restore primary or delete vip on vr's interface
+    match vr.parameters.iftype() {
+        IfTypes::macvlan => {
+            // removes macvlan interface
+            vr.setup_macvlan_link(vr.parameters.ifmac(), Operation::Rem, debug);
+            // restore back vif and physical interfaces
+            let vif = vr.parameters.interface();
+            let phys = vr.parameters.vifname();
+            vr.parameters.set_vifname(vif);
+            vr.parameters.set_interface(phys);
+            // remove added routes
+            vr.set_ip_routes(fd, Operation::Rem, debug);
+        }
+        _ => {
+            // restore interface's MAC address
+            vr.set_mac_addresses(fd, vr.parameters.ifmac(), debug);
+            match vr.parameters.netdrv() {
+                NetDrivers::ioctl => {
+                    // restore primary IP
+                    #[cfg(target_os = "linux")]
+                    vr.set_ip_addresses(fd, Operation::Rem, debug);
+                    // re-set routes
+                    vr.set_ip_routes(fd, Operation::Add, debug);
+                }
+                NetDrivers::libnl => {
+                    // delete vip
+                    vr.delete_ip_addresses(fd, debug);
+                }
+            }
+        }
+    }
+    // END Linux specific interface type handling
+
+    // -- FreeBSD specific interface type handling
+    #[cfg(target_os = "freebsd")]
+    {
+        // we don't have to re-set the mac address here
+        // delete the VIP
+        vr.delete_ip_addresses(fd, debug);
+    }
+    // END FreeBSD specific interface type handling
+}
+
+// restart_timers() function
+/// start a new timers generation with the current intervals,
+/// the running timers stop on their next expiry
+fn restart_timers(
+    vr: &mut VirtualRouter,
+    timer_tx: Arc<Mutex<mpsc::Sender<Event>>>,
+    timer_vr: Arc<RwLock<VirtualRouter>>,
+    debug: &Verbose,
+) {
+    vr.timers.renew();
+    let epoch = vr.timers.epoch();
+    let d = debug.clone();
+    let _timer_thread = thread::spawn(move || {
+        timers::start_timers(timer_tx, timer_vr, epoch, &d);
+    });
+}
+
+// handle_reconfigure() function
+/// restart the running timers of a virtual router whose parameters have been reloaded
+fn handle_reconfigure(
+    vr: &mut VirtualRouter,
+    timer_tx: Arc<Mutex<mpsc::Sender<Event>>>,
+    timer_vr: Arc<RwLock<VirtualRouter>>,
+    fd: i32,
+    debug: &Verbose,
+) {
+    // re-init the running timer
+    match vr.get_states() {
+        States::Master => vr.timers.advert = Some(vr.parameters.adverint()),
+        States::Backup => vr.timers.master_down = vr.parameters.master_down(),
+        _ => return,
+    }
+
+    // print debugging information
+    print_debug(
+        debug,
+        DEBUG_LEVEL_HIGH,
+        DEBUG_SRC_FSM,
+        format!(
+            "restarting timers of reconfigured virtual router for group {}",
+            vr.parameters.vrid()
+        ),
+    );
+    // restart the timers with the new intervals
+    restart_timers(vr, timer_tx, timer_vr, debug);
+
+    // a master immediately advertises its new priority
+    if let States::Master = vr.get_states() {
+        match vr.send_advertisement(fd, debug) {
+            Ok(_) => (),
+            Err(e) => print_error(
+                debug,
+                DEBUG_SRC_FSM,
+                format!(
+                    "error while sending VRRP advertisement on interface {}: {}",
+                    vr.parameters.interface(),
+                    e
+                ),
+            ),
+        }
+    }
+}
+
+// handle_track_change() function
+/// update the priority of a virtual router upon a tracked object state change
+fn handle_track_change(vr: &mut VirtualRouter, state: &str, fd: i32, debug: &Verbose) {
+    // print information
+    print_vr(debug, vr, state.to_string());
+
+    // the address owner (255) and a shutting down virtual router (0)
+    // keep their priority
+    let prio = vr.parameters.prio();
+    if prio == 255 || prio == 0 {
+        return;
+    }
+
+    let new_prio = vr.parameters.tracked_prio();
+    if new_prio == prio {
+        return;
+    }
+    vr.parameters.update_prio(new_prio);
+    vr.publish(events::EventKind::Priority {
+        from: prio,
+        to: new_prio,
+    });
+    print_vr(
+        debug,
+        vr,
+        format!("Priority changed from {} to {}", prio, new_prio),
+    );
+
+    // a master immediately advertises its new priority
+    match vr.get_states() {
+        States::Master => match vr.send_advertisement(fd, debug) {
+            Ok(_) => (),
+            Err(e) => print_error(
+                debug,
+                DEBUG_SRC_FSM,
+                format!(
+                    "error while sending VRRP advertisement on interface {}: {}",
+                    vr.parameters.interface(),
+                    e
+                ),
+            ),
+        },
+        _ => (),
+    }
+}
+
 // register_tx() function
 /// registers the virtual router sending channel
 fn register_tx(
@@ -831,6 +1269,10 @@ fn register_tx(
 
     // setting up the notification tx channel
     vr.parameters.set_notification(Arc::clone(tx));
+    // join the virtual router's sync group
+    if let Some(sg) = vr.parameters.sync_group() {
+        sg.register(tx);
+    }
     // print debugging information
     print_debug(
         debug,
@@ -842,13 +1284,45 @@ fn register_tx(
 
 // is_primary_higher() function
 /// return a boolean true if the primary address is higher than local
-fn is_primary_higher(primary: &[u8; 4], local: &[u8; 4]) -> bool {
-    let mut result = false;
-    // do a per byte comparison, and returns true if primary's is higher
-    for (i, b) in primary.iter().enumerate() {
-        if *b > local[i] {
-            result = true;
+fn is_primary_higher(primary: &IpAddr, local: &IpAddr) -> bool {
+    // addresses of the same family are compared as unsigned integers
+    primary > local
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn sync_group_follows_master_exit() {
+        let debug = Verbose::new(0, 0, 0);
+        let sg = threads::SyncGroup::new("sg".to_string());
+        let (tx_a, rx_a) = mpsc::channel();
+        let (tx_b, rx_b) = mpsc::channel();
+        let tx_a = Arc::new(Mutex::new(tx_a));
+        let tx_b = Arc::new(Mutex::new(tx_b));
+        sg.register(&tx_a);
+        sg.register(&tx_b);
+
+        // a member going from Master to Init (shutdown) releases the others
+        let ev = sync_event("Master", "Init").unwrap();
+        sg.notify(&tx_a, ev, &debug);
+        match rx_b.try_recv() {
+            Ok(Event::SyncBackup) => (),
+            r => panic!("unexpected sync group notification: {:?}", r),
         }
+        assert!(rx_a.try_recv().is_err());
+
+        assert!(match sync_event("Master", "Down") {
+            Some(Event::SyncBackup) => true,
+            _ => false,
+        });
+        assert!(match sync_event("Backup", "Master") {
+            Some(Event::SyncMaster) => true,
+            _ => false,
+        });
+        assert!(sync_event("Init", "Backup").is_none());
+        assert!(sync_event("Backup", "Init").is_none());
     }
-    result
 }
@@ -0,0 +1,168 @@
+//! notification hooks module
+//! This module runs the notify hooks upon virtual routers state transitions.
+use super::*;
+
+// std
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicUsize;
+use std::time::{Duration, Instant};
+
+// channels
+use std::sync::mpsc;
+
+// debugging
+use crate::debug::{print_debug, print_error, Verbose};
+
+// number of queued hooks that have not completed yet
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+// hooks polling interval (in milliseconds)
+const HOOKS_TICK_MS: u64 = 100;
+
+/// State Transition Structure
+#[derive(Debug)]
+pub struct Transition {
+    pub group: u8,
+    pub interface: String,
+    pub vif: Option<String>,
+    pub vip: String,
+    pub old_state: String,
+    pub new_state: String,
+}
+
+/// Notifier Structure
+/// Hooks run in order in the notifier's own thread, so the
+/// worker thread only has to queue the transition
+#[derive(Debug)]
+pub struct Notifier {
+    tx: Mutex<mpsc::Sender<Transition>>,
+    hooks: usize,
+}
+
+// Notifier Type Implementation
+impl Notifier {
+    // new() method
+    // spawn the notifier thread running the hooks
+    pub fn new(hooks: Vec<String>, timeout: u32, debug: &Verbose) -> Notifier {
+        let (tx, rx) = mpsc::channel::<Transition>();
+        let debug = debug.clone();
+        let count = hooks.len();
+        thread::spawn(move || {
+            for t in rx.iter() {
+                for hook in &hooks {
+                    run_hook(hook, &t, timeout, &debug);
+                    PENDING.fetch_sub(1, Ordering::SeqCst);
+                }
+            }
+        });
+        Notifier {
+            tx: Mutex::new(tx),
+            hooks: count,
+        }
+    }
+    // notify() method
+    // queue a state transition
+    pub fn notify(&self, transition: Transition) {
+        PENDING.fetch_add(self.hooks, Ordering::SeqCst);
+        if self.tx.lock().unwrap().send(transition).is_err() {
+            PENDING.fetch_sub(self.hooks, Ordering::SeqCst);
+        }
+    }
+}
+
+// run_hook() function
+/// run a notify hook, and kill it if it did not complete before the timeout
+fn run_hook(hook: &str, t: &Transition, timeout: u32, debug: &Verbose) {
+    // print debugging information
+    print_debug(
+        debug,
+        DEBUG_LEVEL_HIGH,
+        DEBUG_SRC_HOOK,
+        format!(
+            "running notify hook {} for group {} on interface {} ({} to {})",
+            hook, t.group, t.interface, t.old_state, t.new_state
+        ),
+    );
+
+    let mut cmd = Command::new(hook);
+    cmd.arg(t.group.to_string())
+        .arg(&t.interface)
+        .arg(&t.vip)
+        .arg(&t.old_state)
+        .arg(&t.new_state)
+        .env("RVRRPD_GROUP", t.group.to_string())
+        .env("RVRRPD_INTERFACE", &t.interface)
+        .env("RVRRPD_VIP", &t.vip)
+        .env("RVRRPD_OLD_STATE", &t.old_state)
+        .env("RVRRPD_NEW_STATE", &t.new_state);
+    // the virtual interface of a macvlan virtual router
+    if let Some(vif) = &t.vif {
+        cmd.arg(vif).env("RVRRPD_VIF", vif);
+    }
+    let mut child = match cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            print_error(
+                debug,
+                DEBUG_SRC_HOOK,
+                format!("cannot run notify hook {}: {}", hook, e),
+            );
+            return;
+        }
+    };
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_HOOK,
+                        format!("notify hook {} exited with {}", hook, status),
+                    );
+                }
+                return;
+            }
+            Ok(None) => {
+                if started.elapsed() >= Duration::from_secs(timeout as u64) {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_HOOK,
+                        format!(
+                            "notify hook {} timed out after {}s, killing it",
+                            hook, timeout
+                        ),
+                    );
+                    let _r = child.kill();
+                    let _r = child.wait();
+                    return;
+                }
+                thread::sleep(Duration::from_millis(HOOKS_TICK_MS));
+            }
+            Err(e) => {
+                print_error(
+                    debug,
+                    DEBUG_SRC_HOOK,
+                    format!("error while waiting for notify hook {}: {}", hook, e),
+                );
+                return;
+            }
+        }
+    }
+}
+
+// wait_pending() function
+/// wait for the queued hooks to complete, each of them being killed after the timeout
+pub fn wait_pending(timeout: u32) {
+    let max = Duration::from_secs(PENDING.load(Ordering::SeqCst) as u64 * timeout as u64 + 1);
+    let started = Instant::now();
+    while PENDING.load(Ordering::SeqCst) > 0 && started.elapsed() < max {
+        thread::sleep(Duration::from_millis(HOOKS_TICK_MS));
+    }
+}
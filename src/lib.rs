@@ -52,7 +52,7 @@ use constants::*;
 
 // VRRP data structure
 mod packets;
-use packets::VRRPpkt;
+use packets::{VRRPpkt, VRRPpkt6};
 
 // operating systems support
 mod os;
@@ -71,14 +71,17 @@ use os::freebsd::libc::read_bpf_buf;
 #[cfg(target_os = "linux")]
 use os::linux::filter::{SockFilter, SockFprog};
 #[cfg(target_os = "linux")]
-use os::linux::libc::{open_raw_socket_fd, recv_ip_pkts, set_sock_filter};
+use os::linux::libc::{open_raw_socket6_fd, open_raw_socket_fd, recv_ip_pkts, set_sock_filter};
 
 // finite state machine
 mod fsm;
 
 // application programming interface
 mod api;
-use api::client::{capi_start_app, DownstreamAPI, FSMQueryResult, UpstreamAPI};
+use api::client::{
+    capi_start_app, capi_start_local, capi_start_metrics, DownstreamAPI, FSMQueryResult,
+    UpstreamAPI,
+};
 
 // checksums
 mod checksums;
@@ -86,7 +89,28 @@ mod checksums;
 // timers
 mod timers;
 
+// track scripts
+mod scripts;
+
+// notify hooks
+mod hooks;
+
+// virtual routers events
+mod events;
+
+// SNMP AgentX subagent
+mod snmp;
+
+// advertisements and transitions statistics
+mod stats;
+use stats::DropReason;
+
+// configuration reload
+#[cfg(target_os = "linux")]
+mod reload;
+
 // channels and threads
+use std::net::IpAddr;
 use std::sync::mpsc;
 use std::sync::RwLock;
 use std::sync::{Arc, Mutex};
@@ -101,7 +125,7 @@ use config::decode_config;
 
 // virtual routers
 mod vrouter;
-use vrouter::VirtualRouter;
+use vrouter::{is_ipv6_linklocal, VirtualRouter};
 
 // protocols
 #[allow(dead_code)] // not supported on freebsd yet
@@ -114,7 +138,7 @@ use auth::gen_auth_data;
 
 // debug
 mod debug;
-use debug::{print_debug, Verbose};
+use debug::{print_debug, print_error, Backend, Verbose};
 
 // std
 #[cfg(target_os = "freebsd")]
@@ -224,6 +248,277 @@ fn setup_signal_handler() -> Arc<AtomicBool> {
     flag
 }
 
+// configuration reload flag, set upon SIGHUP
+#[cfg(target_os = "linux")]
+static RELOAD: AtomicBool = AtomicBool::new(false);
+
+// setup_reload_handler function
+/// Setup a signal handler for SIGHUP signals, requesting a configuration reload
+/// (must be called after setup_signal_handler(), which also catches SIGHUP)
+#[cfg(target_os = "linux")]
+fn setup_reload_handler() {
+    extern "C" fn handler(_signum: libc::c_int) {
+        RELOAD.store(true, Ordering::Relaxed);
+    }
+    unsafe {
+        libc::signal(libc::SIGHUP, handler as libc::sighandler_t);
+    }
+}
+
+// build_protocols() function
+/// Build the internal protocols structure from the configuration
+fn build_protocols(config: &config::CConfig) -> Protocols {
+    // initialize internal protocols structure
+    let mut protocols = Protocols { r#static: None };
+
+    // read protocols configuration (if any)
+    match &config.protocols {
+        // if protocols config definition exists
+        Some(proto) => {
+            match &proto.r#static {
+                // if static routes exists
+                Some(st) => {
+                    let mut static_vec: Vec<Static> = Vec::with_capacity(st.len());
+                    for s in st {
+                        // push static route to the fixed-size vector
+                        static_vec.push(build_static(s));
+                    }
+                    // set static routes
+                    protocols.r#static = Some(static_vec);
+                }
+                None => {}
+            }
+        }
+        None => {}
+    }
+
+    protocols
+}
+
+// build_static() function
+/// Build the internal static route structure from its configuration
+fn build_static(s: &config::Static) -> Static {
+    Static::new(s.route(), s.mask(), s.nh(), s.metric(), s.mtu())
+}
+
+// new_vrouter() function
+/// Create a virtual router from its configuration, along with its notify hooks
+/// and sync group (created if it does not exist yet)
+fn new_vrouter(
+    config: &config::CConfig,
+    vr: &config::VRConfig,
+    protocols: &Arc<Mutex<Protocols>>,
+    sync_groups: &mut Vec<Arc<threads::SyncGroup>>,
+    debug: &Verbose,
+) -> io::Result<VirtualRouter> {
+    // create new virtual router structure
+    let mut nvr = VirtualRouter::new(
+        vr.group(),
+        vr.interface().to_string(),
+        vr.priority(),
+        vr.vips(),
+        vr.timer_advert(),
+        vr.preemption(),
+        vr.preempt_delay(),
+        vr.startup_delay(),
+        vr.rfc3768(),
+        vr.version(),
+        vr.auth_type(),
+        vr.auth_secret().clone(),
+        Arc::clone(protocols),
+        debug,
+        vr.netdrv(),
+        vr.iftype(),
+        vr.vifname(),
+        -1,
+        vr.socket_filter(),
+        vr.track_interface(),
+        vr.track_script()
+            .into_iter()
+            .map(|n| {
+                let w = config.track_script_weight(&n);
+                (n, w)
+            })
+            .collect(),
+        vr.unicast_peers(),
+        vr.unicast_src(),
+        vr.check_ttl(),
+    )?;
+
+    // global then virtual router's notify hooks
+    let hooks: Vec<String> = config.notify().into_iter().chain(vr.notify()).collect();
+    if !hooks.is_empty() {
+        nvr.parameters
+            .set_notifier(hooks::Notifier::new(hooks, config.notify_timeout(), debug));
+    }
+
+    // join the virtual router's sync group
+    if let Some(name) = config.sync_group_of(vr.group(), vr.interface()) {
+        nvr.parameters.set_sync_group(sync_group(sync_groups, name));
+    }
+
+    Ok(nvr)
+}
+
+// sync_group() function
+/// Return the named sync group, created if it does not exist yet
+fn sync_group(
+    sync_groups: &mut Vec<Arc<threads::SyncGroup>>,
+    name: String,
+) -> Arc<threads::SyncGroup> {
+    match sync_groups.iter().find(|sg| *sg.name() == name) {
+        Some(sg) => Arc::clone(sg),
+        None => {
+            let sg = Arc::new(threads::SyncGroup::new(name));
+            sync_groups.push(Arc::clone(&sg));
+            sg
+        }
+    }
+}
+
+// setup_vr_socket() function
+/// Open the virtual router's sockets and set its interface in promiscuous mode
+#[cfg(target_os = "linux")]
+fn setup_vr_socket(vr: &mut VirtualRouter, capi: Option<&UpstreamAPI>) -> io::Result<()> {
+    // open vr's raw socket (IPv4 or IPv6)
+    let sock_fd = if vr.parameters.is_ipv6() {
+        open_raw_socket6_fd()?
+    } else {
+        open_raw_socket_fd()?
+    };
+
+    // set BPF socket filter if enabled
+    if vr.parameters.socket_filter() {
+        let filter: [SockFilter; 10] = if vr.parameters.is_ipv6() {
+            SockFilter::new_vrrpv3_ipv6_gid(vr.parameters.vrid())
+        } else {
+            SockFilter::new_vrrpv2_gid(vr.parameters.vrid())
+        };
+        let bpf_fprog = SockFprog::build_fprog_vrrpv2_gid(&filter);
+        set_sock_filter(sock_fd, &bpf_fprog)?;
+    }
+
+    // wake up the listener thread periodically, so it notices its
+    // virtual router being removed by a configuration reload
+    os::linux::libc::set_sock_rcvtimeo(sock_fd, 1)?;
+
+    // convert interface string
+    let iface = CString::new(vr.parameters.interface().as_bytes() as &[u8]).unwrap();
+
+    // set interface is promiscuous mode
+    os::linux::netdev::set_if_promiscuous(sock_fd, &iface, PflagOp::Set)?;
+
+    // store raw socket file descriptor
+    vr.parameters.set_fd(sock_fd);
+
+    // open the unicast advertisements socket
+    if !vr.parameters.unicast_peers().is_empty() {
+        let unicast_fd = os::linux::libc::open_unicast_socket_fd(
+            &vr.parameters.interface(),
+            vr.parameters.unicast_src(),
+        )?;
+        vr.parameters.set_unicast_fd(unicast_fd);
+    }
+
+    // if enabled, set downstream client API sender and receiver channels
+    match capi {
+        Some(c) => {
+            let (s, r) = c.channels();
+            vr.parameters.set_capi_tx(s);
+            vr.parameters.set_capi_rx(r);
+        }
+        None => (),
+    }
+
+    Ok(())
+}
+
+// spawn_listener() function
+/// Spawn the thread receiving the virtual router's VRRP packets
+#[cfg(target_os = "linux")]
+fn spawn_listener(vr: &Arc<RwLock<VirtualRouter>>, debug: &Verbose) {
+    // get vr's socket descriptor
+    let vro = vr.read().unwrap();
+    let sock_fd = vro.parameters.fd();
+    // IPv6 virtual routers use a distinct packet verification
+    let is_ipv6 = vro.parameters.is_ipv6();
+
+    // create single item vrouters vector
+    let mut vrouters: Vec<Arc<RwLock<VirtualRouter>>> = Vec::new();
+    vrouters.push(vr.clone());
+    let debug = debug.clone();
+
+    thread::spawn(move || {
+        // initialize packet buffer
+        let mut pkt_buf: [u8; 1024] = [0; 1024];
+
+        // initialize sockaddr and packet buffer
+        let mut sockaddr: sockaddr_ll = unsafe { mem::zeroed() };
+
+        loop {
+            // Block on receiving IP packets
+            match recv_ip_pkts(sock_fd, &mut sockaddr, &mut pkt_buf) {
+                Ok(len) => {
+                    // create and initialize pkg_hdr
+                    let mut pkt_hdr = PktHdr::new();
+                    // set inbound interface's ifindex)
+                    pkt_hdr.in_ifidx = sockaddr.sll_ifindex;
+                    let verified = if is_ipv6 {
+                        verify_vrrp6_pkt(&pkt_hdr, &pkt_buf[0..len], &vrouters, &debug)
+                    } else {
+                        verify_vrrp_pkt(sock_fd, &pkt_hdr, &pkt_buf[0..len], &vrouters, &debug)
+                    };
+                    match verified {
+                        Some((ifindex, vrid, ipsrc, advert_prio, advert_int)) => {
+                            handle_vrrp_advert(
+                                &vrouters,
+                                ifindex,
+                                vrid,
+                                ipsrc,
+                                advert_prio,
+                                advert_int,
+                                &debug,
+                            );
+                        }
+                        _ => (),
+                    }
+                }
+                Err(_e) => (),
+            }
+
+            // exit once the virtual router released its socket (configuration reload)
+            if vrouters[0].read().unwrap().parameters.fd() != sock_fd {
+                os::linux::libc::close_fd(sock_fd);
+                break;
+            }
+        }
+    });
+}
+
+// check_config() function
+/// Validate the configuration file and print its errors and warnings
+///
+/// Returns true if the configuration has no error
+pub fn check_config(cfg: &Config) -> bool {
+    let config = match config::read_config(cfg.conf(), cfg.cfg_format()) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error(config): {}", e);
+            return false;
+        }
+    };
+    let issues = config.validate();
+    let ok = config::report_issues(&issues);
+    let errors = issues.iter().filter(|i| i.is_error()).count();
+    println!(
+        "{}: {} error(s), {} warning(s)",
+        cfg.conf(),
+        errors,
+        issues.len() - errors
+    );
+    ok
+}
+
 // listen_ip_pkts() function
 /// Listen for IP packets
 ///
@@ -377,6 +672,11 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
             // read configuration file
             let config = decode_config(cfg.conf(), cfg.cfg_format());
 
+            // validate the configuration, reporting all its errors at once
+            if !config::report_issues(&config.validate()) {
+                std::process::exit(1);
+            }
+
             // read debugging level from Config first
             let debug_level = match cfg.debug() {
                 Some(v) => v,
@@ -386,9 +686,16 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
 
             // initialize 'debug' variable of type Verbose
             // and pass time format string from configuration file
-            let debug: Verbose =
+            let mut debug: Verbose =
                 Verbose::new(debug_level, config.time_zone(), config.time_format());
 
+            // override the debug level of the sources configured in the logging section
+            if let Some(logging) = &config.logging {
+                for (src, level) in logging.levels() {
+                    debug.set_level(&src, level);
+                }
+            }
+
             // if the mode is 2, then daemonize:
             if cfg.mode == 2 {
                 // create log files
@@ -411,41 +718,36 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
                 }
             }
 
+            // open the logging backend (after the daemon forked)
+            if let Some(logging) = &config.logging {
+                let backend = match &logging.backend()[..] {
+                    "syslog" => Backend::syslog(&logging.syslog_socket(), logging.facility()),
+                    "journald" => Backend::journald(),
+                    "json" => Backend::json(&logging.json_file()),
+                    _ => Ok(Backend::Stdout),
+                };
+                match backend {
+                    Ok(b) => debug::set_backend(b),
+                    Err(e) => print_error(
+                        &debug,
+                        DEBUG_SRC_MAIN,
+                        format!(
+                            "cannot open the {} logging backend: {}",
+                            logging.backend(),
+                            e
+                        ),
+                    ),
+                }
+            }
+
             // setup signal handler for the possibly forked process
             let shutdown = setup_signal_handler();
 
             // initialize the virtual router vector
             let mut vrouters: Vec<Arc<RwLock<VirtualRouter>>> = Vec::new();
 
-            // initialize internal protocols structure
-            let mut protocols = Protocols { r#static: None };
-
             // read protocols configuration (if any)
-            match &config.protocols {
-                // if protocols config definition exists
-                Some(proto) => {
-                    match &proto.r#static {
-                        // if static routes exists
-                        Some(st) => {
-                            let mut static_vec: Vec<Static> = Vec::with_capacity(st.len());
-                            for s in st {
-                                // push static route to the fixed-size vector
-                                static_vec.push(Static::new(
-                                    s.route(),
-                                    s.mask(),
-                                    s.nh(),
-                                    s.metric(),
-                                    s.mtu(),
-                                ));
-                            }
-                            // set static routes
-                            protocols.r#static = Some(static_vec);
-                        }
-                        None => {}
-                    }
-                }
-                None => {}
-            }
+            let protocols = build_protocols(&config);
             // print debugging information
             print_debug(
                 &debug,
@@ -462,101 +764,99 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
             let vcvr = match &config.vrouter {
                 Some(vr) => vr,
                 None => {
-                    eprintln!("error(main): no virtual router configured. exiting...");
+                    print_error(
+                        &debug,
+                        DEBUG_SRC_MAIN,
+                        format!("no virtual router configured. exiting..."),
+                    );
                     std::process::exit(1);
                 }
             };
 
+            // create the sync groups shared by their members
+            let mut sync_groups: Vec<Arc<threads::SyncGroup>> = config
+                .sync_groups()
+                .into_iter()
+                .map(|name| Arc::new(threads::SyncGroup::new(name)))
+                .collect();
+
             // create a new virtual router and push it into the 'vrouters' vector
             for vr in vcvr {
-                // clone protocols
-                let protocols = Arc::clone(&protocols);
-
-                // create new virtual router structure
-                match VirtualRouter::new(
-                    vr.group(),
-                    vr.interface().to_string(),
-                    vr.priority(),
-                    vr.vip(),
-                    vr.timer_advert(),
-                    vr.preemption(),
-                    vr.rfc3768(),
-                    vr.auth_type(),
-                    vr.auth_secret().clone(),
-                    protocols,
-                    &debug,
-                    vr.netdrv(),
-                    vr.iftype(),
-                    vr.vifname(),
-                    -1,
-                    vr.socket_filter(),
-                ) {
-                    Ok(vr) => {
-                        let vr = RwLock::new(vr);
-                        vrouters.push(Arc::new(vr));
-                    }
-                    Err(e) => return Err(e),
-                }
+                let nvr = new_vrouter(&config, vr, &protocols, &mut sync_groups, &debug)?;
+                vrouters.push(Arc::new(RwLock::new(nvr)));
             }
 
+            // track scripts run by the supervisor thread, replaced upon configuration reloads
+            let shared_scripts = Arc::new(RwLock::new(config.track_scripts()));
+
+            // time after which a notify hook is killed, waited for upon exit
+            let notify_timeout = config.notify_timeout();
+
+            // virtual routers and configuration shared with the other threads,
+            // replaced upon configuration reloads
+            let shared_vrouters = Arc::new(RwLock::new(vrouters.clone()));
+            let shared_config = Arc::new(RwLock::new(config.clone()));
+
+            // virtual routers creation and removal requested through the client API
+            let (ctl_tx, ctl_rx) = crossbeam::unbounded();
+
             // Initialize the Downstream Client API, spawn its thread and set its reference.
             let isClientAPIEnabled = config.client_api();
             let up_api = UpstreamAPI::new();
             let down_api = DownstreamAPI::new();
-            let capi: Option<&UpstreamAPI> = match isClientAPIEnabled {
-                true => {
-                    let host = config.api.as_ref().unwrap().host();
-                    let tls = config.api.as_ref().unwrap().tls();
-                    let tls_key = config.api.as_ref().unwrap().tls_key();
-                    let tls_cert = config.api.as_ref().unwrap().tls_cert();
-                    up_api.spawn_thread(&down_api, config, &vrouters);
-                    capi_start_app(down_api, host, tls, tls_key, tls_cert);
-                    Some(&up_api)
-                }
-                false => None,
-            };
+            let capi: Option<&UpstreamAPI> =
+                match isClientAPIEnabled || config.control_socket.is_some() {
+                    true => {
+                        up_api.spawn_thread(
+                            &down_api,
+                            &shared_config,
+                            &shared_vrouters,
+                            ctl_tx,
+                            &debug,
+                        );
+                        // local Unix socket, authorized by the peer credentials
+                        if let Some(cs) = &config.control_socket {
+                            capi_start_local(
+                                down_api.clone(),
+                                cs.path(),
+                                cs.mode(),
+                                cs.group(),
+                                &debug,
+                            );
+                        }
+                        if isClientAPIEnabled {
+                            let host = config.api.as_ref().unwrap().host();
+                            let tls = config.api.as_ref().unwrap().tls();
+                            let tls_key = config.api.as_ref().unwrap().tls_key();
+                            let tls_cert = config.api.as_ref().unwrap().tls_cert();
+                            let metrics_host = config.api.as_ref().unwrap().metrics_host();
+                            if let Some(metrics_host) = metrics_host {
+                                capi_start_metrics(shared_vrouters.clone(), metrics_host);
+                            }
+                            capi_start_app(down_api, host, tls, tls_key, tls_cert);
+                        }
+                        Some(&up_api)
+                    }
+                    false => None,
+                };
+
+            // spawn the SNMP AgentX subagent
+            if let Some(snmp) = &config.snmp {
+                let socket = snmp.agentx_socket();
+                let vrouters = Arc::clone(&shared_vrouters);
+                let debug = debug.clone();
+                thread::spawn(move || snmp::start_subagent(socket, vrouters, debug));
+            }
 
             // --- Linux specific handling
             #[cfg(target_os = "linux")]
             {
-                // set vr's interface(s) in promiscuous mode
-                for vr in &vrouters {
-                    // acquire write lock
-                    let mut vr = vr.write().unwrap();
-
-                    // open vr's raw socket
-                    let sock_fd = open_raw_socket_fd()?;
-
-                    // set BPF socket filter if enabled
-                    if vr.parameters.socket_filter() {
-                        let filter: [SockFilter; 10] =
-                            SockFilter::new_vrrpv2_gid(vr.parameters.vrid());
-                        let bpf_fprog = SockFprog::build_fprog_vrrpv2_gid(&filter);
-                        set_sock_filter(sock_fd, &bpf_fprog)?;
-                    }
+                // setup the configuration reload handler
+                setup_reload_handler();
 
-                    // convert interface string
-                    let iface =
-                        CString::new(vr.parameters.interface().as_bytes() as &[u8]).unwrap();
-
-                    // set interface is promiscuous mode
-                    match os::linux::netdev::set_if_promiscuous(sock_fd, &iface, PflagOp::Set) {
-                        Err(e) => return Err(e),
-                        _ => {}
-                    }
-
-                    // store raw socket file descriptor
-                    vr.parameters.set_fd(sock_fd);
-
-                    // if enabled, set downstream client API sender and receiver channels
-                    match capi {
-                        Some(c) => {
-                            let (s, r) = c.channels();
-                            vr.parameters.set_capi_tx(s);
-                            vr.parameters.set_capi_rx(r);
-                        }
-                        None => (),
-                    }
+                // open the vr's sockets and set their interface(s) in promiscuous mode
+                for vr in &vrouters {
+                    setup_vr_socket(&mut vr.write().unwrap(), capi)?;
                 }
 
                 // print debugging information
@@ -572,69 +872,110 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
 
                 // send Startup event to worker threads
                 std::thread::sleep(std::time::Duration::from_secs(1));
-                threads.startup(&vrouters, &debug);
 
-                // spawn a listener thread per virtual router
-                for vr in &vrouters {
-                    // get vr's socket descriptor
-                    let vro = &vr.read().unwrap();
-                    let sock_fd = vro.parameters.fd();
-
-                    // create single item vrouters vector
-                    let mut vrouters: Vec<Arc<RwLock<VirtualRouter>>> = Vec::new();
-                    vrouters.push(vr.clone());
+                // spawn the link state monitoring thread if any interface is tracked
+                let mut links_monitored = false;
+                if vrouters
+                    .iter()
+                    .any(|v| !v.read().unwrap().parameters.tracked().is_empty())
+                {
+                    let vrouters = Arc::clone(&shared_vrouters);
+                    let debug = debug.clone();
+                    thread::spawn(move || monitor_links(&vrouters, &debug));
+                    links_monitored = true;
+                }
 
+                // spawn the track scripts supervisor thread
+                let mut scripts_supervised = false;
+                if !shared_scripts.read().unwrap().is_empty() {
+                    let track_scripts = Arc::clone(&shared_scripts);
+                    let vrouters = Arc::clone(&shared_vrouters);
+                    let debug = debug.clone();
                     thread::spawn(move || {
-                        // initialize packet buffer
-                        let mut pkt_buf: [u8; 1024] = [0; 1024];
+                        scripts::start_supervisor(&track_scripts, &vrouters, &debug)
+                    });
+                    scripts_supervised = true;
+                }
 
-                        // initialize sockaddr and packet buffer
-                        let mut sockaddr: sockaddr_ll = unsafe { mem::zeroed() };
+                threads.startup(&vrouters, &debug);
 
-                        loop {
-                            // Block on receiving IP packets
-                            match recv_ip_pkts(sock_fd, &mut sockaddr, &mut pkt_buf) {
-                                Ok(len) => {
-                                    // create and initialize pkg_hdr
-                                    let mut pkt_hdr = PktHdr::new();
-                                    // set inbound interface's ifindex)
-                                    pkt_hdr.in_ifidx = sockaddr.sll_ifindex;
-                                    match verify_vrrp_pkt(
-                                        sock_fd,
-                                        &pkt_hdr,
-                                        &pkt_buf[0..len],
-                                        &vrouters,
-                                        &debug,
-                                    ) {
-                                        Some((ifindex, vrid, ipsrc, advert_prio)) => {
-                                            handle_vrrp_advert(
-                                                &vrouters,
-                                                ifindex,
-                                                vrid,
-                                                ipsrc,
-                                                advert_prio,
-                                                &debug,
-                                            );
-                                        }
-                                        _ => (),
-                                    }
-                                }
-                                Err(_e) => (),
-                            }
-                        }
-                    });
+                // spawn a listener thread per virtual router
+                for vr in &vrouters {
+                    spawn_listener(vr, &debug);
                 }
 
                 // main thread loop
                 loop {
-                    // call sleep() to avoid continuous high cpu usage
-                    std::thread::sleep(std::time::Duration::from_secs(5));
+                    // wait for the client API's changes (avoids continuous high cpu usage)
+                    let mut updated = match ctl_rx.recv_timeout(std::time::Duration::from_secs(1)) {
+                        Ok(ctl) => reload::apply_control(
+                            ctl,
+                            &mut vrouters,
+                            &mut threads,
+                            &protocols,
+                            &mut sync_groups,
+                            capi,
+                            &debug,
+                        ),
+                        Err(crossbeam::RecvTimeoutError::Timeout) => None,
+                        // the client API is gone, keep the loop from spinning
+                        Err(crossbeam::RecvTimeoutError::Disconnected) => {
+                            std::thread::sleep(std::time::Duration::from_secs(1));
+                            None
+                        }
+                    };
+
+                    // check if a configuration reload was requested (SIGHUP)
+                    if RELOAD.swap(false, Ordering::Relaxed) {
+                        if let Some(config) = reload::reload_config(
+                            cfg.conf(),
+                            cfg.cfg_format(),
+                            &mut vrouters,
+                            &mut threads,
+                            &protocols,
+                            &mut sync_groups,
+                            capi,
+                            &debug,
+                        ) {
+                            updated = Some(config);
+                        }
+                    }
+
+                    if let Some(config) = updated {
+                        // publish the new virtual routers, track scripts and configuration
+                        *shared_vrouters.write().unwrap() = vrouters.clone();
+                        *shared_scripts.write().unwrap() = config.track_scripts();
+                        *shared_config.write().unwrap() = config;
+                        // start monitoring the links if newly tracked
+                        if !links_monitored
+                            && vrouters
+                                .iter()
+                                .any(|v| !v.read().unwrap().parameters.tracked().is_empty())
+                        {
+                            let vrouters = Arc::clone(&shared_vrouters);
+                            let debug = debug.clone();
+                            thread::spawn(move || monitor_links(&vrouters, &debug));
+                            links_monitored = true;
+                        }
+                        // start supervising the track scripts if newly configured
+                        if !scripts_supervised && !shared_scripts.read().unwrap().is_empty() {
+                            let track_scripts = Arc::clone(&shared_scripts);
+                            let vrouters = Arc::clone(&shared_vrouters);
+                            let debug = debug.clone();
+                            thread::spawn(move || {
+                                scripts::start_supervisor(&track_scripts, &vrouters, &debug)
+                            });
+                            scripts_supervised = true;
+                        }
+                    }
 
                     // check if global shutdown variable is set
                     // if set, then call set_if_promiscuous() to remove promisc mode on interface
                     if shutdown.load(Ordering::Relaxed) {
                         // manually calling the threads pool destructor
                         threads.drop(&vrouters, &debug);
+                        // let the notify hooks of the last transitions complete
+                        hooks::wait_pending(notify_timeout);
 
                         for vr in &vrouters {
                             // acquire read lock
@@ -666,6 +1007,9 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
             // --- FreeBSD specific handling
             #[cfg(target_os = "freebsd")]
             {
+                // virtual routers are not created nor removed at runtime yet
+                drop(ctl_rx);
+
                 // initialize BPF buffer
                 let mut bpf_buf: [u8; 118] = [0; 118];
 
@@ -713,6 +1057,17 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
 
                 // send Startup event to worker threads
                 std::thread::sleep(std::time::Duration::from_secs(1));
+
+                // spawn the track scripts supervisor thread
+                if !shared_scripts.read().unwrap().is_empty() {
+                    let track_scripts = Arc::clone(&shared_scripts);
+                    let vrouters = Arc::clone(&shared_vrouters);
+                    let debug = debug.clone();
+                    thread::spawn(move || {
+                        scripts::start_supervisor(&track_scripts, &vrouters, &debug)
+                    });
+                }
+
                 threads.startup(&vrouters, &debug);
 
                 // start a BPF listener thread per virtual router
@@ -781,13 +1136,20 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
                                             &vrouters,
                                             &debug,
                                         ) {
-                                            Some((ifindex, vrid, ipsrc, advert_prio)) => {
+                                            Some((
+                                                ifindex,
+                                                vrid,
+                                                ipsrc,
+                                                advert_prio,
+                                                advert_int,
+                                            )) => {
                                                 handle_vrrp_advert(
                                                     &vrouters,
                                                     ifindex,
                                                     vrid,
                                                     ipsrc,
                                                     advert_prio,
+                                                    advert_int,
                                                     &debug,
                                                 );
                                             }
@@ -822,6 +1184,8 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
 
                         // Manually calling the threads pool destructor
                         threads.drop(&vrouters, &debug);
+                        // let the notify hooks of the last transitions complete
+                        hooks::wait_pending(notify_timeout);
                         std::process::exit(0);
                     }
                 }
@@ -835,6 +1199,59 @@ pub fn listen_ip_pkts(cfg: &Config) -> io::Result<()> {
     }
 }
 
+// publish_auth_failure() function
+/// send an advertisement's authentication failure to the client API's event streams
+fn publish_auth_failure(vr: &VirtualRouter, ipsrc: &[u8; 4], reason: &str) {
+    vr.publish(events::EventKind::AuthFailure {
+        source: IpAddr::from(*ipsrc).to_string(),
+        reason: reason.to_string(),
+    });
+}
+
+// count_drop() function
+/// count a dropped advertisement against the virtual router of its VRID on the
+/// receiving interface, or against every virtual router of the interface if none matches
+fn count_drop(
+    vrouters: &Vec<Arc<RwLock<VirtualRouter>>>,
+    ifindex: i32,
+    vrid: u8,
+    reason: DropReason,
+) {
+    let on_if: Vec<_> = vrouters
+        .iter()
+        .map(|v| v.read().unwrap())
+        .filter(|v| v.parameters.ifindex() == ifindex)
+        .collect();
+    match on_if.iter().find(|v| v.parameters.vrid() == vrid) {
+        Some(vr) => vr.stats.count_drop(reason),
+        None => on_if.iter().for_each(|vr| vr.stats.count_drop(reason)),
+    }
+}
+
+// verify_address_list() function
+/// count the advertisements missing an address of the virtual router, which are
+/// only logged (RFC3768 7.1) as the addresses are configured on both ends
+fn verify_address_list<T: AsRef<[u8]>>(
+    vr: &VirtualRouter,
+    addrs: &[u8],
+    vips: &[T],
+    debug: &Verbose,
+) {
+    let missing = vips.iter().any(|vip| {
+        let vip = vip.as_ref();
+        !addrs.chunks(vip.len()).any(|a| a == vip)
+    });
+    if missing {
+        print_debug(
+            debug,
+            DEBUG_LEVEL_MEDIUM,
+            DEBUG_SRC_MAIN,
+            format!("received a VRRP message with a non-matching address list"),
+        );
+        vr.stats.count_address_list_error();
+    }
+}
+
 // verify_vrrp_pkt() function
 /// Verify VRRPv2 ADVERTISEMENT packets (as per RFC3768 7.1)
 fn verify_vrrp_pkt(
@@ -843,9 +1260,9 @@ fn verify_vrrp_pkt(
     packet: &[u8],
     vrouters: &Vec<Arc<RwLock<VirtualRouter>>>,
     debug: &Verbose,
-) -> Option<(i32, u8, [u8; 4], u8)> {
-    // ignore packets that are too short (plus one IP address and auth. data. field)
-    if packet.len() < (mem::size_of::<VRRPpkt>() + 4 + 8) {
+) -> Option<(i32, u8, IpAddr, u8, u16)> {
+    // ignore packets that are too short (plus one IP address)
+    if packet.len() < (mem::size_of::<VRRPpkt>() + 4) {
         return None;
     }
 
@@ -857,18 +1274,44 @@ fn verify_vrrp_pkt(
         return None;
     }
 
-    // verify the IP ttl is 255
-    if *vrrp_pkt.ipttl() != IP_TTL_VRRP_MINTTL {
-        return None;
-    }
+    // verify the VRRP version is 0x2 or 0x3 and the message type is 0x1 (ADVERTISEMENT)
+    let version = match *vrrp_pkt.version() {
+        VRRP_V2_VER_TYPE_AUTHMSG => 2,
+        VRRP_V3_VER_TYPE_ADVERT => 3,
+        v => {
+            // a known version carrying another message type
+            let reason = match v >> 4 {
+                2 | 3 => DropReason::InvalidType,
+                _ => DropReason::Version,
+            };
+            count_drop(vrouters, pkt_hdr.in_ifidx, *vrrp_pkt.vrid(), reason);
+            return None;
+        }
+    };
 
-    // verify the VRRP version is 0x2 and the message type is 0x1 (ADVERTISEMENT)
-    if *vrrp_pkt.version() != VRRP_V2_VER_TYPE_AUTHMSG {
+    // ignore VRRPv2 packets that are too short (plus one IP address and auth. data. field)
+    if version == 2 && packet.len() < (mem::size_of::<VRRPpkt>() + 4 + 8) {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::PacketLength,
+        );
         return None;
     }
 
-    // compute the number of bytes to read for the IP addresses
+    // compute the number of bytes to read for the IP addresses,
+    // drop the packets whose length does not match the address count
     let ip_bcnt = (vrrp_pkt.s_addrcount(packet.len()) * 4) as usize;
+    if ip_bcnt == 0 {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::PacketLength,
+        );
+        return None;
+    }
 
     // construct a variable-size vector of u8 for the VRRP PDU
     let mut vrrp_pdu = Vec::new();
@@ -892,17 +1335,40 @@ fn verify_vrrp_pkt(
     vrrp_pdu.extend_from_slice(&ipaddrs);
 
     // read authentication data from packet buffer
-    // and extend vrrp_pdu vector to auth. data
-    let authdata = unsafe {
-        slice::from_raw_parts(
-            packet[ETHER_VRRP_IPADDR_POS + ip_bcnt..].as_ptr() as *const _,
-            8,
-        )
+    // and extend vrrp_pdu vector to auth. data (VRRPv2 only)
+    let authdata: &[u8] = match version {
+        2 => unsafe {
+            slice::from_raw_parts(
+                packet[ETHER_VRRP_IPADDR_POS + ip_bcnt..].as_ptr() as *const _,
+                8,
+            )
+        },
+        _ => &[],
     };
     vrrp_pdu.extend_from_slice(&authdata);
 
-    // verify the VRRP checksum (RFC1071)
-    if checksums::rfc1071(&vrrp_pdu) != 0xFFFF {
+    // verify the VRRP checksum (RFC1071),
+    // the VRRPv3 checksum also covers the IPv4 pseudo-header (RFC5798 5.2.8)
+    let checksum = match version {
+        3 => {
+            let mut data = checksums::ipv4_pseudo_header(
+                vrrp_pkt.ipsrc(),
+                vrrp_pkt.ipdst(),
+                IP_UPPER_PROTO_VRRP,
+                vrrp_pdu.len(),
+            );
+            data.extend_from_slice(&vrrp_pdu);
+            checksums::rfc1071(&data)
+        }
+        _ => checksums::rfc1071(&vrrp_pdu),
+    };
+    if checksum != 0xFFFF {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::Checksum,
+        );
         return None;
     }
 
@@ -918,17 +1384,91 @@ fn verify_vrrp_pkt(
             // first get read lock on vr's RwLock guard
             let vr = vr.read().unwrap();
 
+            // verify the message's version matches the configured version
+            // for this virtual router
+            if version != vr.parameters.version() || vr.parameters.is_ipv6() {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_MEDIUM,
+                    DEBUG_SRC_MAIN,
+                    format!(
+                        "received a VRRPv{} message for a VRRPv{} virtual router",
+                        version,
+                        vr.parameters.version()
+                    ),
+                );
+                vr.stats.count_drop(DropReason::Version);
+                return None;
+            }
+
+            // verify the IP ttl is 255 (unless disabled for routed unicast peers)
+            if vr.parameters.check_ttl() && *vrrp_pkt.ipttl() != IP_TTL_VRRP_MINTTL {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_MEDIUM,
+                    DEBUG_SRC_MAIN,
+                    format!("received a VRRP message with a TTL other than 255"),
+                );
+                vr.stats.count_drop(DropReason::Ttl);
+                return None;
+            }
+
+            // with unicast peers, only accept the advertisements of the configured peers
+            if !vr.parameters.unicast_peers().is_empty()
+                && !vr.parameters.unicast_peers().contains(vrrp_pkt.ipsrc())
+            {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_MEDIUM,
+                    DEBUG_SRC_MAIN,
+                    format!(
+                        "received a VRRP message from {:?}, which is not a unicast peer",
+                        vrrp_pkt.ipsrc()
+                    ),
+                );
+                vr.stats.count_drop(DropReason::UnicastPeer);
+                return None;
+            }
+
             // verify the destination address is not owned by the virtual router
-            if vr.parameters.ipaddrs().contains(vrrp_pkt.ipdst()) {
+            // (unicast advertisements are destined to a local address)
+            if vr.parameters.unicast_peers().is_empty()
+                && vr.parameters.ipaddrs().contains(vrrp_pkt.ipdst())
+            {
                 print_debug(
                     debug,
                     DEBUG_LEVEL_MEDIUM,
                     DEBUG_SRC_MAIN,
                     format!("received a VRRP message for an owned IP address"),
                 );
+                vr.stats.count_drop(DropReason::OwnedAddress);
                 return None;
             }
 
+            // VRRPv3 messages carry no authentication data and the advertisement
+            // interval of the master is learned, not verified (RFC5798 6.4.2)
+            if version == 3 {
+                // an advertisement interval of zero cannot be learned
+                if !vrrp_pkt.valid_max_adverint() {
+                    print_debug(
+                        debug,
+                        DEBUG_LEVEL_MEDIUM,
+                        DEBUG_SRC_MAIN,
+                        format!("received a VRRPv3 message with a zero advertisement interval"),
+                    );
+                    vr.stats.count_drop(DropReason::Adverint);
+                    return None;
+                }
+                verify_address_list(&vr, ipaddrs, &vr.parameters.vips4(), debug);
+                return Some((
+                    vr.parameters.ifindex(),
+                    vr.parameters.vrid(),
+                    IpAddr::from(*vrrp_pkt.ipsrc()),
+                    *vrrp_pkt.prio(),
+                    vrrp_pkt.max_adverint(),
+                ));
+            }
+
             // verify the authentication type matches the configured method
             // for this virtual router
             if *vrrp_pkt.authtype() != vr.parameters.authtype() {
@@ -938,6 +1478,8 @@ fn verify_vrrp_pkt(
                     DEBUG_SRC_MAIN,
                     format!("received a VRRP message with a non-matching authentication type"),
                 );
+                publish_auth_failure(&vr, vrrp_pkt.ipsrc(), "non-matching authentication type");
+                vr.stats.count_drop(DropReason::AuthType);
                 return None;
             }
 
@@ -963,6 +1505,8 @@ fn verify_vrrp_pkt(
                             DEBUG_SRC_AUTH,
                             format!("VRRP message authentication failed"),
                         );
+                        publish_auth_failure(&vr, vrrp_pkt.ipsrc(), "authentication failed");
+                        vr.stats.count_drop(DropReason::AuthFailure);
                         return None;
                     }
                 }
@@ -1005,6 +1549,8 @@ fn verify_vrrp_pkt(
                             DEBUG_SRC_AUTH,
                             format!("VRRP message authentication failed"),
                         );
+                        publish_auth_failure(&vr, vrrp_pkt.ipsrc(), "authentication failed");
+                        vr.stats.count_drop(DropReason::AuthFailure);
                         return None;
                     }
                 }
@@ -1012,24 +1558,30 @@ fn verify_vrrp_pkt(
                 _ => {}
             }
 
+            // verify the advertised addresses match the virtual router's addresses
+            verify_address_list(&vr, ipaddrs, &vr.parameters.vips4(), debug);
+
             // verify the message's 'avertint' field matches the locally
             // configured vr's advertisement interval
-            if *vrrp_pkt.adverint() != vr.parameters.adverint() {
+            if *vrrp_pkt.adverint() != vr.parameters.adverint_secs() {
                 print_debug(
                     debug,
                     DEBUG_LEVEL_MEDIUM,
                     DEBUG_SRC_MAIN,
                     format!("received a VRRP message with a non-matching advertisement interval"),
                 );
+                vr.stats.count_drop(DropReason::Adverint);
                 return None;
             }
 
-            // return the vr's ifindex, the vrid and advertisement's priority to the caller function
+            // return the vr's ifindex, the vrid, advertisement's priority
+            // and advertisement interval (in centiseconds) to the caller function
             Some((
                 vr.parameters.ifindex(),
                 vr.parameters.vrid(),
-                *vrrp_pkt.ipsrc(),
+                IpAddr::from(*vrrp_pkt.ipsrc()),
                 *vrrp_pkt.prio(),
+                *vrrp_pkt.adverint() as u16 * 100,
             ))
         }
         // if no matching virtual router exists, simply drop the VRRP message
@@ -1040,19 +1592,286 @@ fn verify_vrrp_pkt(
                 DEBUG_SRC_MAIN,
                 format!("received a VRRP message for a non-existing virtual router"),
             );
+            count_drop(
+                vrouters,
+                pkt_hdr.in_ifidx,
+                *vrrp_pkt.vrid(),
+                DropReason::UnknownVrid,
+            );
             return None;
         }
     }
 }
 
+// verify_vrrp6_pkt() function
+/// Verify a VRRPv3 over IPv6 packet
+#[cfg(target_os = "linux")]
+fn verify_vrrp6_pkt(
+    pkt_hdr: &PktHdr,
+    packet: &[u8],
+    vrouters: &Vec<Arc<RwLock<VirtualRouter>>>,
+    debug: &Verbose,
+) -> Option<(i32, u8, IpAddr, u8, u16)> {
+    // ignore packets that are too short (plus one IPv6 address)
+    if packet.len() < (mem::size_of::<VRRPpkt6>() + 16) {
+        return None;
+    }
+
+    // read the *possibly* VRRPv3 packet
+    let vrrp_pkt: VRRPpkt6 = unsafe { ptr::read(packet.as_ptr() as *const _) };
+
+    // filter out all IPv6 packets with next header not matching VRRP
+    if *vrrp_pkt.ipnexthdr() != IP_UPPER_PROTO_VRRP {
+        return None;
+    }
+
+    // verify the IPv6 hop limit is 255
+    if *vrrp_pkt.iphoplimit() != IP_V6_HOP_LIMIT_VRRP {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::Ttl,
+        );
+        return None;
+    }
+
+    // verify the IPv6 source address is link-local (RFC5798 5.1.2.2)
+    if !is_ipv6_linklocal(vrrp_pkt.ipsrc()) {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::Source,
+        );
+        return None;
+    }
+
+    // verify the VRRP version is 0x3 and the message type is 0x1 (ADVERTISEMENT)
+    if *vrrp_pkt.version() != VRRP_V3_VER_TYPE_ADVERT {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::Version,
+        );
+        return None;
+    }
+
+    // compute the number of bytes to read for the IPv6 addresses
+    let ip_bcnt = vrrp_pkt.s_addrcount(packet.len()) as usize * 16;
+    if ip_bcnt == 0 {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::PacketLength,
+        );
+        return None;
+    }
+
+    // VRRP PDU (header and IPv6 addresses) as found in the packet buffer
+    let vrrp_pdu = &packet[VRRP_V3_IPV6_FRAME_OFFSET..VRRP_V3_IPV6_ADDR_POS + ip_bcnt];
+
+    // verify the VRRP checksum, including the IPv6 pseudo-header (RFC5798 5.2.8)
+    let mut data = checksums::ipv6_pseudo_header(
+        vrrp_pkt.ipsrc(),
+        vrrp_pkt.ipdst(),
+        IP_UPPER_PROTO_VRRP,
+        vrrp_pdu.len(),
+    );
+    data.extend_from_slice(vrrp_pdu);
+    if checksums::rfc1071(&data) != 0xFFFF {
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::Checksum,
+        );
+        return None;
+    }
+
+    // an advertisement interval of zero cannot be learned
+    if !vrrp_pkt.valid_max_adverint() {
+        print_debug(
+            debug,
+            DEBUG_LEVEL_MEDIUM,
+            DEBUG_SRC_MAIN,
+            format!("received a VRRPv3 IPv6 message with a zero advertisement interval"),
+        );
+        count_drop(
+            vrouters,
+            pkt_hdr.in_ifidx,
+            *vrrp_pkt.vrid(),
+            DropReason::Adverint,
+        );
+        return None;
+    }
+
+    // verify there is an existing IPv6 vrouter (matching vrid) on the receiving interface
+    let ifb_vr = vrouters.iter().find(|&v| {
+        let v = v.read().unwrap();
+        (v.parameters.ifindex() == pkt_hdr.in_ifidx)
+            && (v.parameters.vrid() == *vrrp_pkt.vrid())
+            && v.parameters.is_ipv6()
+    });
+    match ifb_vr {
+        Some(vr) => {
+            let vr = vr.read().unwrap();
+            // verify the advertised addresses match the virtual router's addresses
+            verify_address_list(
+                &vr,
+                &packet[VRRP_V3_IPV6_ADDR_POS..VRRP_V3_IPV6_ADDR_POS + ip_bcnt],
+                &vr.parameters.vips6(),
+                debug,
+            );
+            // return the vr's ifindex, the vrid, advertisement's priority
+            // and advertisement interval (in centiseconds) to the caller function
+            Some((
+                vr.parameters.ifindex(),
+                vr.parameters.vrid(),
+                IpAddr::from(*vrrp_pkt.ipsrc()),
+                *vrrp_pkt.prio(),
+                vrrp_pkt.max_adverint(),
+            ))
+        }
+        // if no matching virtual router exists, simply drop the VRRP message
+        None => {
+            print_debug(
+                debug,
+                DEBUG_LEVEL_MEDIUM,
+                DEBUG_SRC_MAIN,
+                format!("received a VRRPv3 IPv6 message for a non-existing virtual router"),
+            );
+            count_drop(
+                vrouters,
+                pkt_hdr.in_ifidx,
+                *vrrp_pkt.vrid(),
+                DropReason::UnknownVrid,
+            );
+            return None;
+        }
+    }
+}
+
+// monitor_links() function
+/// Watch the tracked interfaces' link state through rtnetlink
+/// and notify the virtual routers tracking them
+#[cfg(target_os = "linux")]
+fn monitor_links(vrouters: &RwLock<Vec<Arc<RwLock<VirtualRouter>>>>, debug: &Verbose) {
+    // open the rtnetlink socket and request the initial link states
+    let fd = match os::linux::rtnetlink::open_link_socket() {
+        Ok(fd) => fd,
+        Err(e) => {
+            print_error(
+                debug,
+                DEBUG_SRC_MAIN,
+                format!(
+                    "cannot open rtnetlink socket, interface tracking disabled: {}",
+                    e
+                ),
+            );
+            return;
+        }
+    };
+    if let Err(e) = os::linux::rtnetlink::request_link_dump(fd) {
+        print_error(
+            debug,
+            DEBUG_SRC_MAIN,
+            format!("cannot request the interfaces link state: {}", e),
+        );
+    }
+
+    // initialize message buffer
+    let mut buf: [u8; 8192] = [0; 8192];
+    // consecutive receive errors
+    let mut errors = 0;
+
+    loop {
+        let events = match os::linux::rtnetlink::recv_link_events(fd, &mut buf) {
+            Ok(events) => {
+                errors = 0;
+                events
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            // the kernel dropped some notifications, request the link states again
+            Err(ref e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                print_error(
+                    debug,
+                    DEBUG_SRC_MAIN,
+                    format!("rtnetlink socket overrun, resynchronizing the link states"),
+                );
+                if let Err(e) = os::linux::rtnetlink::request_link_dump(fd) {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_MAIN,
+                        format!("cannot request the interfaces link state: {}", e),
+                    );
+                }
+                continue;
+            }
+            Err(e) => {
+                errors += 1;
+                if errors >= LINK_MONITOR_MAX_ERRORS {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_MAIN,
+                        format!(
+                            "cannot receive the rtnetlink messages, interface tracking disabled: {}",
+                            e
+                        ),
+                    );
+                    unsafe { libc::close(fd) };
+                    return;
+                }
+                print_error(
+                    debug,
+                    DEBUG_SRC_MAIN,
+                    format!("cannot receive the rtnetlink messages: {}", e),
+                );
+                // back off before the next attempt
+                std::thread::sleep(std::time::Duration::from_millis(
+                    LINK_MONITOR_BACKOFF_MS << (errors - 1),
+                ));
+                continue;
+            }
+        };
+        for (ifname, up) in events {
+            for vr in vrouters.read().unwrap().iter() {
+                let vr = vr.read().unwrap();
+                if !vr.parameters.is_tracking(&ifname) {
+                    continue;
+                }
+                // print debugging information
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_MEDIUM,
+                    DEBUG_SRC_MAIN,
+                    format!(
+                        "sending LinkState event notification for interface {}",
+                        ifname
+                    ),
+                );
+                if let Some(tx) = vr.parameters.notification() {
+                    let _r = tx
+                        .lock()
+                        .unwrap()
+                        .send(fsm::Event::LinkState(ifname.clone(), up));
+                }
+            }
+        }
+    }
+}
+
 // handle_vrrp_advert() function
-/// Handle VRRPv2 ADVERTISEMENT message
+/// Handle VRRPv2/v3 ADVERTISEMENT message
 fn handle_vrrp_advert(
     vrouters: &Vec<Arc<RwLock<VirtualRouter>>>,
     ifindex: i32,
     vrid: u8,
-    ipsrc: [u8; 4],
+    ipsrc: IpAddr,
     advert_prio: u8,
+    advert_int: u16,
     debug: &Verbose,
 ) {
     // print debugging information
@@ -1061,7 +1880,7 @@ fn handle_vrrp_advert(
         DEBUG_LEVEL_MEDIUM,
         DEBUG_SRC_MAIN,
         format!(
-            "got a valid VRRPv2 packet for VRID {} on if {}",
+            "got a valid VRRP packet for VRID {} on if {}",
             vrid, ifindex
         ),
     );
@@ -1077,6 +1896,10 @@ fn handle_vrrp_advert(
         Some(vr) => {
             // get read lock (again)
             let vr = vr.read().unwrap();
+            vr.stats.count_received();
+            if advert_prio == 0 {
+                vr.stats.count_prio_zero_received();
+            }
             // if the channel is registered, acquire lock and notify the VR of the message receipt
             match vr.parameters.notification() {
                 Some(tx) => {
@@ -1090,7 +1913,7 @@ fn handle_vrrp_advert(
                     // acquiring lock on sender channel
                     tx.lock()
                         .unwrap()
-                        .send(fsm::Event::Advert(ipsrc, advert_prio))
+                        .send(fsm::Event::Advert(ipsrc, advert_prio, advert_int))
                         .unwrap();
                     // print debugging information
                     print_debug(
@@ -1115,10 +1938,10 @@ fn handle_vrrp_advert(
 }
 
 // filter_vrrp_pkt() function
-/// Filter VRRPv2 packets for sniffing mode
+/// Filter VRRPv2/v3 packets for sniffing mode
 fn filter_vrrp_pkt(fd: i32, _pkt_hdr: &PktHdr, packet: &[u8]) {
-    // ignore packets that are way too short (plus auth. data. field)
-    if packet.len() < (mem::size_of::<VRRPpkt>() + 8) {
+    // ignore packets that are way too short
+    if packet.len() < mem::size_of::<VRRPpkt>() {
         return;
     }
 
@@ -1139,8 +1962,17 @@ fn filter_vrrp_pkt(fd: i32, _pkt_hdr: &PktHdr, packet: &[u8]) {
     }
 
     // perform VRRP sanity checks
-    // if VRRP version is not 2 and type is not advertisement (p/x 0b00100001)
-    if *vrrp_pkt.version() != VRRP_V2_VER_TYPE_AUTHMSG {
+    // if VRRP version is not 2 or 3 and type is not advertisement
+    // (p/x 0b00100001 or 0b00110001)
+    if *vrrp_pkt.version() != VRRP_V2_VER_TYPE_AUTHMSG
+        && *vrrp_pkt.version() != VRRP_V3_VER_TYPE_ADVERT
+    {
+        return;
+    }
+    let v3 = *vrrp_pkt.version() == VRRP_V3_VER_TYPE_ADVERT;
+
+    // ignore VRRPv2 packets that are too short (plus auth. data. field)
+    if !v3 && packet.len() < (mem::size_of::<VRRPpkt>() + 8) {
         return;
     }
 
@@ -1169,38 +2001,63 @@ fn filter_vrrp_pkt(fd: i32, _pkt_hdr: &PktHdr, packet: &[u8]) {
     vrrp_pdu.extend_from_slice(&ipaddrs);
 
     // read authentication data from packet buffer
-    // and extend vrrp_pdu vector to auth. data
-    let authdata = unsafe {
-        slice::from_raw_parts(
-            packet[ETHER_VRRP_IPADDR_POS + ip_bcnt..].as_ptr() as *const _,
-            8,
-        )
+    // and extend vrrp_pdu vector to auth. data (VRRPv2 only)
+    let authdata: &[u8] = if v3 {
+        &[]
+    } else {
+        unsafe {
+            slice::from_raw_parts(
+                packet[ETHER_VRRP_IPADDR_POS + ip_bcnt..].as_ptr() as *const _,
+                8,
+            )
+        }
     };
     vrrp_pdu.extend_from_slice(&authdata);
 
-    // verify result of the RFC1071 checksum
-    if checksums::rfc1071(&vrrp_pdu) != 0xFFFF {
+    // verify result of the RFC1071 checksum,
+    // including the IPv4 pseudo-header for VRRPv3
+    let checksum = if v3 {
+        let mut data = checksums::ipv4_pseudo_header(
+            vrrp_pkt.ipsrc(),
+            vrrp_pkt.ipdst(),
+            IP_UPPER_PROTO_VRRP,
+            vrrp_pdu.len(),
+        );
+        data.extend_from_slice(&vrrp_pdu);
+        checksums::rfc1071(&data)
+    } else {
+        checksums::rfc1071(&vrrp_pdu)
+    };
+    if checksum != 0xFFFF {
         println!(
             "VRRP message with invalid checksum {:#X} detected",
-            checksums::rfc1071(&vrrp_pdu)
+            checksum
         );
     }
 
-    // call show_vrrp_pkt() to handle VRRPv2 packets
+    // call show_vrrp_pkt() to handle VRRPv2/v3 packets
     show_vrrp_pkt(fd, &vrrp_pkt, ipaddrs, authdata);
 }
 
 // show_vrrp_pkt() function
-/// Display VRRPv2 packets
+/// Display VRRPv2/v3 packets
 fn show_vrrp_pkt(_fd: i32, vrrp_pkt: &VRRPpkt, ipaddrs: &[u8], _authdata: &[u8]) {
     // prints some fields
-    println!("VRRPv2 Packet:");
+    if *vrrp_pkt.version() == VRRP_V3_VER_TYPE_ADVERT {
+        println!("VRRPv3 Packet:");
+    } else {
+        println!("VRRPv2 Packet:");
+    }
     println!(" Version/Type: {:#2X}", vrrp_pkt.version());
     println!(" Virtual Router ID: {}", vrrp_pkt.vrid());
     println!(" Priority: {}", vrrp_pkt.prio());
     println!(" IP Address Count: {}", vrrp_pkt.addrcount());
-    println!(" Authentication Type: {:#2X}", vrrp_pkt.authtype());
-    println!(" Advertisement Interval: {}s", vrrp_pkt.adverint());
+    if *vrrp_pkt.version() == VRRP_V3_VER_TYPE_ADVERT {
+        println!(" Max Advertisement Interval: {}cs", vrrp_pkt.max_adverint());
+    } else {
+        println!(" Authentication Type: {:#2X}", vrrp_pkt.authtype());
+        println!(" Advertisement Interval: {}s", vrrp_pkt.adverint());
+    }
     println!(" VRRP Checksum: {:#X}", vrrp_pkt.checksum());
     println!(" IP Address(es):");
     for (a, b, c, d) in ipaddrs.into_iter().tuple_windows() {
@@ -117,6 +117,99 @@ impl SockFilter {
     }
 }
 
+// SockFilter implementation (IPv6)
+impl SockFilter {
+    // new_vrrpv3_ipv6_gid() method
+    //
+    // BPF Filter - VRRPv3 IPv6 Advertisement Packets:
+    // ldh      [12]
+    // jne      #0x86dd, drop
+    // ldb      [20]
+    // jneq     #0x70, drop
+    // ldb      [54]
+    // jneq     #0x31, drop
+    // ldb      [55]
+    // jneq     #0x1, drop
+    // ret      #-1
+    // drop:    ret #0
+    //
+    pub fn new_vrrpv3_ipv6_gid(gid: u8) -> [SockFilter; 10] {
+        let filter: [SockFilter; 10] = [
+            SockFilter {
+                // 001
+                code: 0x28,
+                jt: 0x0,
+                jf: 0x0,
+                k: 0x0000000c,
+            },
+            SockFilter {
+                // 002
+                code: 0x15,
+                jt: 0x0,
+                jf: 0x7,
+                k: 0x000086dd,
+            },
+            SockFilter {
+                // 003
+                code: 0x30,
+                jt: 0x0,
+                jf: 0x0,
+                k: 0x00000014,
+            },
+            SockFilter {
+                // 004
+                code: 0x15,
+                jt: 0x0,
+                jf: 0x5,
+                k: 0x00000070,
+            },
+            SockFilter {
+                // 005
+                code: 0x30,
+                jt: 0x0,
+                jf: 0x0,
+                k: 0x00000036,
+            },
+            SockFilter {
+                // 006
+                code: 0x15,
+                jt: 0x0,
+                jf: 0x3,
+                k: 0x00000031,
+            },
+            SockFilter {
+                // 007
+                code: 0x30,
+                jt: 0x0,
+                jf: 0x0,
+                k: 0x00000037,
+            },
+            SockFilter {
+                // 008
+                code: 0x15,
+                jt: 0x0,
+                jf: 0x1,
+                k: gid as u32, // replace by the group id
+            },
+            SockFilter {
+                // 009
+                code: 0x06,
+                jt: 0x0,
+                jf: 0x0,
+                k: 0xffffffff,
+            },
+            SockFilter {
+                // 010
+                code: 0x06,
+                jt: 0x0,
+                jf: 0x0,
+                k: 0000000000,
+            },
+        ];
+        filter
+    }
+}
+
 // SockFprog structure
 #[repr(C)]
 pub struct SockFprog {
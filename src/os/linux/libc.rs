@@ -2,7 +2,11 @@
 use crate::*;
 
 // std, libc, ffi
-use libc::{setsockopt, socket, AF_PACKET, SOCK_RAW, SOL_SOCKET, SO_ATTACH_FILTER};
+use libc::{
+    bind, close, in_addr, setsockopt, sockaddr_in, socket, timeval, AF_INET, AF_PACKET, IPPROTO_IP,
+    IP_TOS, IP_TTL, SOCK_RAW, SOL_SOCKET, SO_ATTACH_FILTER, SO_BINDTODEVICE, SO_RCVBUF,
+    SO_RCVTIMEO,
+};
 use std::ffi::CString;
 use std::io;
 use std::mem;
@@ -23,6 +27,124 @@ pub fn open_raw_socket_fd() -> io::Result<i32> {
     }
 }
 
+// open_raw_socket6_fd() function
+/// Open a raw AF_PACKET socket for IPv6
+pub fn open_raw_socket6_fd() -> io::Result<i32> {
+    unsafe {
+        // man 2 socket
+        // returns a file descriptor or -1 if error.
+        match socket(AF_PACKET, SOCK_RAW, ETHER_P_IPV6.to_be() as i32) {
+            -1 => Err(io::Error::last_os_error()),
+            fd => Ok(fd),
+        }
+    }
+}
+
+// open_unicast_socket_fd() function
+/// Open a raw IPv4 socket sending VRRP advertisements to unicast peers,
+/// bound to the virtual router's interface and optional source address
+pub fn open_unicast_socket_fd(ifname: &str, src: Option<[u8; 4]>) -> io::Result<i32> {
+    let fd = match unsafe { socket(AF_INET, SOCK_RAW, IP_UPPER_PROTO_VRRP as i32) } {
+        -1 => return Err(io::Error::last_os_error()),
+        fd => fd,
+    };
+
+    // the socket is never read from (the advertisements are received on the
+    // raw socket of the virtual router), queue as few packets as possible,
+    // then the TTL must be set to 255 and the DSCP to CS6
+    let rcvbuf: i32 = 0;
+    let ttl = IP_TTL_VRRP_MINTTL as i32;
+    let tos = IP_DSCP_CS6 as i32;
+    let iface = CString::new(ifname).unwrap();
+    unsafe {
+        if setsockopt(
+            fd,
+            SOL_SOCKET,
+            SO_RCVBUF,
+            &rcvbuf as *const _ as *const c_void,
+            mem::size_of::<i32>() as u32,
+        ) == -1
+            || setsockopt(
+                fd,
+                IPPROTO_IP,
+                IP_TTL,
+                &ttl as *const _ as *const c_void,
+                mem::size_of::<i32>() as u32,
+            ) == -1
+            || setsockopt(
+                fd,
+                IPPROTO_IP,
+                IP_TOS,
+                &tos as *const _ as *const c_void,
+                mem::size_of::<i32>() as u32,
+            ) == -1
+            || setsockopt(
+                fd,
+                SOL_SOCKET,
+                SO_BINDTODEVICE,
+                iface.as_ptr() as *const c_void,
+                iface.as_bytes_with_nul().len() as u32,
+            ) == -1
+        {
+            let e = io::Error::last_os_error();
+            close(fd);
+            return Err(e);
+        }
+    }
+
+    // source the advertisements from the configured address
+    if let Some(src) = src {
+        let sa = sockaddr_in {
+            sin_family: AF_INET as u16,
+            sin_port: 0,
+            sin_addr: in_addr {
+                s_addr: u32::from_ne_bytes(src),
+            },
+            sin_zero: [0; 8],
+        };
+        unsafe {
+            if bind(
+                fd,
+                &sa as *const sockaddr_in as *const libc::sockaddr,
+                mem::size_of::<sockaddr_in>() as u32,
+            ) == -1
+            {
+                let e = io::Error::last_os_error();
+                close(fd);
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(fd)
+}
+
+// unicast_sendto() function
+/// Send a VRRP PDU to a unicast peer, the IP header is built by the kernel
+pub fn unicast_sendto(sockfd: i32, dst: [u8; 4], pdu: &[u8]) -> io::Result<()> {
+    let sa = sockaddr_in {
+        sin_family: AF_INET as u16,
+        sin_port: 0,
+        sin_addr: in_addr {
+            s_addr: u32::from_ne_bytes(dst),
+        },
+        sin_zero: [0; 8],
+    };
+    unsafe {
+        match libc::sendto(
+            sockfd,
+            pdu.as_ptr() as *const c_void,
+            pdu.len(),
+            0,
+            &sa as *const sockaddr_in as *const libc::sockaddr,
+            mem::size_of::<sockaddr_in>() as u32,
+        ) {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
 // set_sock_filter function
 /// Set a BPF filter on a socket
 pub fn set_sock_filter(sockfd: i32, bpf: &SockFprog) -> io::Result<i32> {
@@ -41,6 +163,38 @@ pub fn set_sock_filter(sockfd: i32, bpf: &SockFprog) -> io::Result<i32> {
     }
 }
 
+// set_sock_rcvtimeo() function
+/// Set a receive timeout on a socket, so blocked readers wake up periodically
+pub fn set_sock_rcvtimeo(sockfd: i32, secs: u32) -> io::Result<()> {
+    let tv = timeval {
+        tv_sec: secs as libc::time_t,
+        tv_usec: 0,
+    };
+    unsafe {
+        // man 7 socket
+        match setsockopt(
+            sockfd,
+            SOL_SOCKET,
+            SO_RCVTIMEO,
+            &tv as *const _ as *const c_void,
+            mem::size_of::<timeval>() as u32,
+        ) {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// close_fd() function
+/// Close a socket file descriptor
+pub fn close_fd(sockfd: i32) {
+    if sockfd >= 0 {
+        unsafe {
+            close(sockfd);
+        }
+    }
+}
+
 // recv_ip_pkts() function
 /// Receive IP packets
 pub fn recv_ip_pkts(sockfd: i32, sockaddr: &mut sockaddr_ll, buf: &mut [u8]) -> io::Result<usize> {
@@ -93,10 +247,13 @@ pub fn raw_sendto(
     frame: &mut Vec<u8>,
     _debug: &Verbose,
 ) -> io::Result<()> {
+    // read the ethertype from the frame (IPv4 or IPv6)
+    let ethertype = ((frame[12] as u16) << 8) | frame[13] as u16;
+
     // sockaddr_ll (man 7 packet)
     let mut sa = libc::sockaddr_ll {
         sll_family: libc::AF_PACKET as u16,
-        sll_protocol: ETHER_P_IP.to_be(),
+        sll_protocol: ethertype.to_be(),
         sll_ifindex: ifindex,
         sll_hatype: 0,
         sll_pkttype: 0,
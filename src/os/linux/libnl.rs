@@ -3,11 +3,14 @@
 use crate::*;
 
 // libc
-use libc::{c_char, c_int, c_uint, c_void, AF_INET, AF_LLC, ETH_ALEN, IFF_UP, IF_NAMESIZE};
+use libc::{
+    c_char, c_int, c_uint, c_void, AF_INET, AF_INET6, AF_LLC, ETH_ALEN, IFF_UP, IF_NAMESIZE,
+};
 
 // std
 use std::ffi::CString;
 use std::io;
+use std::net::{IpAddr, Ipv6Addr};
 
 // constants
 const INT_RTAX_MAX: usize = 8; // to verify __RTAX_MAX enum value
@@ -252,6 +255,46 @@ pub fn set_ip_address(
     netmask: [u8; 4],
     op: Operation,
     debug: &Verbose,
+) -> io::Result<()> {
+    // convert netmask to prefix length
+    // by counting the number of bit set
+    // per bytes in netmask array
+    let mut prefixlen = 0;
+    for b in netmask.iter() {
+        prefixlen += b.count_ones();
+    }
+
+    // create IP address string
+    let ip_str = format!("{}.{}.{}.{}/{}", ip[0], ip[1], ip[2], ip[3], prefixlen);
+
+    set_address(ifindex, ifname, ip_str, AF_INET, op, debug)
+}
+
+// set_ip6_address() function
+/// Set or remove an IPv6 address on an interface according to the passed Operation variant
+pub fn set_ip6_address(
+    ifindex: i32,
+    ifname: &CString,
+    ip: [u8; 16],
+    prefixlen: u8,
+    op: Operation,
+    debug: &Verbose,
+) -> io::Result<()> {
+    // create IPv6 address string
+    let ip_str = format!("{}/{}", Ipv6Addr::from(ip), prefixlen);
+
+    set_address(ifindex, ifname, ip_str, AF_INET6, op, debug)
+}
+
+// set_address() function
+/// Set or remove an address (of the given family) on an interface
+fn set_address(
+    ifindex: i32,
+    ifname: &CString,
+    ip_str: String,
+    family: c_int,
+    op: Operation,
+    debug: &Verbose,
 ) -> io::Result<()> {
     // call to external nlsock() function
     let nlsock = unsafe { nl_socket_alloc() };
@@ -287,21 +330,10 @@ pub fn set_ip_address(
         a_addr: [0; 4],
     };
 
-    // convert netmask to prefix length
-    // by counting the number of bit set
-    // per bytes in netmask array
-    let mut prefixlen = 0;
-    for b in netmask.iter() {
-        prefixlen += b.count_ones();
-    }
-
-    // create IP address string
-    let ip_str = format!("{}.{}.{}.{}/{}", ip[0], ip[1], ip[2], ip[3], prefixlen);
-
     // set local IP address in rtnl_addr 'addr'
     let ipaddr = CString::new(ip_str).unwrap();
     let mut laddr_ptr = &mut laddr;
-    let r = unsafe { nl_addr_parse(ipaddr.as_ptr(), AF_INET, &mut laddr_ptr) };
+    let r = unsafe { nl_addr_parse(ipaddr.as_ptr(), family, &mut laddr_ptr) };
     if r < 0 {
         return Err(io::Error::last_os_error());
     }
@@ -383,9 +415,9 @@ pub fn set_ip_address(
 pub fn set_ip_route(
     _ifindex: i32,
     _ifname: &String,
-    route: [u8; 4],
-    rtmask: [u8; 4],
-    gw: [u8; 4],
+    route: IpAddr,
+    rtmask: IpAddr,
+    gw: IpAddr,
     metric: i16,
     mtu: u64,
     op: &Operation,
@@ -422,22 +454,25 @@ pub fn set_ip_route(
         a_addr: [0; 4],
     };
 
+    // address family of the route
+    let family = match route {
+        IpAddr::V4(_) => AF_INET,
+        IpAddr::V6(_) => AF_INET6,
+    };
+
     // convert netmask to prefix length
-    let mut prefixlen = 0;
-    for b in rtmask.iter() {
-        prefixlen += b.count_ones();
-    }
+    let prefixlen = match rtmask {
+        IpAddr::V4(m) => u32::from(m).count_ones(),
+        IpAddr::V6(m) => u128::from(m).count_ones(),
+    };
     // create route string
-    let route_str = format!(
-        "{}.{}.{}.{}/{}",
-        route[0], route[1], route[2], route[3], prefixlen
-    );
+    let route_str = format!("{}/{}", route, prefixlen);
     // convert route string to a Cstring type
     let route_cstr = CString::new(route_str).unwrap();
     // create pointer to 'nl_addr' rtdst
     let mut rtdst_ptr = &mut rtdst;
     // parse destination route
-    let r = unsafe { nl_addr_parse(route_cstr.as_ptr(), AF_INET, &mut rtdst_ptr) };
+    let r = unsafe { nl_addr_parse(route_cstr.as_ptr(), family, &mut rtdst_ptr) };
     if r < 0 {
         return Err(io::Error::last_os_error());
     }
@@ -446,10 +481,7 @@ pub fn set_ip_route(
         debug,
         DEBUG_LEVEL_EXTENSIVE,
         DEBUG_SRC_IP,
-        format!(
-            "route_slice: {:?}, nl_addr {:?}, result: {}",
-            route, *rtdst_ptr, r
-        ),
+        format!("route: {}, nl_addr {:?}, result: {}", route, *rtdst_ptr, r),
     );
     // set destination route in 'nlroute'
     let r = unsafe { rtnl_route_set_dst(nlroute, rtdst_ptr) };
@@ -467,13 +499,13 @@ pub fn set_ip_route(
         a_addr: [0; 4],
     };
     // create nexthop string
-    let nh_str = format!("{}.{}.{}.{}", gw[0], gw[1], gw[2], gw[3]);
+    let nh_str = gw.to_string();
     // convert nh_str string to a CString
     let nh_cstr = CString::new(nh_str).unwrap();
     // create pointer to 'nl_addr' nhaddr
     let mut nhaddr_ptr = &mut nhaddr;
     // parse Cstring nexthop address in nhaddr
-    let r = unsafe { nl_addr_parse(nh_cstr.as_ptr(), AF_INET, &mut nhaddr_ptr) };
+    let r = unsafe { nl_addr_parse(nh_cstr.as_ptr(), family, &mut nhaddr_ptr) };
     // check for error(s)
     if r < 0 {
         return Err(io::Error::last_os_error());
@@ -483,10 +515,7 @@ pub fn set_ip_route(
         debug,
         DEBUG_LEVEL_EXTENSIVE,
         DEBUG_SRC_IP,
-        format!(
-            "gw_slice: {:?}, nl_addr {:?}, result: {}",
-            gw, *nhaddr_ptr, r
-        ),
+        format!("gw: {}, nl_addr {:?}, result: {}", gw, *nhaddr_ptr, r),
     );
 
     // allocate nexthop
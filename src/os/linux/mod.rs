@@ -6,7 +6,11 @@ pub mod libc;
 pub mod netdev;
 // libnl netlink support
 pub mod libnl;
+// rtnetlink link monitoring support
+pub mod rtnetlink;
 // Linux ARP support
 pub mod arp;
+// Linux IPv6 Neighbor Discovery support
+pub mod ndp;
 // Linux Socket Filter support
 pub mod filter;
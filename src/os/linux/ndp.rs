@@ -0,0 +1,29 @@
+//! Linux Neighbor Discovery Protocol (NDP) module
+//! This module provides IPv6 neighbor discovery related structures.
+
+/// Unsolicited Neighbor Advertisement Structure
+#[repr(C)]
+pub struct NAframe {
+    // Ethernet Header
+    pub dst_mac: [u8; 6], // destination MAC address
+    pub src_mac: [u8; 6], // source MAC address
+    pub ethertype: u16,   // ether type
+
+    // IPv6 Header
+    pub ipvtcfl: [u8; 4], // version, traffic class and flow label
+    pub iplength: u16,    // payload length
+    pub ipnexthdr: u8,    // next header (ICMPv6)
+    pub iphoplimit: u8,   // hop limit
+    pub ipsrc: [u8; 16],  // source address
+    pub ipdst: [u8; 16],  // destination address
+
+    // ICMPv6 Neighbor Advertisement (RFC4861 4.4)
+    pub icmp_type: u8,       // type (136)
+    pub icmp_code: u8,       // code (0)
+    pub icmp_checksum: u16,  // ICMPv6 checksum
+    pub flags: [u8; 4],      // router, solicited and override flags
+    pub target: [u8; 16],    // target address
+    pub opt_type: u8,        // option type (target link-layer address)
+    pub opt_len: u8,         // option length (in units of 8 bytes)
+    pub opt_lladdr: [u8; 6], // target link-layer address
+}
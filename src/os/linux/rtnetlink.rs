@@ -0,0 +1,151 @@
+//! Linux rtnetlink link monitoring module
+//! This module subscribes to the rtnetlink link group to track interfaces' link state.
+use crate::*;
+
+// std, libc
+use byteorder::{ByteOrder, NativeEndian};
+use libc::{bind, recv, send, sockaddr, sockaddr_nl, socket, AF_NETLINK, SOCK_RAW};
+use std::io;
+use std::mem;
+
+// rtnetlink constants (see linux/netlink.h and linux/rtnetlink.h)
+const NETLINK_ROUTE: i32 = 0;
+const RTMGRP_LINK: u32 = 0x1;
+const NLMSG_DONE: u16 = 0x3;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_DUMP: u16 = 0x300;
+const RTM_NEWLINK: u16 = 16;
+const RTM_DELLINK: u16 = 17;
+const RTM_GETLINK: u16 = 18;
+const IFLA_IFNAME: u16 = 3;
+const IFF_RUNNING: u32 = 0x40;
+const NLMSG_HDRLEN: usize = 16;
+const IFINFOMSG_LEN: usize = 16;
+
+/// Netlink Link Dump Request Structure
+#[repr(C)]
+struct LinkRequest {
+    // netlink message header
+    nlmsg_len: u32,
+    nlmsg_type: u16,
+    nlmsg_flags: u16,
+    nlmsg_seq: u32,
+    nlmsg_pid: u32,
+    // ifinfomsg
+    ifi_family: u8,
+    ifi_pad: u8,
+    ifi_type: u16,
+    ifi_index: i32,
+    ifi_flags: u32,
+    ifi_change: u32,
+}
+
+// open_link_socket() function
+/// Open a rtnetlink socket subscribed to the link multicast group
+pub fn open_link_socket() -> io::Result<i32> {
+    let fd = match unsafe { socket(AF_NETLINK, SOCK_RAW, NETLINK_ROUTE) } {
+        -1 => return Err(io::Error::last_os_error()),
+        fd => fd,
+    };
+
+    // bind the socket to the link group
+    let mut sa: sockaddr_nl = unsafe { mem::zeroed() };
+    sa.nl_family = AF_NETLINK as u16;
+    sa.nl_groups = RTMGRP_LINK;
+    unsafe {
+        match bind(
+            fd,
+            &sa as *const sockaddr_nl as *const sockaddr,
+            mem::size_of::<sockaddr_nl>() as u32,
+        ) {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(fd),
+        }
+    }
+}
+
+// request_link_dump() function
+/// Request the state of all the links, replies are read by recv_link_events()
+pub fn request_link_dump(fd: i32) -> io::Result<()> {
+    let req = LinkRequest {
+        nlmsg_len: mem::size_of::<LinkRequest>() as u32,
+        nlmsg_type: RTM_GETLINK,
+        nlmsg_flags: NLM_F_REQUEST | NLM_F_DUMP,
+        nlmsg_seq: 1,
+        nlmsg_pid: 0,
+        ifi_family: 0,
+        ifi_pad: 0,
+        ifi_type: 0,
+        ifi_index: 0,
+        ifi_flags: 0,
+        ifi_change: 0,
+    };
+    unsafe {
+        match send(
+            fd,
+            &req as *const LinkRequest as *const c_void,
+            mem::size_of::<LinkRequest>(),
+            0,
+        ) {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// recv_link_events() function
+/// Block until rtnetlink messages are received, and return
+/// the interfaces' name and link state (true if running) they carry
+pub fn recv_link_events(fd: i32, buf: &mut [u8]) -> io::Result<Vec<(String, bool)>> {
+    let len = match unsafe { recv(fd, buf.as_mut_ptr() as *mut c_void, buf.len(), 0) } {
+        -1 => return Err(io::Error::last_os_error()),
+        len => len as usize,
+    };
+
+    let mut events = Vec::new();
+    let mut pos = 0;
+
+    // walk through the netlink messages
+    while pos + NLMSG_HDRLEN <= len {
+        let msg_len = NativeEndian::read_u32(&buf[pos..pos + 4]) as usize;
+        let msg_type = NativeEndian::read_u16(&buf[pos + 4..pos + 6]);
+        if msg_len < NLMSG_HDRLEN || pos + msg_len > len || msg_type == NLMSG_DONE {
+            break;
+        }
+
+        if (msg_type == RTM_NEWLINK || msg_type == RTM_DELLINK)
+            && msg_len >= NLMSG_HDRLEN + IFINFOMSG_LEN
+        {
+            let ifi = pos + NLMSG_HDRLEN;
+            let flags = NativeEndian::read_u32(&buf[ifi + 8..ifi + 12]);
+
+            // look for the interface name attribute
+            let mut attr = ifi + IFINFOMSG_LEN;
+            while attr + 4 <= pos + msg_len {
+                let attr_len = NativeEndian::read_u16(&buf[attr..attr + 2]) as usize;
+                let attr_type = NativeEndian::read_u16(&buf[attr + 2..attr + 4]);
+                if attr_len < 4 || attr + attr_len > pos + msg_len {
+                    break;
+                }
+                if attr_type == IFLA_IFNAME {
+                    let name: Vec<u8> = buf[attr + 4..attr + attr_len]
+                        .iter()
+                        .take_while(|&&b| b != 0)
+                        .cloned()
+                        .collect();
+                    // a deleted link is considered down
+                    let up = msg_type == RTM_NEWLINK && (flags & IFF_RUNNING) != 0;
+                    events.push((String::from_utf8_lossy(&name).to_string(), up));
+                    break;
+                }
+                // attributes are aligned on 4 bytes
+                attr += (attr_len + 3) & !3;
+            }
+        }
+
+        // messages are aligned on 4 bytes
+        pos += (msg_len + 3) & !3;
+    }
+
+    Ok(events)
+}
@@ -173,3 +173,22 @@ pub fn get_addrlist(
 
     Ok(())
 }
+
+// get_addrlist6() function
+/// get list of IPv6 address(es) and store them into a vector
+pub fn get_addrlist6(ifname: &String, v6addrs: &mut Vec<[u8; 16]>) -> io::Result<()> {
+    // get list of all ip address per interfaces
+    let addrlist = IfAddrs::get()?;
+
+    // for every matching entry of the vr's interface,
+    // push the IPv6 address into the v6addrs vector.
+    for a in addrlist.iter() {
+        if a.name().to_lowercase() == *ifname {
+            if let Some(IpAddr::V6(ip)) = a.addr() {
+                v6addrs.push(ip.octets());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -7,8 +7,8 @@ use crate::constants::*;
 // virtual router
 use crate::VirtualRouter;
 
-/// Raw VRRPv2 Packet Format Structure
-/// This is the fixed size portion of a possibly VRRPv2 packet
+/// Raw VRRPv2/v3 Packet Format Structure
+/// This is the fixed size portion of a possibly VRRP packet
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct VRRPpkt {
@@ -30,6 +30,8 @@ pub struct VRRPpkt {
     ipdst: [u8; 4],  // destinatin IP address
 
     // VRRPv2 packet format (RFC3768)
+    // VRRPv3 shares the same layout, the authtype and adverint fields
+    // hold the 4 bits reserved and 12 bits max advertisement interval (RFC5798)
     version: u8,   // version/type - 4/4 bits
     vrid: u8,      // virtual router id - 8 bits
     prio: u8,      // priority - 8 bits
@@ -77,9 +79,17 @@ impl VRRPpkt {
     }
     // safer getter for addrcount, with checks for valid frame size
     pub fn s_addrcount(&self, framesize: usize) -> u8 {
-        // make sure the address count matches the frame size,
-        // a valid packet with one address should equal 60 bytes
-        if framesize != 56 + (self.addrcount as usize * 4) as usize {
+        // compute the expected IP packet length from the address count,
+        // VRRPv2 messages also carry 8 bytes of authentication data
+        let mut iplength = 20 + 8 + (self.addrcount as usize * 4);
+        if self.version == VRRP_V2_VER_TYPE_AUTHMSG {
+            iplength += 8;
+        }
+        // make sure the address count matches the IP packet length and the frame size,
+        // ethernet frames shorter than 60 bytes are padded
+        if u16::from_be(self.iplength) as usize != iplength
+            || framesize < ETHER_FRAME_SIZE + iplength
+        {
             return 0u8;
         }
         self.addrcount
@@ -90,18 +100,29 @@ impl VRRPpkt {
     pub fn adverint(&self) -> &u8 {
         &self.adverint
     }
+    // max_adverint() method
+    // VRRPv3 12-bits maximum advertisement interval (in centiseconds),
+    // stored in place of the VRRPv2 authentication type and advertisement interval
+    pub fn max_adverint(&self) -> u16 {
+        (((self.authtype & 0x0f) as u16) << 8) | self.adverint as u16
+    }
+    // valid_max_adverint() method
+    // a zero interval would collapse the master_down interval of the Backups
+    // learning it to the skew time
+    pub fn valid_max_adverint(&self) -> bool {
+        self.max_adverint() > 0
+    }
     pub fn checksum(&self) -> &u16 {
         &self.checksum
     }
     // gen_advert() method
-    // generate a VRRPv2 ADVERTISEMENT packet
+    // generate a VRRPv2 or VRRPv3 ADVERTISEMENT packet
     pub fn gen_advert(vr: &VirtualRouter) -> VRRPpkt {
         // Ethernet frame headers:
         // dst multicast MAC address for 224.0.0.18
         let dst_mac = ETHER_VRRP_V2_DST_MAC;
         // generate source MAC address from VID
-        let mut src_mac = ETHER_VRRP_V2_SRC_MAC;
-        src_mac[5] = vr.parameters.vrid();
+        let src_mac = vr.parameters.vmac();
         // ipv4 ethertype
         let ethertype = ETHER_P_IP.to_be();
 
@@ -125,16 +146,27 @@ impl VRRPpkt {
         // VRRPv2 multicast group
         let ipdst = VRRP_V2_IP_MCAST_DST;
 
-        // VRRPv2 ADVERTISEMENT:
-        // version = 0x2
+        // VRRP ADVERTISEMENT:
+        // version = 0x2 or 0x3
         // type = 0x1 (ADVERTISEMENT)
-        let version = VRRP_V2_ADVERT_VERSION_TYPE;
+        let (version, authtype, adverint) = match vr.parameters.version() {
+            // VRRPv3 (RFC5798): 4 bits reserved and 12 bits maximum
+            // advertisement interval (in centiseconds)
+            3 => {
+                let (rsvd_maxint, maxint) = split_max_adverint(vr.parameters.adverint_cs());
+                (VRRP_V3_VER_TYPE_ADVERT, rsvd_maxint, maxint)
+            }
+            // VRRPv2 (RFC3768)
+            _ => (
+                VRRP_V2_ADVERT_VERSION_TYPE,
+                vr.parameters.authtype(),
+                vr.parameters.adverint_secs(),
+            ),
+        };
         // virtual router id
         let vrid = vr.parameters.vrid();
         let prio = vr.parameters.prio();
         let addrcount = vr.parameters.addrcount();
-        let authtype = vr.parameters.authtype();
-        let adverint = vr.parameters.adverint();
         // generate checksum on VRRP message
         let checksum = 0;
 
@@ -164,8 +196,269 @@ impl VRRPpkt {
     }
 }
 
+/// Raw VRRPv3 over IPv6 Packet Format Structure
+/// This is the fixed size portion of a possibly VRRPv3 IPv6 packet
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct VRRPpkt6 {
+    // Ethernet frame headers
+    dst_mac: [u8; 6], // destination MAC address
+    src_mac: [u8; 6], // source MAC address
+    ethertype: u16,   // ether type
+
+    // IPv6 packet headers
+    ipvtcfl: [u8; 4], // IP version, traffic class and flow label - 4/8/20 bits
+    iplength: u16,    // payload length
+    ipnexthdr: u8,    // next header
+    iphoplimit: u8,   // hop limit
+    ipsrc: [u8; 16],  // source IPv6 address
+    ipdst: [u8; 16],  // destination IPv6 address
+
+    // VRRPv3 packet format (RFC5798)
+    version: u8,     // version/type - 4/4 bits
+    vrid: u8,        // virtual router id - 8 bits
+    prio: u8,        // priority - 8 bits
+    addrcount: u8,   // count ipv6 addr - 8 bits
+    rsvd_maxint: u8, // reserved and max advertisement interval (high) - 4/4 bits
+    maxint: u8,      // max advertisement interval (low) - 8 bits
+    checksum: u16,   // checksum - 16 bits
+}
+
+// VRRPpkt6 methods
+impl VRRPpkt6 {
+    // getters
+    pub fn ipsrc(&self) -> &[u8; 16] {
+        &self.ipsrc
+    }
+    pub fn ipdst(&self) -> &[u8; 16] {
+        &self.ipdst
+    }
+    pub fn iplength(&self) -> u16 {
+        u16::from_be(self.iplength)
+    }
+    pub fn ipnexthdr(&self) -> &u8 {
+        &self.ipnexthdr
+    }
+    pub fn iphoplimit(&self) -> &u8 {
+        &self.iphoplimit
+    }
+    pub fn version(&self) -> &u8 {
+        &self.version
+    }
+    pub fn vrid(&self) -> &u8 {
+        &self.vrid
+    }
+    pub fn prio(&self) -> &u8 {
+        &self.prio
+    }
+    pub fn addrcount(&self) -> &u8 {
+        &self.addrcount
+    }
+    // safer getter for addrcount, with checks for valid payload and frame size
+    pub fn s_addrcount(&self, framesize: usize) -> u8 {
+        // a VRRPv3 message is 8 bytes long plus 16 bytes per IPv6 address
+        let iplength = 8 + (self.addrcount as usize * 16);
+        if self.iplength() as usize != iplength
+            || framesize < ETHER_FRAME_SIZE + IP_V6_HDR_SIZE + iplength
+        {
+            return 0u8;
+        }
+        self.addrcount
+    }
+    // max_adverint() method
+    // 12-bits maximum advertisement interval (in centiseconds)
+    pub fn max_adverint(&self) -> u16 {
+        (((self.rsvd_maxint & 0x0f) as u16) << 8) | self.maxint as u16
+    }
+    // valid_max_adverint() method
+    // a zero interval would collapse the master_down interval of the Backups
+    // learning it to the skew time
+    pub fn valid_max_adverint(&self) -> bool {
+        self.max_adverint() > 0
+    }
+    pub fn checksum(&self) -> &u16 {
+        &self.checksum
+    }
+    // gen_advert() method
+    // generate a VRRPv3 ADVERTISEMENT packet for IPv6
+    pub fn gen_advert(vr: &VirtualRouter) -> VRRPpkt6 {
+        // Ethernet frame headers:
+        // dst multicast MAC address for ff02::12
+        let dst_mac = ETHER_VRRP_V3_IPV6_DST_MAC;
+        // virtual router MAC address
+        let src_mac = vr.parameters.vmac();
+        // ipv6 ethertype
+        let ethertype = ETHER_P_IPV6.to_be();
+
+        // IPv6 headers:
+        // version 6, traffic class CS6 and no flow label
+        let ipvtcfl = [IP_V6_VERSION | (IP_DSCP_CS6 >> 4), (IP_DSCP_CS6 << 4), 0, 0];
+        // lowest payload length (set by the caller)
+        let iplength = 8u16.to_be();
+        // VRRP is next header 112
+        let ipnexthdr = IP_UPPER_PROTO_VRRP;
+        // hop limit must be set to 255
+        let iphoplimit = IP_V6_HOP_LIMIT_VRRP;
+        // source packet from interface link-local address
+        let ipsrc = vr.parameters.primary_ip6();
+        // VRRPv3 IPv6 multicast group
+        let ipdst = VRRP_V3_IPV6_MCAST_DST;
+
+        // VRRPv3 ADVERTISEMENT:
+        // version = 0x3
+        // type = 0x1 (ADVERTISEMENT)
+        let version = VRRP_V3_VER_TYPE_ADVERT;
+        let (rsvd_maxint, maxint) = split_max_adverint(vr.parameters.adverint_cs());
+        // virtual router id
+        let vrid = vr.parameters.vrid();
+        let prio = vr.parameters.prio();
+        let addrcount = vr.parameters.addrcount();
+        // checksum is computed by the caller
+        let checksum = 0;
+
+        // return the built VRRP ADVERTISEMENT packet
+        VRRPpkt6 {
+            dst_mac,
+            src_mac,
+            ethertype,
+            ipvtcfl,
+            iplength,
+            ipnexthdr,
+            iphoplimit,
+            ipsrc,
+            ipdst,
+            version,
+            vrid,
+            prio,
+            addrcount,
+            rsvd_maxint,
+            maxint,
+            checksum,
+        }
+    }
+}
+
+// split_max_adverint() function
+/// split a 12-bits maximum advertisement interval (in centiseconds)
+/// into its reserved/high and low octets, the reserved bits are left to zero
+pub fn split_max_adverint(maxint: u16) -> (u8, u8) {
+    (((maxint >> 8) & 0x0f) as u8, maxint as u8)
+}
+
 // as_u8_slice() unsafe function
 /// transform type T as slice of u8
 pub unsafe fn as_u8_slice<T: Sized>(p: &T) -> &[u8] {
     ::std::slice::from_raw_parts((p as *const T) as *const u8, ::std::mem::size_of::<T>())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // build a zeroed IPv4 VRRP packet with the given version and address count
+    fn vrrp_pkt(version: u8, addrcount: u8, iplength: u16) -> VRRPpkt {
+        let mut pkt: VRRPpkt = unsafe { std::mem::zeroed() };
+        pkt.version = version;
+        pkt.addrcount = addrcount;
+        pkt.iplength = iplength.to_be();
+        pkt
+    }
+
+    // build a zeroed IPv6 VRRP packet with the given address count
+    fn vrrp_pkt6(addrcount: u8, iplength: u16) -> VRRPpkt6 {
+        let mut pkt: VRRPpkt6 = unsafe { std::mem::zeroed() };
+        pkt.version = VRRP_V3_VER_TYPE_ADVERT;
+        pkt.addrcount = addrcount;
+        pkt.iplength = iplength.to_be();
+        pkt
+    }
+
+    #[test]
+    fn max_adverint_roundtrip() {
+        for &cs in &[0u16, 1, 100, 0xff, 0x100, 0xabc, 0xfff] {
+            let (rsvd_maxint, maxint) = split_max_adverint(cs);
+            assert_eq!(rsvd_maxint & 0xf0, 0);
+
+            let mut pkt = vrrp_pkt(VRRP_V3_VER_TYPE_ADVERT, 0, 0);
+            pkt.authtype = rsvd_maxint;
+            pkt.adverint = maxint;
+            assert_eq!(pkt.max_adverint(), cs);
+
+            let mut pkt6 = vrrp_pkt6(0, 0);
+            pkt6.rsvd_maxint = rsvd_maxint;
+            pkt6.maxint = maxint;
+            assert_eq!(pkt6.max_adverint(), cs);
+        }
+    }
+
+    #[test]
+    fn max_adverint_truncated_to_12_bits() {
+        assert_eq!(split_max_adverint(0x1fff), (0x0f, 0xff));
+    }
+
+    #[test]
+    fn max_adverint_ignores_reserved_bits() {
+        let mut pkt = vrrp_pkt(VRRP_V3_VER_TYPE_ADVERT, 0, 0);
+        pkt.authtype = 0xf1;
+        pkt.adverint = 0x2c;
+        assert_eq!(pkt.max_adverint(), 0x12c);
+
+        let mut pkt6 = vrrp_pkt6(0, 0);
+        pkt6.rsvd_maxint = 0xa0;
+        pkt6.maxint = 0x64;
+        assert_eq!(pkt6.max_adverint(), 100);
+    }
+
+    #[test]
+    fn zero_max_adverint() {
+        let mut pkt = vrrp_pkt(VRRP_V3_VER_TYPE_ADVERT, 1, 32);
+        assert!(!pkt.valid_max_adverint());
+        // the reserved bits do not make an interval
+        pkt.authtype = 0xf0;
+        assert!(!pkt.valid_max_adverint());
+        pkt.adverint = 1;
+        assert!(pkt.valid_max_adverint());
+
+        let mut pkt6 = vrrp_pkt6(1, 24);
+        pkt6.rsvd_maxint = 0xf0;
+        assert!(!pkt6.valid_max_adverint());
+        let (rsvd_maxint, maxint) = split_max_adverint(0x100);
+        pkt6.rsvd_maxint = rsvd_maxint;
+        pkt6.maxint = maxint;
+        assert!(pkt6.valid_max_adverint());
+    }
+
+    #[test]
+    fn addrcount_v3() {
+        // 20 bytes IPv4 header, 8 bytes VRRP header and 2 addresses
+        let pkt = vrrp_pkt(VRRP_V3_VER_TYPE_ADVERT, 2, 36);
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + 36), 2);
+        // ethernet padding is accepted
+        assert_eq!(pkt.s_addrcount(60), 2);
+        // truncated frame
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + 35), 0);
+        // the address count does not match the IP length
+        let pkt = vrrp_pkt(VRRP_V3_VER_TYPE_ADVERT, 3, 36);
+        assert_eq!(pkt.s_addrcount(60), 0);
+    }
+
+    #[test]
+    fn addrcount_v2() {
+        // VRRPv2 messages carry 8 more bytes of authentication data
+        let pkt = vrrp_pkt(VRRP_V2_VER_TYPE_AUTHMSG, 1, 40);
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + 40), 1);
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + 39), 0);
+        let pkt = vrrp_pkt(VRRP_V2_VER_TYPE_AUTHMSG, 1, 32);
+        assert_eq!(pkt.s_addrcount(60), 0);
+    }
+
+    #[test]
+    fn addrcount_v6() {
+        // 8 bytes VRRP header and 2 IPv6 addresses
+        let pkt = vrrp_pkt6(2, 40);
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + IP_V6_HDR_SIZE + 40), 2);
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + IP_V6_HDR_SIZE + 39), 0);
+        let pkt = vrrp_pkt6(3, 40);
+        assert_eq!(pkt.s_addrcount(ETHER_FRAME_SIZE + IP_V6_HDR_SIZE + 56), 0);
+    }
+}
@@ -1,8 +1,11 @@
 //! protocols module
 //! This module includes networking protocols data structures and related functions.
 
+// std
+use std::net::IpAddr;
+
 /// Protocols Structure
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Protocols {
     pub r#static: Option<Vec<Static>>,
 }
@@ -16,11 +19,11 @@ impl Protocols {
 }
 
 /// Static Protocol Structure
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Static {
-    route: [u8; 4],
-    mask: [u8; 4],
-    nh: [u8; 4],
+    route: IpAddr,
+    mask: IpAddr,
+    nh: IpAddr,
     metric: i16,
     mtu: u64,
 }
@@ -28,7 +31,7 @@ pub struct Static {
 // Static Protocol Type Implementation
 impl Static {
     // new() method
-    pub fn new(route: [u8; 4], mask: [u8; 4], nh: [u8; 4], metric: i16, mtu: u64) -> Static {
+    pub fn new(route: IpAddr, mask: IpAddr, nh: IpAddr, metric: i16, mtu: u64) -> Static {
         Static {
             route,
             mask,
@@ -38,15 +41,15 @@ impl Static {
         }
     }
     // route() getter
-    pub fn route(&self) -> [u8; 4] {
+    pub fn route(&self) -> IpAddr {
         self.route
     }
     // mask() getter
-    pub fn mask(&self) -> [u8; 4] {
+    pub fn mask(&self) -> IpAddr {
         self.mask
     }
     // nh() getter
-    pub fn nh(&self) -> [u8; 4] {
+    pub fn nh(&self) -> IpAddr {
         self.nh
     }
     // metric() getter
@@ -0,0 +1,405 @@
+//! configuration reload module
+use super::*;
+
+// config
+use crate::config::{read_config, CConfig, CfgType, VRConfig};
+
+// client API
+use crate::api::client::VRouterControl;
+
+// operating system drivers
+use crate::os::drivers::Operation;
+
+// reload_config() function
+/// read the configuration file again and apply it to the running virtual routers:
+/// removed virtual routers are shut down, added ones are started and the
+/// reloadable parameters of the others are updated in place
+///
+/// the running configuration is left untouched if the new one is invalid
+pub fn reload_config(
+    filename: String,
+    cfgtype: CfgType,
+    vrouters: &mut Vec<Arc<RwLock<VirtualRouter>>>,
+    threads: &mut ThreadPool,
+    protocols: &Arc<Mutex<Protocols>>,
+    sync_groups: &mut Vec<Arc<threads::SyncGroup>>,
+    capi: Option<&UpstreamAPI>,
+    debug: &Verbose,
+) -> Option<CConfig> {
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_INFO,
+        format!("Reloading configuration file {}", filename),
+    );
+
+    // read and validate the new configuration
+    let config = match read_config(filename, cfgtype) {
+        Ok(c) => c,
+        Err(e) => {
+            print_error(
+                debug,
+                DEBUG_SRC_RELOAD,
+                format!("{}, keeping the running configuration", e),
+            );
+            return None;
+        }
+    };
+    let issues = config.validate();
+    for i in &issues {
+        if i.is_error() {
+            print_error(debug, DEBUG_SRC_RELOAD, i.detail());
+        } else {
+            print_debug(debug, DEBUG_LEVEL_INFO, DEBUG_SRC_INFO, i.to_string());
+        }
+    }
+    if issues.iter().any(|i| i.is_error()) {
+        print_error(
+            debug,
+            DEBUG_SRC_RELOAD,
+            format!("invalid configuration, keeping the running configuration"),
+        );
+        return None;
+    }
+    let vcvr = match &config.vrouter {
+        Some(v) if !v.is_empty() => v,
+        _ => {
+            print_error(
+                debug,
+                DEBUG_SRC_RELOAD,
+                format!("no virtual router configured, keeping the running configuration"),
+            );
+            return None;
+        }
+    };
+
+    // shut down the virtual routers removed from the configuration
+    let (kept, removed): (Vec<_>, Vec<_>) = vrouters.drain(..).partition(|vr| {
+        let vr = vr.read().unwrap();
+        let ifname = vr.parameters.cfg_interface();
+        vcvr.iter()
+            .any(|c| c.group() == vr.parameters.vrid() && *c.interface() == ifname)
+    });
+    *vrouters = kept;
+    for vr in removed {
+        remove_vrouter(&vr, vrouters, threads, debug);
+    }
+
+    // update the reloadable parameters of the remaining virtual routers
+    for vr in vrouters.iter() {
+        let mut vro = vr.write().unwrap();
+        let ifname = vro.parameters.cfg_interface();
+        let c = match vcvr
+            .iter()
+            .find(|c| c.group() == vro.parameters.vrid() && *c.interface() == ifname)
+        {
+            Some(c) => c,
+            None => continue,
+        };
+        vro.apply_config(c);
+        // move the virtual router to its new sync group
+        let name = config.sync_group_of(vro.parameters.vrid(), &ifname);
+        if vro.parameters.sync_group().map(|sg| sg.name().clone()) != name {
+            set_sync_group(&mut vro, name, sync_groups, debug);
+        }
+        // print debugging information
+        print_debug(
+            debug,
+            DEBUG_LEVEL_MEDIUM,
+            DEBUG_SRC_RELOAD,
+            format!(
+                "updated virtual router for group {} on interface {}",
+                vro.parameters.vrid(),
+                ifname
+            ),
+        );
+    }
+
+    // replace the static routes installed by the masters
+    let new_protocols = build_protocols(&config);
+    if *protocols.lock().unwrap() != new_protocols {
+        for vr in vrouters.iter() {
+            let mut vr = vr.write().unwrap();
+            let fd = vr.parameters.fd();
+            vr.set_ip_routes(fd, Operation::Rem, debug);
+        }
+        *protocols.lock().unwrap() = new_protocols;
+        for vr in vrouters.iter() {
+            let mut vr = vr.write().unwrap();
+            if let fsm::States::Master = vr.states {
+                let fd = vr.parameters.fd();
+                vr.set_ip_routes(fd, Operation::Add, debug);
+            }
+        }
+        print_debug(
+            debug,
+            DEBUG_LEVEL_INFO,
+            DEBUG_SRC_INFO,
+            format!("Static routes updated"),
+        );
+    }
+
+    // start the virtual routers added to the configuration
+    for c in vcvr {
+        let exists = vrouters.iter().any(|vr| {
+            let vr = vr.read().unwrap();
+            vr.parameters.vrid() == c.group() && vr.parameters.cfg_interface() == *c.interface()
+        });
+        if exists {
+            continue;
+        }
+        if let Err(e) = add_vrouter(
+            &config,
+            c,
+            vrouters,
+            threads,
+            protocols,
+            sync_groups,
+            capi,
+            debug,
+        ) {
+            print_error(debug, DEBUG_SRC_RELOAD, e);
+        }
+    }
+
+    // forget the sync groups no longer configured
+    let names = config.sync_groups();
+    sync_groups.retain(|sg| names.contains(sg.name()));
+
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_INFO,
+        format!("Configuration reloaded"),
+    );
+    Some(config)
+}
+
+// apply_control() function
+/// apply a virtual router creation or removal requested through the client API,
+/// and return the updated configuration if it succeeded
+pub fn apply_control(
+    ctl: VRouterControl,
+    vrouters: &mut Vec<Arc<RwLock<VirtualRouter>>>,
+    threads: &mut ThreadPool,
+    protocols: &Arc<Mutex<Protocols>>,
+    sync_groups: &mut Vec<Arc<threads::SyncGroup>>,
+    capi: Option<&UpstreamAPI>,
+    debug: &Verbose,
+) -> Option<CConfig> {
+    match ctl {
+        VRouterControl::Add(config, c, reply) => {
+            let r = add_vrouter(
+                &config,
+                &c,
+                vrouters,
+                threads,
+                protocols,
+                sync_groups,
+                capi,
+                debug,
+            );
+            let done = r.is_ok();
+            let _r = reply.send(r);
+            if done {
+                Some(config)
+            } else {
+                None
+            }
+        }
+        VRouterControl::Remove(config, group, interface, reply) => {
+            let pos = vrouters.iter().position(|vr| {
+                let vr = vr.read().unwrap();
+                vr.parameters.vrid() == group && vr.parameters.cfg_interface() == interface
+            });
+            match pos {
+                Some(pos) => {
+                    let vr = vrouters.remove(pos);
+                    remove_vrouter(&vr, vrouters, threads, debug);
+                    let _r = reply.send(Ok(()));
+                    Some(config)
+                }
+                None => {
+                    let _r = reply.send(Err(format!(
+                        "No virtual router for group {} on interface {}",
+                        group, interface
+                    )));
+                    None
+                }
+            }
+        }
+        VRouterControl::Route(config, old, new, reply) => {
+            // the masters remove the old route before the new one gets installed
+            if let Some(st) = &old {
+                set_master_route(vrouters, &build_static(st), Operation::Rem, debug);
+            }
+            *protocols.lock().unwrap() = build_protocols(&config);
+            if let Some(st) = &new {
+                set_master_route(vrouters, &build_static(st), Operation::Add, debug);
+            }
+            print_debug(
+                debug,
+                DEBUG_LEVEL_INFO,
+                DEBUG_SRC_INFO,
+                format!("Static routes updated"),
+            );
+            let _r = reply.send(Ok(()));
+            Some(config)
+        }
+    }
+}
+
+// set_master_route() function
+/// install or remove a static route on the virtual routers being master
+fn set_master_route(
+    vrouters: &Vec<Arc<RwLock<VirtualRouter>>>,
+    st: &Static,
+    op: Operation,
+    debug: &Verbose,
+) {
+    for vr in vrouters {
+        let vr = vr.read().unwrap();
+        if let fsm::States::Master = vr.states {
+            vr.set_ip_route(vr.parameters.fd(), st, &op, debug);
+        }
+    }
+}
+
+// set_sync_group() function
+/// move a running virtual router to the named sync group, or out of its sync group
+fn set_sync_group(
+    vr: &mut VirtualRouter,
+    name: Option<String>,
+    sync_groups: &mut Vec<Arc<threads::SyncGroup>>,
+    debug: &Verbose,
+) {
+    let tx = vr.parameters.notification().clone();
+    if let (Some(sg), Some(tx)) = (vr.parameters.sync_group(), &tx) {
+        sg.unregister(tx);
+    }
+    vr.parameters.clear_sync_group();
+    if let Some(name) = &name {
+        let sg = sync_group(sync_groups, name.clone());
+        if let Some(tx) = &tx {
+            sg.register(tx);
+        }
+        vr.parameters.set_sync_group(sg);
+    }
+    // print debugging information
+    print_debug(
+        debug,
+        DEBUG_LEVEL_MEDIUM,
+        DEBUG_SRC_RELOAD,
+        format!(
+            "moved virtual router for group {} on interface {} to sync group {}",
+            vr.parameters.vrid(),
+            vr.parameters.cfg_interface(),
+            name.as_ref().map(|n| &n[..]).unwrap_or("(none)")
+        ),
+    );
+}
+
+// add_vrouter() function
+/// create a virtual router, open its sockets, and start its worker and listener threads
+fn add_vrouter(
+    config: &CConfig,
+    c: &VRConfig,
+    vrouters: &mut Vec<Arc<RwLock<VirtualRouter>>>,
+    threads: &mut ThreadPool,
+    protocols: &Arc<Mutex<Protocols>>,
+    sync_groups: &mut Vec<Arc<threads::SyncGroup>>,
+    capi: Option<&UpstreamAPI>,
+    debug: &Verbose,
+) -> Result<(), String> {
+    let mut nvr = match new_vrouter(config, c, protocols, sync_groups, debug) {
+        Ok(nvr) => nvr,
+        Err(e) => {
+            return Err(format!(
+                "Cannot create virtual router for group {} on interface {}: {}",
+                c.group(),
+                c.interface(),
+                e
+            ))
+        }
+    };
+    if let Err(e) = setup_vr_socket(&mut nvr, capi) {
+        return Err(format!(
+            "Cannot setup virtual router for group {} on interface {}: {}",
+            c.group(),
+            c.interface(),
+            e
+        ));
+    }
+    let vr = Arc::new(RwLock::new(nvr));
+    vrouters.push(Arc::clone(&vr));
+    threads.spawn(&vr, debug);
+    spawn_listener(&vr, debug);
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_INFO,
+        format!(
+            "Added virtual router for group {} on interface {}",
+            c.group(),
+            c.interface()
+        ),
+    );
+    Ok(())
+}
+
+// remove_vrouter() function
+/// shut down a virtual router's worker, and release its interface and sockets
+fn remove_vrouter(
+    vr: &Arc<RwLock<VirtualRouter>>,
+    remaining: &Vec<Arc<RwLock<VirtualRouter>>>,
+    threads: &mut ThreadPool,
+    debug: &Verbose,
+) {
+    // a master gives up its addresses when shut down
+    threads.remove(vr, debug);
+
+    let mut vr = vr.write().unwrap();
+    // the configured interface, a macvlan master runs on its virtual interface
+    let ifname = vr.parameters.cfg_interface();
+
+    // leave the sync group
+    if let (Some(sg), Some(tx)) = (vr.parameters.sync_group(), vr.parameters.notification()) {
+        sg.unregister(tx);
+    }
+
+    // leave the promiscuous mode if no other virtual router uses the interface
+    let shared = remaining
+        .iter()
+        .any(|v| v.read().unwrap().parameters.cfg_interface() == ifname);
+    if !shared {
+        let iface = CString::new(ifname.as_bytes() as &[u8]).unwrap();
+        if let Err(e) =
+            os::linux::netdev::set_if_promiscuous(vr.parameters.fd(), &iface, PflagOp::Unset)
+        {
+            print_error(
+                debug,
+                DEBUG_SRC_RELOAD,
+                format!(
+                    "cannot unset promiscuous mode on interface {}: {}",
+                    ifname, e
+                ),
+            );
+        }
+    }
+
+    // the listener thread closes the raw socket once it sees it released
+    os::linux::libc::close_fd(vr.parameters.unicast_fd());
+    vr.parameters.set_unicast_fd(-1);
+    vr.parameters.set_fd(-1);
+
+    print_debug(
+        debug,
+        DEBUG_LEVEL_INFO,
+        DEBUG_SRC_INFO,
+        format!(
+            "Removed virtual router for group {} on interface {}",
+            vr.parameters.vrid(),
+            ifname
+        ),
+    );
+}
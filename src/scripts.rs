@@ -0,0 +1,250 @@
+//! track scripts supervisor module
+//! This module runs the track scripts and notifies the virtual routers of their state.
+use super::*;
+
+// std
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+// debugging
+use crate::debug::{print_debug, print_error, Verbose};
+
+// configuration
+use crate::config::TrackScript;
+
+// fsm
+use crate::fsm::Event;
+
+// supervisor polling interval (in milliseconds)
+const SUPERVISOR_TICK_MS: u64 = 100;
+
+/// Supervised Track Script Structure
+struct Supervised {
+    script: TrackScript,             // track script configuration
+    child: Option<(Child, Instant)>, // running script process and its start time
+    next_run: Instant,               // next scheduled run
+    successes: u32,                  // consecutive successes
+    failures: u32,                   // consecutive failures
+    healthy: bool,                   // current state
+}
+
+// Supervised Type Implementation
+impl Supervised {
+    // new() method
+    // scripts are assumed healthy until proven otherwise
+    fn new(script: TrackScript) -> Supervised {
+        Supervised {
+            script,
+            child: None,
+            next_run: Instant::now(),
+            successes: 0,
+            failures: 0,
+            healthy: true,
+        }
+    }
+    // stop() method
+    // kill the running script, if any
+    fn stop(&mut self) {
+        if let Some((mut child, _)) = self.child.take() {
+            kill_script(&mut child);
+        }
+    }
+}
+
+// kill_script() function
+/// kill a running script along with the processes it started (its process group)
+fn kill_script(child: &mut Child) {
+    unsafe { libc::kill(-(child.id() as i32), libc::SIGKILL) };
+    let _r = child.wait();
+}
+
+// start_supervisor() function
+/// run the track scripts at their interval, and notify the state changes
+/// to the virtual routers following them
+///
+/// the scripts are replaced upon configuration reloads
+pub fn start_supervisor(
+    scripts: &RwLock<Vec<TrackScript>>,
+    vrouters: &RwLock<Vec<Arc<RwLock<VirtualRouter>>>>,
+    debug: &Verbose,
+) {
+    let mut supervised: Vec<Supervised> = Vec::new();
+
+    loop {
+        // follow the track scripts of the running configuration
+        update_scripts(&mut supervised, &scripts.read().unwrap(), vrouters, debug);
+
+        for s in supervised.iter_mut() {
+            let now = Instant::now();
+
+            // check the running script
+            let result = match &mut s.child {
+                Some((child, started)) => match child.try_wait() {
+                    Ok(Some(status)) => Some(status.success()),
+                    Ok(None) => {
+                        if now.duration_since(*started)
+                            >= Duration::from_secs(s.script.timeout() as u64)
+                        {
+                            print_debug(
+                                debug,
+                                DEBUG_LEVEL_MEDIUM,
+                                DEBUG_SRC_SCRIPT,
+                                format!("track script {} timed out", s.script.name()),
+                            );
+                            kill_script(child);
+                            Some(false)
+                        } else {
+                            None
+                        }
+                    }
+                    Err(_) => Some(false),
+                },
+                None => {
+                    // start the script if it is due
+                    if now >= s.next_run {
+                        s.next_run = now + Duration::from_secs(s.script.interval() as u64);
+                        let mut cmd = Command::new("/bin/sh");
+                        cmd.arg("-c").arg(s.script.script());
+                        // run the script in its own process group, killed as a whole
+                        unsafe {
+                            cmd.pre_exec(|| {
+                                if libc::setsid() == -1 {
+                                    return Err(std::io::Error::last_os_error());
+                                }
+                                Ok(())
+                            });
+                        }
+                        match cmd
+                            .stdin(Stdio::null())
+                            .stdout(Stdio::null())
+                            .stderr(Stdio::null())
+                            .spawn()
+                        {
+                            Ok(child) => {
+                                s.child = Some((child, now));
+                                None
+                            }
+                            Err(e) => {
+                                print_error(
+                                    debug,
+                                    DEBUG_SRC_SCRIPT,
+                                    format!("cannot run track script {}: {}", s.script.name(), e),
+                                );
+                                Some(false)
+                            }
+                        }
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            // account for the script's result
+            if let Some(success) = result {
+                s.child = None;
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_HIGH,
+                    DEBUG_SRC_SCRIPT,
+                    format!(
+                        "track script {} {}",
+                        s.script.name(),
+                        if success { "succeeded" } else { "failed" }
+                    ),
+                );
+                if success {
+                    s.successes += 1;
+                    s.failures = 0;
+                    if !s.healthy && s.successes >= s.script.rise() {
+                        s.healthy = true;
+                        notify_script_state(s.script.name(), true, vrouters, debug);
+                    }
+                } else {
+                    s.failures += 1;
+                    s.successes = 0;
+                    if s.healthy && s.failures >= s.script.fall() {
+                        s.healthy = false;
+                        notify_script_state(s.script.name(), false, vrouters, debug);
+                    }
+                }
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(SUPERVISOR_TICK_MS));
+    }
+}
+
+// update_scripts() function
+/// start supervising the added track scripts, stop the removed ones and restart
+/// the modified ones (keeping their current state)
+fn update_scripts(
+    supervised: &mut Vec<Supervised>,
+    scripts: &[TrackScript],
+    vrouters: &RwLock<Vec<Arc<RwLock<VirtualRouter>>>>,
+    debug: &Verbose,
+) {
+    // the virtual routers no longer get penalized by a removed script
+    for s in supervised.iter_mut() {
+        if scripts.iter().all(|c| c.name() != s.script.name()) {
+            s.stop();
+            if !s.healthy {
+                notify_script_state(s.script.name(), true, vrouters, debug);
+            }
+        }
+    }
+    supervised.retain(|s| scripts.iter().any(|c| c.name() == s.script.name()));
+    for c in scripts {
+        match supervised.iter_mut().find(|s| s.script.name() == c.name()) {
+            Some(s) if s.script == *c => continue,
+            Some(s) => {
+                s.stop();
+                s.script = c.clone();
+                s.next_run = Instant::now();
+                s.successes = 0;
+                s.failures = 0;
+            }
+            None => supervised.push(Supervised::new(c.clone())),
+        }
+        // print debugging information
+        print_debug(
+            debug,
+            DEBUG_LEVEL_MEDIUM,
+            DEBUG_SRC_SCRIPT,
+            format!("supervising track script {}", c.name()),
+        );
+    }
+}
+
+// notify_script_state() function
+/// send a ScriptState event to every virtual router following the track script
+fn notify_script_state(
+    name: &str,
+    healthy: bool,
+    vrouters: &RwLock<Vec<Arc<RwLock<VirtualRouter>>>>,
+    debug: &Verbose,
+) {
+    for vr in vrouters.read().unwrap().iter() {
+        let vr = vr.read().unwrap();
+        if !vr.parameters.is_tracking_script(name) {
+            continue;
+        }
+        // print debugging information
+        print_debug(
+            debug,
+            DEBUG_LEVEL_MEDIUM,
+            DEBUG_SRC_SCRIPT,
+            format!(
+                "sending ScriptState event notification for track script {}",
+                name
+            ),
+        );
+        if let Some(tx) = vr.parameters.notification() {
+            let _r = tx
+                .lock()
+                .unwrap()
+                .send(Event::ScriptState(name.to_string(), healthy));
+        }
+    }
+}
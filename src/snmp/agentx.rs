@@ -0,0 +1,441 @@
+//! AgentX protocol module
+//! This module encodes and decodes the AgentX (RFC2741) PDUs exchanged with the master agent.
+
+// std
+use std::io::{self, Read, Write};
+
+// AgentX protocol version
+const AGENTX_VERSION: u8 = 1;
+
+// AgentX header size
+const AGENTX_HDR_SIZE: usize = 20;
+
+// AgentX header flags
+const FLAG_NON_DEFAULT_CONTEXT: u8 = 0x08;
+const FLAG_NETWORK_BYTE_ORDER: u8 = 0x10;
+
+// well known OID prefix of the compressed OIDs (internet)
+const OID_INTERNET: [u32; 4] = [1, 3, 6, 1];
+
+// AgentX PDU types
+pub const PDU_OPEN: u8 = 1;
+pub const PDU_CLOSE: u8 = 2;
+pub const PDU_REGISTER: u8 = 3;
+pub const PDU_GET: u8 = 5;
+pub const PDU_GETNEXT: u8 = 6;
+pub const PDU_GETBULK: u8 = 7;
+pub const PDU_TESTSET: u8 = 8;
+pub const PDU_COMMITSET: u8 = 9;
+pub const PDU_UNDOSET: u8 = 10;
+pub const PDU_CLEANUPSET: u8 = 11;
+pub const PDU_NOTIFY: u8 = 12;
+pub const PDU_RESPONSE: u8 = 18;
+
+// AgentX response errors
+pub const ERR_NOERROR: u16 = 0;
+pub const ERR_GENERR: u16 = 5;
+pub const ERR_NOTWRITABLE: u16 = 17;
+
+/// Object Identifier
+pub type Oid = Vec<u32>;
+
+/// Variable Value Enumerator
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i32),
+    OctetString(Vec<u8>),
+    ObjectIdentifier(Oid),
+    IpAddress([u8; 4]),
+    Counter32(u32),
+    Gauge32(u32),
+    TimeTicks(u32),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+// Value Type Implementation
+impl Value {
+    // tag() getter
+    /// AgentX varbind type
+    fn tag(&self) -> u16 {
+        match self {
+            Value::Integer(_) => 2,
+            Value::OctetString(_) => 4,
+            Value::ObjectIdentifier(_) => 6,
+            Value::IpAddress(_) => 64,
+            Value::Counter32(_) => 65,
+            Value::Gauge32(_) => 66,
+            Value::TimeTicks(_) => 67,
+            Value::Counter64(_) => 70,
+            Value::NoSuchObject => 128,
+            Value::NoSuchInstance => 129,
+            Value::EndOfMibView => 130,
+        }
+    }
+}
+
+/// Search Range Structure
+/// the start (included or not) and end (excluded, empty if unbounded) OIDs of a request
+#[derive(Debug)]
+pub struct SearchRange {
+    pub start: Oid,
+    pub include: bool,
+    pub end: Oid,
+}
+
+/// PDU Structure
+#[derive(Debug)]
+pub struct Pdu {
+    pub kind: u8,
+    pub session_id: u32,
+    pub transaction_id: u32,
+    pub packet_id: u32,
+    pub payload: Vec<u8>,
+    network_order: bool,
+}
+
+// Pdu Type Implementation
+impl Pdu {
+    // new() method
+    pub fn new(kind: u8, session_id: u32, transaction_id: u32, packet_id: u32) -> Pdu {
+        Pdu {
+            kind,
+            session_id,
+            transaction_id,
+            packet_id,
+            payload: Vec::new(),
+            network_order: true,
+        }
+    }
+    // reply() method
+    /// build the Response PDU answering this PDU
+    pub fn reply(&self, sys_uptime: u32, error: u16, index: u16) -> Pdu {
+        let mut resp = Pdu::new(
+            PDU_RESPONSE,
+            self.session_id,
+            self.transaction_id,
+            self.packet_id,
+        );
+        resp.push_u32(sys_uptime);
+        resp.push_u16(error);
+        resp.push_u16(index);
+        resp
+    }
+    // read() method
+    /// read a PDU from the master agent
+    pub fn read<R: Read>(r: &mut R) -> io::Result<Pdu> {
+        let mut hdr = [0u8; AGENTX_HDR_SIZE];
+        r.read_exact(&mut hdr)?;
+        if hdr[0] != AGENTX_VERSION {
+            return Err(invalid("unsupported AgentX version"));
+        }
+        let network_order = hdr[2] & FLAG_NETWORK_BYTE_ORDER != 0;
+        let u32_at = |i: usize| {
+            let b = [hdr[i], hdr[i + 1], hdr[i + 2], hdr[i + 3]];
+            if network_order {
+                u32::from_be_bytes(b)
+            } else {
+                u32::from_le_bytes(b)
+            }
+        };
+        let len = u32_at(16) as usize;
+        let mut payload = vec![0u8; len];
+        r.read_exact(&mut payload)?;
+        let mut pdu = Pdu {
+            kind: hdr[1],
+            session_id: u32_at(4),
+            transaction_id: u32_at(8),
+            packet_id: u32_at(12),
+            payload,
+            network_order,
+        };
+        // skip the context preceding the payload, the MIBs are served
+        // the same way in every context
+        if hdr[2] & FLAG_NON_DEFAULT_CONTEXT != 0 && has_context(pdu.kind) {
+            let mut r = pdu.reader();
+            r.octets()?;
+            let pos = r.pos;
+            pdu.payload.drain(..pos);
+        }
+        Ok(pdu)
+    }
+    // write() method
+    /// send the PDU to the master agent (in network byte order)
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(AGENTX_HDR_SIZE + self.payload.len());
+        buf.extend_from_slice(&[AGENTX_VERSION, self.kind, FLAG_NETWORK_BYTE_ORDER, 0]);
+        buf.extend_from_slice(&self.session_id.to_be_bytes());
+        buf.extend_from_slice(&self.transaction_id.to_be_bytes());
+        buf.extend_from_slice(&self.packet_id.to_be_bytes());
+        buf.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&self.payload);
+        w.write_all(&buf)
+    }
+    // push_u8() method
+    pub fn push_u8(&mut self, v: u8) {
+        self.payload.push(v);
+    }
+    // push_u16() method
+    pub fn push_u16(&mut self, v: u16) {
+        self.payload.extend_from_slice(&v.to_be_bytes());
+    }
+    // push_u32() method
+    pub fn push_u32(&mut self, v: u32) {
+        self.payload.extend_from_slice(&v.to_be_bytes());
+    }
+    // push_oid() method
+    /// append an OID, compressing the internet prefix
+    pub fn push_oid(&mut self, oid: &[u32], include: bool) {
+        let prefix = oid.len() > 4 && oid[..4] == OID_INTERNET && oid[4] > 0 && oid[4] < 256;
+        let subids = if prefix { &oid[5..] } else { oid };
+        self.push_u8(subids.len() as u8);
+        self.push_u8(if prefix { oid[4] as u8 } else { 0 });
+        self.push_u8(include as u8);
+        self.push_u8(0);
+        for s in subids {
+            self.push_u32(*s);
+        }
+    }
+    // push_octets() method
+    /// append an octet string, padded to a multiple of 4 bytes
+    pub fn push_octets(&mut self, s: &[u8]) {
+        self.push_u32(s.len() as u32);
+        self.payload.extend_from_slice(s);
+        while self.payload.len() % 4 != 0 {
+            self.payload.push(0);
+        }
+    }
+    // push_varbind() method
+    pub fn push_varbind(&mut self, name: &[u32], value: &Value) {
+        self.push_u16(value.tag());
+        self.push_u16(0);
+        self.push_oid(name, false);
+        match value {
+            Value::Integer(v) => self.push_u32(*v as u32),
+            Value::Counter32(v) | Value::Gauge32(v) | Value::TimeTicks(v) => self.push_u32(*v),
+            Value::Counter64(v) => {
+                self.push_u32((*v >> 32) as u32);
+                self.push_u32(*v as u32);
+            }
+            Value::OctetString(s) => self.push_octets(s),
+            Value::IpAddress(a) => self.push_octets(a),
+            Value::ObjectIdentifier(o) => self.push_oid(o, false),
+            Value::NoSuchObject | Value::NoSuchInstance | Value::EndOfMibView => {}
+        }
+    }
+    // reader() method
+    /// read the payload of a received PDU
+    pub fn reader(&self) -> PayloadReader<'_> {
+        PayloadReader {
+            buf: &self.payload,
+            pos: 0,
+            network_order: self.network_order,
+        }
+    }
+}
+
+/// Payload Reader Structure
+pub struct PayloadReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    network_order: bool,
+}
+
+// PayloadReader Type Implementation
+impl<'a> PayloadReader<'a> {
+    // at_end() method
+    pub fn at_end(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+    // take() method
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.pos + n > self.buf.len() {
+            return Err(invalid("truncated AgentX PDU"));
+        }
+        let s = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(s)
+    }
+    // u8() method
+    pub fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+    // u16() method
+    pub fn u16(&mut self) -> io::Result<u16> {
+        let b = self.take(2)?;
+        let b = [b[0], b[1]];
+        Ok(if self.network_order {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+    // u32() method
+    pub fn u32(&mut self) -> io::Result<u32> {
+        let b = self.take(4)?;
+        let b = [b[0], b[1], b[2], b[3]];
+        Ok(if self.network_order {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+    // oid() method
+    /// read an OID and its include flag
+    pub fn oid(&mut self) -> io::Result<(Oid, bool)> {
+        let n_subid = self.u8()?;
+        let prefix = self.u8()?;
+        let include = self.u8()? != 0;
+        self.u8()?;
+        let mut oid = Vec::new();
+        if prefix != 0 {
+            oid.extend_from_slice(&OID_INTERNET);
+            oid.push(prefix as u32);
+        }
+        for _ in 0..n_subid {
+            oid.push(self.u32()?);
+        }
+        Ok((oid, include))
+    }
+    // octets() method
+    /// read an octet string and its padding
+    pub fn octets(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        let s = self.take(len)?;
+        self.take((4 - len % 4) % 4)?;
+        Ok(s)
+    }
+    // search_range() method
+    pub fn search_range(&mut self) -> io::Result<SearchRange> {
+        let (start, include) = self.oid()?;
+        let (end, _) = self.oid()?;
+        Ok(SearchRange {
+            start,
+            include,
+            end,
+        })
+    }
+    // search_ranges() method
+    /// read the search ranges up to the end of the payload
+    pub fn search_ranges(&mut self) -> io::Result<Vec<SearchRange>> {
+        let mut ranges = Vec::new();
+        while !self.at_end() {
+            ranges.push(self.search_range()?);
+        }
+        Ok(ranges)
+    }
+}
+
+// has_context() function
+/// check if the PDUs of a type may carry a context
+fn has_context(kind: u8) -> bool {
+    matches!(
+        kind,
+        PDU_REGISTER | PDU_GET | PDU_GETNEXT | PDU_GETBULK | PDU_TESTSET | PDU_NOTIFY
+    )
+}
+
+// invalid() function
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // round trip a PDU through its wire format
+    fn roundtrip(pdu: &Pdu) -> Pdu {
+        let mut buf = Vec::new();
+        pdu.write(&mut buf).unwrap();
+        Pdu::read(&mut &buf[..]).unwrap()
+    }
+
+    #[test]
+    fn oid_prefix_compression() {
+        let mut pdu = Pdu::new(PDU_GET, 1, 2, 3);
+        pdu.push_oid(&[1, 3, 6, 1, 2, 1, 68, 1, 3, 1, 3], true);
+        // 6 sub-identifiers after the mib-2 (1.3.6.1.2) prefix
+        assert_eq!(&pdu.payload[..4], &[6, 2, 1, 0]);
+        assert_eq!(pdu.payload.len(), 4 + 6 * 4);
+
+        let pdu = roundtrip(&pdu);
+        let (oid, include) = pdu.reader().oid().unwrap();
+        assert_eq!(oid, vec![1, 3, 6, 1, 2, 1, 68, 1, 3, 1, 3]);
+        assert!(include);
+    }
+
+    #[test]
+    fn oid_without_prefix() {
+        // neither outside the internet subtree nor below 1.3.6.1.0 is compressed
+        for oid in &[
+            vec![1, 0, 8802, 1],
+            vec![1, 3, 6, 1, 0, 1],
+            vec![1, 3, 6, 1],
+        ] {
+            let mut pdu = Pdu::new(PDU_GET, 1, 2, 3);
+            pdu.push_oid(oid, false);
+            assert_eq!(&pdu.payload[..4], &[oid.len() as u8, 0, 0, 0]);
+            let pdu = roundtrip(&pdu);
+            assert_eq!(pdu.reader().oid().unwrap(), (oid.clone(), false));
+        }
+    }
+
+    #[test]
+    fn octets_padding() {
+        for len in 0..9 {
+            let s: Vec<u8> = (1..=len).collect();
+            let mut pdu = Pdu::new(PDU_RESPONSE, 1, 2, 3);
+            pdu.push_octets(&s);
+            pdu.push_u32(0xdead_beef);
+            assert_eq!(pdu.payload.len(), 4 + ((len as usize + 3) & !3) + 4);
+
+            let pdu = roundtrip(&pdu);
+            let mut r = pdu.reader();
+            assert_eq!(r.octets().unwrap(), &s[..]);
+            assert_eq!(r.u32().unwrap(), 0xdead_beef);
+            assert!(r.at_end());
+        }
+    }
+
+    #[test]
+    fn non_default_context() {
+        // GetNext in the "ctx" context, in little endian byte order
+        let mut buf = vec![AGENTX_VERSION, PDU_GETNEXT, FLAG_NON_DEFAULT_CONTEXT, 0];
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&2u32.to_le_bytes());
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&24u32.to_le_bytes());
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(b"ctx\0");
+        buf.extend_from_slice(&[2, 2, 1, 0]);
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&68u32.to_le_bytes());
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+
+        let pdu = Pdu::read(&mut &buf[..]).unwrap();
+        assert_eq!(pdu.packet_id, 3);
+        let ranges = pdu.reader().search_ranges().unwrap();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].start, vec![1, 3, 6, 1, 2, 1, 68]);
+        assert!(ranges[0].include);
+        assert!(ranges[0].end.is_empty());
+    }
+
+    #[test]
+    fn truncated_context() {
+        let mut buf = vec![
+            AGENTX_VERSION,
+            PDU_GET,
+            FLAG_NON_DEFAULT_CONTEXT | FLAG_NETWORK_BYTE_ORDER,
+            0,
+        ];
+        buf.extend_from_slice(&[0; 12]);
+        buf.extend_from_slice(&8u32.to_be_bytes());
+        buf.extend_from_slice(&16u32.to_be_bytes());
+        buf.extend_from_slice(b"ctx\0");
+        assert!(Pdu::read(&mut &buf[..]).is_err());
+    }
+}
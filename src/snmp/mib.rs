@@ -0,0 +1,286 @@
+//! VRRP MIBs module
+//! This module builds the VRRP-MIB (RFC2787) and VRRPv3-MIB (RFC6527) objects
+//! from the running virtual routers.
+use super::*;
+
+// std
+use std::net::IpAddr;
+use std::time::{Duration, Instant, SystemTime};
+
+// statistics
+use crate::stats::{DropReason, NewMasterReason};
+
+// VRRP-MIB (RFC2787)
+pub const VRRP_MIB: [u32; 7] = [1, 3, 6, 1, 2, 1, 68];
+// VRRPv3-MIB (RFC6527)
+pub const VRRPV3_MIB: [u32; 7] = [1, 3, 6, 1, 2, 1, 207];
+
+// SNMPv2-MIB snmpTrapOID.0
+pub const SNMP_TRAP_OID: [u32; 11] = [1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+
+lazy_static! {
+    // start of the subagent, the TimeStamp objects are relative to it
+    static ref STARTED: (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+}
+
+// uptime() function
+/// time elapsed since the start of the subagent (in hundredths of a second)
+pub fn uptime() -> u32 {
+    (STARTED.0.elapsed().as_millis() / 10) as u32
+}
+
+// timestamp() function
+/// uptime at a past instant given as its age, zero if before the subagent's start
+fn timestamp(age: Duration) -> u32 {
+    match STARTED.0.elapsed().checked_sub(age) {
+        Some(d) => (d.as_millis() / 10) as u32,
+        None => 0,
+    }
+}
+
+// oid() function
+/// build an OID from a MIB, a sub-identifier path and an index
+pub fn oid(mib: &[u32], path: &[u32], index: &[u32]) -> Oid {
+    let mut o = mib.to_vec();
+    o.extend_from_slice(path);
+    o.extend_from_slice(index);
+    o
+}
+
+// vrrp_state() function
+/// VRRP-MIB and VRRPv3-MIB state: initialize(1), backup(2) or master(3)
+fn vrrp_state(vr: &VirtualRouter) -> i32 {
+    match vr.current_state() {
+        "Backup" => 2,
+        "Master" => 3,
+        _ => 1,
+    }
+}
+
+// truth() function
+/// SNMPv2-TC TruthValue
+fn truth(b: bool) -> Value {
+    Value::Integer(if b { 1 } else { 2 })
+}
+
+// master_ip() function
+/// address of the current master, the primary address of a master virtual router
+pub fn master_ip(vr: &VirtualRouter) -> Option<IpAddr> {
+    match vr.current_state() {
+        "Master" => primary_ip(vr),
+        _ => vr.parameters.last_master(),
+    }
+}
+
+// primary_ip() function
+fn primary_ip(vr: &VirtualRouter) -> Option<IpAddr> {
+    if vr.parameters.is_ipv6() {
+        Some(IpAddr::from(vr.parameters.primary_ip6()))
+    } else {
+        vr.parameters.ipaddrs().first().map(|a| IpAddr::from(*a))
+    }
+}
+
+// inet_address() function
+/// INET-ADDRESS-MIB InetAddress octets
+fn inet_address(ip: Option<IpAddr>) -> Vec<u8> {
+    match ip {
+        Some(IpAddr::V4(ip)) => ip.octets().to_vec(),
+        Some(IpAddr::V6(ip)) => ip.octets().to_vec(),
+        None => Vec::new(),
+    }
+}
+
+// ipv4_address() function
+pub fn ipv4_address(ip: Option<IpAddr>) -> [u8; 4] {
+    match ip {
+        Some(IpAddr::V4(ip)) => ip.octets(),
+        _ => [0; 4],
+    }
+}
+
+// new_master_reason() function
+/// VRRPv3-MIB vrrpv3StatisticsNewMasterReason
+fn new_master_reason(reason: NewMasterReason) -> i32 {
+    match reason {
+        NewMasterReason::NotMaster => 0,
+        NewMasterReason::Priority => 1,
+        NewMasterReason::Preempted => 2,
+        NewMasterReason::MasterNoResponse => 3,
+    }
+}
+
+// build() function
+/// build the sorted list of the objects served by the subagent
+pub fn build(vrs: &Vec<Arc<RwLock<VirtualRouter>>>) -> Vec<(Oid, Value)> {
+    let vrs: Vec<_> = vrs.iter().map(|vr| vr.read().unwrap()).collect();
+    let mut objs: Vec<(Oid, Value)> = Vec::new();
+
+    // VRRP-MIB scalars, vrrpNodeVersion and vrrpNotificationCntl (enabled)
+    objs.push((oid(&VRRP_MIB, &[1, 1, 0], &[]), Value::Integer(2)));
+    objs.push((oid(&VRRP_MIB, &[1, 2, 0], &[]), Value::Integer(1)));
+
+    // router-wide counters, the drops of unknown VRIDs are counted on
+    // every virtual router of the receiving interface
+    let sum = |reason: DropReason| -> u64 { vrs.iter().map(|vr| vr.stats.drops(reason)).sum() };
+    let mut vrid_errors: Vec<(i32, u64)> = Vec::new();
+    for vr in &vrs {
+        let n = vr.stats.drops(DropReason::UnknownVrid);
+        match vrid_errors
+            .iter_mut()
+            .find(|(i, _)| *i == vr.parameters.ifindex())
+        {
+            Some(e) => e.1 = e.1.max(n),
+            None => vrid_errors.push((vr.parameters.ifindex(), n)),
+        }
+    }
+    let vrid_errors: u64 = vrid_errors.iter().map(|(_, n)| n).sum();
+    let checksum_errors = sum(DropReason::Checksum);
+    let version_errors = sum(DropReason::Version);
+    objs.push((
+        oid(&VRRP_MIB, &[2, 1, 0], &[]),
+        Value::Counter32(checksum_errors as u32),
+    ));
+    objs.push((
+        oid(&VRRP_MIB, &[2, 2, 0], &[]),
+        Value::Counter32(version_errors as u32),
+    ));
+    objs.push((
+        oid(&VRRP_MIB, &[2, 3, 0], &[]),
+        Value::Counter32(vrid_errors as u32),
+    ));
+    objs.push((
+        oid(&VRRPV3_MIB, &[1, 2, 1, 0], &[]),
+        Value::Counter64(checksum_errors),
+    ));
+    objs.push((
+        oid(&VRRPV3_MIB, &[1, 2, 2, 0], &[]),
+        Value::Counter64(version_errors),
+    ));
+    objs.push((
+        oid(&VRRPV3_MIB, &[1, 2, 3, 0], &[]),
+        Value::Counter64(vrid_errors),
+    ));
+    objs.push((oid(&VRRPV3_MIB, &[1, 2, 4, 0], &[]), Value::TimeTicks(0)));
+
+    for vr in &vrs {
+        let ifindex = vr.parameters.ifindex() as u32;
+        let vrid = vr.parameters.vrid() as u32;
+        let stats = &vr.stats;
+        let up_time = timestamp(stats.state_duration());
+        let discontinuity = {
+            let started = STARTED
+                .1
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            (stats.discontinuity_time().saturating_sub(started) * 100) as u32
+        };
+
+        // VRRP-MIB (IPv4 virtual routers only), indexed by ifIndex and VRID
+        if !vr.parameters.is_ipv6() {
+            let idx = [ifindex, vrid];
+            // vrrpOperTable
+            let mut oper =
+                |col: u32, v: Value| objs.push((oid(&VRRP_MIB, &[1, 3, 1, col], &idx), v));
+            oper(2, Value::OctetString(vr.parameters.vmac().to_vec()));
+            oper(3, Value::Integer(vrrp_state(vr)));
+            oper(
+                4,
+                Value::Integer(if vr.current_state() == "Down" { 2 } else { 1 }),
+            );
+            oper(5, Value::Integer(vr.parameters.prio() as i32));
+            oper(6, Value::Integer(vr.parameters.vips4().len() as i32));
+            oper(7, Value::IpAddress(ipv4_address(master_ip(vr))));
+            oper(8, Value::IpAddress(ipv4_address(primary_ip(vr))));
+            match vr.parameters.authtype() {
+                0 => oper(9, Value::Integer(1)),
+                AUTH_TYPE_SIMPLE => oper(9, Value::Integer(2)),
+                // the proprietary authentication types have no MIB value
+                _ => {}
+            }
+            // the authentication key always reads as a zero-length string
+            oper(10, Value::OctetString(Vec::new()));
+            oper(11, Value::Integer(vr.parameters.adverint_secs() as i32));
+            oper(12, truth(vr.parameters.preempt()));
+            oper(13, Value::TimeTicks(up_time));
+            oper(14, Value::Integer(1));
+            oper(15, Value::Integer(1));
+            // vrrpAssoIpAddrTable
+            for vip in vr.parameters.vips4() {
+                let mut idx = idx.to_vec();
+                idx.extend(vip.iter().map(|b| *b as u32));
+                objs.push((oid(&VRRP_MIB, &[1, 4, 1, 2], &idx), Value::Integer(1)));
+            }
+            // vrrpRouterStatsTable
+            let mut stat = |col: u32, v: u64| {
+                objs.push((
+                    oid(&VRRP_MIB, &[2, 4, 1, col], &idx),
+                    Value::Counter32(v as u32),
+                ))
+            };
+            stat(1, stats.transitions("Master"));
+            stat(2, stats.adverts_rcvd());
+            stat(3, stats.drops(DropReason::Adverint));
+            stat(4, stats.drops(DropReason::AuthFailure));
+            stat(5, stats.drops(DropReason::Ttl));
+            stat(6, stats.prio_zero_rcvd());
+            stat(7, stats.prio_zero_sent());
+            stat(8, stats.drops(DropReason::InvalidType));
+            stat(9, stats.address_list_errors());
+            stat(10, 0);
+            stat(11, stats.drops(DropReason::AuthType));
+            stat(12, stats.drops(DropReason::PacketLength));
+        }
+
+        // VRRPv3-MIB, indexed by VRID, address type and ifIndex
+        let addr_type = if vr.parameters.is_ipv6() { 2 } else { 1 };
+        let idx = [vrid, addr_type, ifindex];
+        // vrrpv3OperationsTable
+        let mut oper =
+            |col: u32, v: Value| objs.push((oid(&VRRPV3_MIB, &[1, 1, 1, 1, col], &idx), v));
+        oper(3, Value::OctetString(inet_address(master_ip(vr))));
+        oper(4, Value::OctetString(inet_address(primary_ip(vr))));
+        oper(5, Value::OctetString(vr.parameters.vmac().to_vec()));
+        oper(6, Value::Integer(vrrp_state(vr)));
+        oper(7, Value::Gauge32(vr.parameters.prio() as u32));
+        oper(8, Value::Integer(vr.parameters.addrcount() as i32));
+        oper(9, Value::Integer(vr.parameters.adverint_cs() as i32));
+        oper(10, truth(vr.parameters.preempt()));
+        oper(12, Value::TimeTicks(up_time));
+        oper(13, Value::Integer(1));
+        // vrrpv3AssociatedIpAddrTable, the address index is prefixed by its length
+        let vips: Vec<Vec<u8>> = if vr.parameters.is_ipv6() {
+            vr.parameters.vips6().iter().map(|a| a.to_vec()).collect()
+        } else {
+            vr.parameters.vips4().iter().map(|a| a.to_vec()).collect()
+        };
+        for vip in vips {
+            let mut idx = idx.to_vec();
+            idx.push(vip.len() as u32);
+            idx.extend(vip.iter().map(|b| *b as u32));
+            objs.push((oid(&VRRPV3_MIB, &[1, 1, 2, 1, 2], &idx), Value::Integer(1)));
+        }
+        // vrrpv3StatisticsTable
+        let mut stat =
+            |col: u32, v: Value| objs.push((oid(&VRRPV3_MIB, &[1, 2, 5, 1, col], &idx), v));
+        stat(1, Value::Counter32(stats.transitions("Master") as u32));
+        stat(
+            2,
+            Value::Integer(new_master_reason(stats.new_master_reason())),
+        );
+        stat(3, Value::Counter64(stats.adverts_rcvd()));
+        stat(4, Value::Counter64(stats.drops(DropReason::Adverint)));
+        stat(5, Value::Counter64(stats.drops(DropReason::Ttl)));
+        stat(7, Value::Counter64(stats.prio_zero_rcvd()));
+        stat(8, Value::Counter64(stats.prio_zero_sent()));
+        stat(9, Value::Counter64(stats.drops(DropReason::InvalidType)));
+        stat(10, Value::Counter64(stats.address_list_errors()));
+        stat(11, Value::Counter64(stats.drops(DropReason::PacketLength)));
+        stat(12, Value::TimeTicks(discontinuity));
+        stat(13, Value::Gauge32(vr.parameters.adverint()));
+    }
+
+    objs.sort_by(|a, b| a.0.cmp(&b.0));
+    objs
+}
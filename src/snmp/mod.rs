@@ -0,0 +1,434 @@
+//! SNMP module
+//! This module runs the AgentX (RFC2741) subagent serving the VRRP MIBs
+//! and sending their notifications.
+use super::*;
+
+// std
+use std::cmp;
+use std::io;
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+// events
+use crate::events::{self, EventKind};
+
+// AgentX protocol
+mod agentx;
+use agentx::*;
+
+// VRRP MIBs
+mod mib;
+use mib::{VRRPV3_MIB, VRRP_MIB};
+
+// delay between the connection attempts to the master agent (in seconds)
+const AGENTX_RETRY_SECS: u64 = 5;
+
+// time to wait for the master agent's responses (in seconds)
+const AGENTX_TIMEOUT_SECS: u64 = 5;
+
+// default registration priority
+const AGENTX_PRIORITY: u8 = 127;
+
+// subagent description
+const AGENTX_DESCR: &[u8] = b"rVRRPd VRRP subagent";
+
+/// AgentX Session Structure
+struct Session {
+    stream: UnixStream, // connection to the master agent
+    id: u32,            // session identifier
+    packet_id: u32,     // last packet identifier
+}
+
+// Session Type Implementation
+impl Session {
+    // send() method
+    /// send a PDU initiated by the subagent
+    fn send(&mut self, mut pdu: Pdu) -> io::Result<()> {
+        self.packet_id = self.packet_id.wrapping_add(1);
+        pdu.session_id = self.id;
+        pdu.packet_id = self.packet_id;
+        pdu.write(&mut self.stream)
+    }
+}
+
+// start_subagent() function
+/// connect to the master agent and serve its requests, reconnecting
+/// whenever the session is lost
+pub fn start_subagent(
+    socket: String,
+    vrouters: Arc<RwLock<Vec<Arc<RwLock<VirtualRouter>>>>>,
+    debug: Verbose,
+) {
+    // the TimeStamp objects are relative to the start of the subagent
+    mib::uptime();
+
+    // the notifications are sent over the current session, if any
+    let session: Arc<Mutex<Option<Session>>> = Arc::new(Mutex::new(None));
+    {
+        let session = Arc::clone(&session);
+        let vrouters = Arc::clone(&vrouters);
+        let rx = events::listen();
+        thread::spawn(move || send_notifications(rx, &session, &vrouters, &debug));
+    }
+
+    loop {
+        match connect(&socket) {
+            Ok((stream, id)) => {
+                print_debug(
+                    &debug,
+                    DEBUG_LEVEL_INFO,
+                    DEBUG_SRC_SNMP,
+                    format!("AgentX session {} opened on {}", id, socket),
+                );
+                let reader = stream.try_clone();
+                *session.lock().unwrap() = Some(Session {
+                    stream,
+                    id,
+                    packet_id: 0,
+                });
+                let r = match reader {
+                    Ok(reader) => serve(reader, &session, &vrouters, &debug),
+                    Err(e) => Err(e),
+                };
+                *session.lock().unwrap() = None;
+                if let Err(e) = r {
+                    print_error(
+                        &debug,
+                        DEBUG_SRC_SNMP,
+                        format!("AgentX session {} closed: {}", id, e),
+                    );
+                }
+            }
+            Err(e) => print_debug(
+                &debug,
+                DEBUG_LEVEL_LOW,
+                DEBUG_SRC_SNMP,
+                format!("cannot open an AgentX session on {}: {}", socket, e),
+            ),
+        }
+        thread::sleep(Duration::from_secs(AGENTX_RETRY_SECS));
+    }
+}
+
+// connect() function
+/// open a session with the master agent and register the VRRP MIBs
+fn connect(socket: &str) -> io::Result<(UnixStream, u32)> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(Duration::from_secs(AGENTX_TIMEOUT_SECS)))?;
+
+    // open the session (default timeout)
+    let mut open = Pdu::new(PDU_OPEN, 0, 0, 1);
+    open.push_u32(0);
+    open.push_oid(&VRRPV3_MIB, false);
+    open.push_octets(AGENTX_DESCR);
+    open.write(&mut stream)?;
+    let id = read_response(&mut stream)?.session_id;
+
+    // register the MIBs subtrees
+    for (i, tree) in [VRRP_MIB, VRRPV3_MIB].iter().enumerate() {
+        let mut reg = Pdu::new(PDU_REGISTER, id, 0, 2 + i as u32);
+        reg.push_u8(0);
+        reg.push_u8(AGENTX_PRIORITY);
+        reg.push_u8(0);
+        reg.push_u8(0);
+        reg.push_oid(tree, false);
+        reg.write(&mut stream)?;
+        read_response(&mut stream)?;
+    }
+
+    stream.set_read_timeout(None)?;
+    Ok((stream, id))
+}
+
+// read_response() function
+/// read the master agent's response to a request of the subagent
+fn read_response(stream: &mut UnixStream) -> io::Result<Pdu> {
+    let pdu = Pdu::read(stream)?;
+    if pdu.kind != PDU_RESPONSE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected AgentX PDU type {}", pdu.kind),
+        ));
+    }
+    let mut r = pdu.reader();
+    r.u32()?;
+    match r.u16()? {
+        ERR_NOERROR => Ok(pdu),
+        e => Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("AgentX error {}", e),
+        )),
+    }
+}
+
+// serve() function
+/// answer the master agent's requests until the session is closed
+fn serve(
+    mut reader: UnixStream,
+    session: &Mutex<Option<Session>>,
+    vrouters: &RwLock<Vec<Arc<RwLock<VirtualRouter>>>>,
+    debug: &Verbose,
+) -> io::Result<()> {
+    loop {
+        let pdu = Pdu::read(&mut reader)?;
+        print_debug(
+            debug,
+            DEBUG_LEVEL_EXTENSIVE,
+            DEBUG_SRC_SNMP,
+            format!("received AgentX PDU type {}", pdu.kind),
+        );
+        let resp = match pdu.kind {
+            PDU_GET | PDU_GETNEXT | PDU_GETBULK => {
+                let objs = mib::build(&vrouters.read().unwrap());
+                Some(answer(&pdu, &objs)?)
+            }
+            // the MIBs are read-only
+            PDU_TESTSET => Some(pdu.reply(mib::uptime(), ERR_NOTWRITABLE, 1)),
+            PDU_COMMITSET | PDU_UNDOSET => Some(pdu.reply(mib::uptime(), ERR_GENERR, 1)),
+            // no response to the set cleanups and to the master agent's responses
+            PDU_CLEANUPSET | PDU_RESPONSE => None,
+            PDU_CLOSE => {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "closed by the master agent",
+                ))
+            }
+            _ => Some(pdu.reply(mib::uptime(), ERR_GENERR, 0)),
+        };
+        if let Some(resp) = resp {
+            if let Some(s) = session.lock().unwrap().as_mut() {
+                resp.write(&mut s.stream)?;
+            }
+        }
+    }
+}
+
+// answer() function
+/// answer the Get, GetNext and GetBulk requests
+fn answer(pdu: &Pdu, objs: &[(Oid, Value)]) -> io::Result<Pdu> {
+    let mut r = pdu.reader();
+    let mut resp = pdu.reply(mib::uptime(), ERR_NOERROR, 0);
+    match pdu.kind {
+        PDU_GET => {
+            for range in r.search_ranges()? {
+                let v = match objs.iter().find(|(o, _)| *o == range.start) {
+                    Some((_, v)) => v.clone(),
+                    None => Value::NoSuchInstance,
+                };
+                resp.push_varbind(&range.start, &v);
+            }
+        }
+        PDU_GETNEXT => {
+            for range in r.search_ranges()? {
+                let (o, v) = next(objs, &range);
+                resp.push_varbind(&o, &v);
+            }
+        }
+        _ => {
+            let non_repeaters = r.u16()? as usize;
+            let max_repetitions = r.u16()?;
+            let mut ranges = r.search_ranges()?;
+            let mut repeaters = ranges.split_off(cmp::min(non_repeaters, ranges.len()));
+            for range in &ranges {
+                let (o, v) = next(objs, range);
+                resp.push_varbind(&o, &v);
+            }
+            // the repeaters are walked together, until they all reached their end
+            for _ in 0..max_repetitions {
+                let mut ended = true;
+                for range in repeaters.iter_mut() {
+                    let (o, v) = next(objs, range);
+                    resp.push_varbind(&o, &v);
+                    if v != Value::EndOfMibView {
+                        ended = false;
+                        range.start = o;
+                        range.include = false;
+                    }
+                }
+                if ended || repeaters.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(resp)
+}
+
+// next() function
+/// find the first object within a search range
+fn next(objs: &[(Oid, Value)], range: &SearchRange) -> (Oid, Value) {
+    let found = objs.iter().find(|(o, _)| {
+        (*o > range.start || (range.include && *o == range.start))
+            && (range.end.is_empty() || *o < range.end)
+    });
+    match found {
+        Some((o, v)) => (o.clone(), v.clone()),
+        None => (range.start.clone(), Value::EndOfMibView),
+    }
+}
+
+// send_notifications() function
+/// send the VRRP-MIB notifications of the virtual routers' events
+fn send_notifications(
+    rx: Receiver<events::VREvent>,
+    session: &Mutex<Option<Session>>,
+    vrouters: &RwLock<Vec<Arc<RwLock<VirtualRouter>>>>,
+    debug: &Verbose,
+) {
+    for ev in rx.iter() {
+        let (trap, objects) = {
+            // the VRRP-MIB only covers the IPv4 virtual routers
+            let vrs = vrouters.read().unwrap();
+            let vr = vrs.iter().map(|vr| vr.read().unwrap()).find(|vr| {
+                vr.parameters.vrid() == ev.group()
+                    && vr.parameters.cfg_interface() == ev.interface()
+                    && !vr.parameters.is_ipv6()
+            });
+            let vr = match vr {
+                Some(vr) => vr,
+                None => continue,
+            };
+            let idx = [vr.parameters.ifindex() as u32, vr.parameters.vrid() as u32];
+            match ev.kind() {
+                // vrrpTrapNewMaster, with vrrpOperMasterIpAddr
+                EventKind::Transition { to, .. } if to == "Master" => (
+                    mib::oid(&VRRP_MIB, &[0, 1], &[]),
+                    vec![(
+                        mib::oid(&VRRP_MIB, &[1, 3, 1, 7], &idx),
+                        Value::IpAddress(mib::ipv4_address(mib::master_ip(&vr))),
+                    )],
+                ),
+                // vrrpTrapAuthFailure, with vrrpTrapPacketSrc and vrrpTrapAuthErrorType
+                EventKind::AuthFailure { source, reason } => (
+                    mib::oid(&VRRP_MIB, &[0, 2], &[]),
+                    vec![
+                        (
+                            mib::oid(&VRRP_MIB, &[1, 5, 0], &[]),
+                            Value::IpAddress(mib::ipv4_address(source.parse().ok())),
+                        ),
+                        (
+                            mib::oid(&VRRP_MIB, &[1, 6, 0], &[]),
+                            Value::Integer(match &reason[..] {
+                                "non-matching authentication type" => 2,
+                                _ => 3,
+                            }),
+                        ),
+                    ],
+                ),
+                _ => continue,
+            }
+        };
+
+        // the master agent adds sysUpTime.0 before snmpTrapOID.0
+        let mut pdu = Pdu::new(PDU_NOTIFY, 0, 0, 0);
+        pdu.push_varbind(&mib::SNMP_TRAP_OID, &Value::ObjectIdentifier(trap));
+        for (o, v) in &objects {
+            pdu.push_varbind(o, v);
+        }
+        if let Some(s) = session.lock().unwrap().as_mut() {
+            if let Err(e) = s.send(pdu) {
+                print_error(
+                    debug,
+                    DEBUG_SRC_SNMP,
+                    format!("cannot send an AgentX notification: {}", e),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB2: [u32; 6] = [1, 3, 6, 1, 2, 1];
+
+    // OID below the mib-2 subtree
+    fn oid(subids: &[u32]) -> Oid {
+        let mut oid = MIB2.to_vec();
+        oid.extend_from_slice(subids);
+        oid
+    }
+
+    // objects of the two walked tables
+    fn objects() -> Vec<(Oid, Value)> {
+        vec![
+            (oid(&[10, 1]), Value::Integer(1)),
+            (oid(&[10, 2]), Value::Integer(2)),
+            (oid(&[10, 3]), Value::Integer(3)),
+            (oid(&[20, 1]), Value::Integer(4)),
+            (oid(&[20, 2]), Value::Integer(5)),
+        ]
+    }
+
+    // GetBulk request with one non-repeater (10) and two repeaters (10.1 and 20)
+    fn getbulk(max_repetitions: u16) -> Pdu {
+        let mut pdu = Pdu::new(PDU_GETBULK, 1, 2, 3);
+        pdu.push_u16(1);
+        pdu.push_u16(max_repetitions);
+        pdu.push_oid(&oid(&[10]), false);
+        pdu.push_oid(&[], false);
+        pdu.push_oid(&oid(&[10, 1]), false);
+        pdu.push_oid(&oid(&[20]), false);
+        pdu.push_oid(&oid(&[20]), false);
+        pdu.push_oid(&[], false);
+        pdu
+    }
+
+    // read the varbinds of a response holding integers
+    fn varbinds(pdu: &Pdu) -> Vec<(Oid, Value)> {
+        let mut r = pdu.reader();
+        r.u32().unwrap();
+        assert_eq!(r.u16().unwrap(), ERR_NOERROR);
+        r.u16().unwrap();
+        let mut vbs = Vec::new();
+        while !r.at_end() {
+            let tag = r.u16().unwrap();
+            r.u16().unwrap();
+            let (name, _) = r.oid().unwrap();
+            let value = match tag {
+                2 => Value::Integer(r.u32().unwrap() as i32),
+                130 => Value::EndOfMibView,
+                t => panic!("unexpected varbind type {}", t),
+            };
+            vbs.push((name, value));
+        }
+        vbs
+    }
+
+    #[test]
+    fn getbulk_repetitions() {
+        let resp = answer(&getbulk(2), &objects()).unwrap();
+        assert_eq!(
+            varbinds(&resp),
+            vec![
+                (oid(&[10, 1]), Value::Integer(1)),
+                (oid(&[10, 2]), Value::Integer(2)),
+                (oid(&[20, 1]), Value::Integer(4)),
+                (oid(&[10, 3]), Value::Integer(3)),
+                (oid(&[20, 2]), Value::Integer(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn getbulk_end_of_mib_view() {
+        // the repeaters stop once they all reached the end of their range
+        let resp = answer(&getbulk(10), &objects()).unwrap();
+        let vbs = varbinds(&resp);
+        assert_eq!(vbs.len(), 7);
+        assert_eq!(
+            &vbs[5..],
+            &[
+                (oid(&[10, 3]), Value::EndOfMibView),
+                (oid(&[20, 2]), Value::EndOfMibView),
+            ]
+        );
+    }
+
+    #[test]
+    fn getbulk_non_repeaters_only() {
+        let resp = answer(&getbulk(0), &objects()).unwrap();
+        assert_eq!(varbinds(&resp), vec![(oid(&[10, 1]), Value::Integer(1))]);
+    }
+}
@@ -0,0 +1,247 @@
+//! statistics module
+//! This module counts the virtual routers' advertisements and transitions,
+//! along the lines of the VRRPv3-MIB statistics (RFC6527).
+
+// std
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Drop Reason Enumerator
+/// reasons for which a received advertisement is dropped
+#[derive(Debug, Clone, Copy)]
+pub enum DropReason {
+    Version,      // invalid or non-matching VRRP version
+    Checksum,     // invalid VRRP checksum
+    Ttl,          // IP TTL (or IPv6 hop limit) other than 255
+    Source,       // IPv6 source address other than link-local
+    UnicastPeer,  // not sent by a configured unicast peer
+    OwnedAddress, // destined to an address owned by the virtual router
+    AuthType,     // non-matching authentication type
+    AuthFailure,  // authentication failure
+    Adverint,     // non-matching (VRRPv2) or zero (VRRPv3) advertisement interval
+    UnknownVrid,  // no virtual router for the VRID on the interface
+    InvalidType,  // message type other than ADVERTISEMENT
+    PacketLength, // packet length inconsistent with the address count
+}
+
+// number of drop reasons
+const DROP_REASONS: usize = 12;
+
+// DropReason Type Implementation
+impl DropReason {
+    // all() method
+    /// every drop reason
+    pub fn all() -> [DropReason; DROP_REASONS] {
+        [
+            DropReason::Version,
+            DropReason::Checksum,
+            DropReason::Ttl,
+            DropReason::Source,
+            DropReason::UnicastPeer,
+            DropReason::OwnedAddress,
+            DropReason::AuthType,
+            DropReason::AuthFailure,
+            DropReason::Adverint,
+            DropReason::UnknownVrid,
+            DropReason::InvalidType,
+            DropReason::PacketLength,
+        ]
+    }
+    // name() getter
+    pub fn name(&self) -> &'static str {
+        match self {
+            DropReason::Version => "version",
+            DropReason::Checksum => "checksum",
+            DropReason::Ttl => "ttl",
+            DropReason::Source => "source",
+            DropReason::UnicastPeer => "unicast_peer",
+            DropReason::OwnedAddress => "owned_address",
+            DropReason::AuthType => "auth_type",
+            DropReason::AuthFailure => "auth_failure",
+            DropReason::Adverint => "adverint",
+            DropReason::UnknownVrid => "unknown_vrid",
+            DropReason::InvalidType => "invalid_type",
+            DropReason::PacketLength => "packet_length",
+        }
+    }
+}
+
+/// New Master Reason Enumerator
+/// reason of the last transition to the Master state (vrrpv3StatisticsNewMasterReason)
+#[derive(Debug, Clone, Copy)]
+pub enum NewMasterReason {
+    NotMaster,        // not in Master state
+    Priority,         // owner of the virtual addresses (priority 255)
+    Preempted,        // preempted a lower priority master (or takeover)
+    MasterNoResponse, // the master stopped sending advertisements
+}
+
+// NewMasterReason Type Implementation
+impl NewMasterReason {
+    // name() getter
+    pub fn name(&self) -> &'static str {
+        match self {
+            NewMasterReason::NotMaster => "not_master",
+            NewMasterReason::Priority => "priority",
+            NewMasterReason::Preempted => "preempted",
+            NewMasterReason::MasterNoResponse => "master_no_response",
+        }
+    }
+}
+
+// states the transitions are counted to
+pub const TRANSITION_STATES: [&str; 3] = ["Master", "Backup", "Down"];
+
+/// Statistics Structure
+/// the counters are updated by the listener and worker threads,
+/// holding the virtual router's read or write lock
+#[derive(Debug)]
+pub struct Stats {
+    transitions: [AtomicU64; 3],      // transitions to the TRANSITION_STATES
+    adverts_sent: AtomicU64,          // advertisements sent
+    adverts_rcvd: AtomicU64,          // valid advertisements received
+    drops: [AtomicU64; DROP_REASONS], // advertisements dropped, by reason
+    address_list_errors: AtomicU64,   // advertisements not matching the virtual addresses
+    prio_zero_sent: AtomicU64,        // priority 0 advertisements sent
+    prio_zero_rcvd: AtomicU64,        // priority 0 advertisements received
+    new_master_reason: AtomicU8,      // reason of the last transition to Master
+    state_since: Mutex<Instant>,      // time of the last transition
+    discontinuity: Mutex<SystemTime>, // time of the last counters reset
+}
+
+// Stats Type Implementation
+impl Stats {
+    // new() method
+    pub fn new() -> Stats {
+        Stats {
+            transitions: Default::default(),
+            adverts_sent: AtomicU64::new(0),
+            adverts_rcvd: AtomicU64::new(0),
+            drops: Default::default(),
+            address_list_errors: AtomicU64::new(0),
+            prio_zero_sent: AtomicU64::new(0),
+            prio_zero_rcvd: AtomicU64::new(0),
+            new_master_reason: AtomicU8::new(NewMasterReason::NotMaster as u8),
+            state_since: Mutex::new(Instant::now()),
+            discontinuity: Mutex::new(SystemTime::now()),
+        }
+    }
+    // reset() method
+    /// reset the counters and record the discontinuity time
+    pub fn reset(&self) {
+        let counters = [
+            &self.adverts_sent,
+            &self.adverts_rcvd,
+            &self.address_list_errors,
+            &self.prio_zero_sent,
+            &self.prio_zero_rcvd,
+        ];
+        for c in self
+            .transitions
+            .iter()
+            .chain(self.drops.iter())
+            .chain(counters.iter().cloned())
+        {
+            c.store(0, Ordering::Relaxed);
+        }
+        *self.discontinuity.lock().unwrap() = SystemTime::now();
+    }
+    // count_transition() method
+    /// count a transition to a new state
+    pub fn count_transition(&self, to: &str) {
+        if let Some(i) = TRANSITION_STATES.iter().position(|s| *s == to) {
+            self.transitions[i].fetch_add(1, Ordering::Relaxed);
+        }
+        *self.state_since.lock().unwrap() = Instant::now();
+    }
+    // count_sent() method
+    pub fn count_sent(&self) {
+        self.adverts_sent.fetch_add(1, Ordering::Relaxed);
+    }
+    // count_received() method
+    pub fn count_received(&self) {
+        self.adverts_rcvd.fetch_add(1, Ordering::Relaxed);
+    }
+    // count_drop() method
+    pub fn count_drop(&self, reason: DropReason) {
+        self.drops[reason as usize].fetch_add(1, Ordering::Relaxed);
+    }
+    // count_address_list_error() method
+    pub fn count_address_list_error(&self) {
+        self.address_list_errors.fetch_add(1, Ordering::Relaxed);
+    }
+    // count_prio_zero_sent() method
+    pub fn count_prio_zero_sent(&self) {
+        self.prio_zero_sent.fetch_add(1, Ordering::Relaxed);
+    }
+    // count_prio_zero_received() method
+    pub fn count_prio_zero_received(&self) {
+        self.prio_zero_rcvd.fetch_add(1, Ordering::Relaxed);
+    }
+    // set_new_master_reason() setter
+    pub fn set_new_master_reason(&self, reason: NewMasterReason) {
+        self.new_master_reason
+            .store(reason as u8, Ordering::Relaxed);
+    }
+    // transitions() getter
+    /// number of transitions to a state
+    pub fn transitions(&self, to: &str) -> u64 {
+        match TRANSITION_STATES.iter().position(|s| *s == to) {
+            Some(i) => self.transitions[i].load(Ordering::Relaxed),
+            None => 0,
+        }
+    }
+    // adverts_sent() getter
+    pub fn adverts_sent(&self) -> u64 {
+        self.adverts_sent.load(Ordering::Relaxed)
+    }
+    // adverts_rcvd() getter
+    pub fn adverts_rcvd(&self) -> u64 {
+        self.adverts_rcvd.load(Ordering::Relaxed)
+    }
+    // drops() getter
+    /// number of advertisements dropped for a reason
+    pub fn drops(&self, reason: DropReason) -> u64 {
+        self.drops[reason as usize].load(Ordering::Relaxed)
+    }
+    // address_list_errors() getter
+    pub fn address_list_errors(&self) -> u64 {
+        self.address_list_errors.load(Ordering::Relaxed)
+    }
+    // prio_zero_sent() getter
+    pub fn prio_zero_sent(&self) -> u64 {
+        self.prio_zero_sent.load(Ordering::Relaxed)
+    }
+    // prio_zero_rcvd() getter
+    pub fn prio_zero_rcvd(&self) -> u64 {
+        self.prio_zero_rcvd.load(Ordering::Relaxed)
+    }
+    // new_master_reason() getter
+    pub fn new_master_reason(&self) -> NewMasterReason {
+        match self.new_master_reason.load(Ordering::Relaxed) {
+            1 => NewMasterReason::Priority,
+            2 => NewMasterReason::Preempted,
+            3 => NewMasterReason::MasterNoResponse,
+            _ => NewMasterReason::NotMaster,
+        }
+    }
+    // discontinuity_time() getter
+    /// time of the last counters reset, in seconds since the epoch
+    pub fn discontinuity_time(&self) -> u64 {
+        match self
+            .discontinuity
+            .lock()
+            .unwrap()
+            .duration_since(SystemTime::UNIX_EPOCH)
+        {
+            Ok(d) => d.as_secs(),
+            Err(_) => 0,
+        }
+    }
+    // state_duration() getter
+    /// time spent in the current state
+    pub fn state_duration(&self) -> Duration {
+        self.state_since.lock().unwrap().elapsed()
+    }
+}
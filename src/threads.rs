@@ -19,6 +19,7 @@ use fsm::{fsm_run, Event};
 /// ThreadPool Structure
 pub struct ThreadPool {
     workers: Vec<Worker>,
+    next_id: usize,
 }
 
 // ThreadPool Implementation
@@ -40,7 +41,63 @@ impl ThreadPool {
             workers.push(Worker::new(id, Arc::clone(&vr), vro.parameters.fd(), debug));
         }
 
-        ThreadPool { workers }
+        ThreadPool {
+            next_id: workers.len(),
+            workers,
+        }
+    }
+    // spawn() method
+    // Create a new worker for a virtual router and send it the Startup event
+    #[cfg(target_os = "linux")]
+    pub fn spawn(&mut self, vr: &Arc<RwLock<VirtualRouter>>, debug: &Verbose) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let fd = vr.read().unwrap().parameters.fd();
+        self.workers
+            .push(Worker::new(id, Arc::clone(vr), fd, debug));
+
+        // wait for the worker to register its notification channel
+        loop {
+            if let Some(tx) = vr.read().unwrap().parameters.notification() {
+                // print debugging information
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_EXTENSIVE,
+                    DEBUG_SRC_THREAD,
+                    format!("sending Startup event to worker thread {}", id),
+                );
+                let _r = tx.lock().unwrap().send(Event::Startup);
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+    // remove() method
+    // Shut down the worker of a virtual router and wait for it to exit
+    #[cfg(target_os = "linux")]
+    pub fn remove(&mut self, vr: &Arc<RwLock<VirtualRouter>>, debug: &Verbose) {
+        let pos = match self.workers.iter().position(|w| Arc::ptr_eq(&w.vr, vr)) {
+            Some(pos) => pos,
+            None => return,
+        };
+        let mut worker = self.workers.remove(pos);
+
+        // send Shutdown/Terminate events to the worker
+        if let Some(tx) = vr.read().unwrap().parameters.notification() {
+            let _r = tx.lock().unwrap().send(Event::Shutdown);
+            let _r = tx.lock().unwrap().send(Event::Terminate);
+        }
+
+        // print debugging information
+        print_debug(
+            debug,
+            DEBUG_LEVEL_HIGH,
+            DEBUG_SRC_THREAD,
+            format!("waiting for thread {} to exit...", worker.id),
+        );
+        if let Some(thread) = worker.thread.take() {
+            thread.join().unwrap();
+        }
     }
     // startup() method
     // Send startup event to every worker threads
@@ -106,6 +163,59 @@ impl ThreadPool {
     }
 }
 
+/// Sync Group Structure
+/// Members of a sync group follow each other's Master/Backup transitions
+#[derive(Debug)]
+pub struct SyncGroup {
+    name: String,
+    members: Mutex<Vec<Arc<Mutex<mpsc::Sender<Event>>>>>,
+}
+
+// SyncGroup Implementation
+impl SyncGroup {
+    // new() method
+    pub fn new(name: String) -> SyncGroup {
+        SyncGroup {
+            name,
+            members: Mutex::new(Vec::new()),
+        }
+    }
+    // name() getter
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    // register() method
+    // register the notification channel of a member's worker thread
+    pub fn register(&self, tx: &Arc<Mutex<mpsc::Sender<Event>>>) {
+        self.members.lock().unwrap().push(Arc::clone(tx));
+    }
+    // unregister() method
+    // unregister the notification channel of a member's worker thread
+    pub fn unregister(&self, tx: &Arc<Mutex<mpsc::Sender<Event>>>) {
+        self.members.lock().unwrap().retain(|m| !Arc::ptr_eq(m, tx));
+    }
+    // notify() method
+    // send an event to every member but the sender
+    pub fn notify(&self, from: &Arc<Mutex<mpsc::Sender<Event>>>, event: Event, debug: &Verbose) {
+        for tx in self.members.lock().unwrap().iter() {
+            if Arc::ptr_eq(tx, from) {
+                continue;
+            }
+            // print debugging information
+            print_debug(
+                debug,
+                DEBUG_LEVEL_HIGH,
+                DEBUG_SRC_THREAD,
+                format!(
+                    "sending {:?} event to sync group {} member",
+                    event, self.name
+                ),
+            );
+            let _r = tx.lock().unwrap().send(event.clone());
+        }
+    }
+}
+
 // new Trait FnBox to take ownership of a Self stored in a Box<T>
 pub trait FnBox {
     fn call_box(self: Box<Self>);
@@ -122,6 +232,7 @@ impl<F: FnOnce()> FnBox for F {
 /// Worker Structure
 pub struct Worker {
     id: usize,
+    vr: Arc<RwLock<VirtualRouter>>,
     // we wrap thread::JoinHandle in a Option<T> so we can
     // consume the thread later when calling .join().
     thread: Option<thread::JoinHandle<()>>,
@@ -160,6 +271,7 @@ impl Worker {
 
         Worker {
             id,
+            vr,
             thread: Some(worker_thread),
         }
     }
@@ -23,10 +23,12 @@ use std::sync::RwLock;
 use std::time::{Duration, Instant};
 
 // start_timers() function
-/// starts the various timers needed for the protocol and internal operations
+/// starts the various timers needed for the protocol and internal operations,
+/// they stop once the timers generation differs from 'epoch'
 pub fn start_timers(
     tx: Arc<Mutex<mpsc::Sender<Event>>>,
     vr: Arc<RwLock<VirtualRouter>>,
+    epoch: u32,
     debug: &Verbose,
 ) {
     // clone vr's Arc
@@ -38,8 +40,10 @@ pub fn start_timers(
     let debug = debug.clone();
 
     // set duration from vr's timer
-    let master_down = Duration::from_secs(vr0.timers.master_down() as u64);
-    let advert = Duration::from_secs(vr0.timers.advert() as u64);
+    // (master_down may not be a whole number of seconds with VRRPv3 learned intervals)
+    // disabled (zero or lower) or stopped timers stop on their first expiry
+    let master_down = Duration::from_millis(((vr0.timers.master_down() * 1000.0) as u64).max(1));
+    let advert = Duration::from_millis((vr0.timers.advert().unwrap_or(0) as u64).max(1));
 
     // drop the lock as we don't need read access to vr anymore
     drop(vr0);
@@ -56,7 +60,7 @@ pub fn start_timers(
     // 0x1, no ADVERTISEMENT has been received (since) and the master is signaled
     // down to the approriate vr's thread, this timer share the 'tx' channel with.
     let master_down_int = Interval::new_interval(master_down)
-        .take_while(move |_| future::ok(is_master_down_disabled(&vr1, &debug)))
+        .take_while(move |_| future::ok(is_master_down_disabled(&vr1, epoch, &debug)))
         .for_each(move |_| {
             print_debug(
                 &debug,
@@ -116,7 +120,7 @@ pub fn start_timers(
     // which then trigger an ADVERTISEMENT message in some finite state machine states.
     let advert_int = Interval::new(Instant::now() + advert, advert)
         // must return true to activate the interval timer
-        .take_while(move |_| future::ok(is_advert_disabled(&vr3, &debug)))
+        .take_while(move |_| future::ok(is_advert_disabled(&vr3, epoch, &debug)))
         .for_each(move |_| {
             // print debugging information
             print_debug(
@@ -150,10 +154,13 @@ pub fn start_timers(
 }
 
 // is_master_down_disabled() function
-/// return boolean false is the master_down interval is zero or lower
-fn is_master_down_disabled(vr: &Arc<RwLock<VirtualRouter>>, debug: &Verbose) -> bool {
+/// return boolean false is the master_down interval is zero or lower,
+/// or if the timers have been renewed since this interval started
+fn is_master_down_disabled(vr: &Arc<RwLock<VirtualRouter>>, epoch: u32, debug: &Verbose) -> bool {
     let vr = vr.read().unwrap();
-    if vr.timers.master_down() > 0.0 {
+    if vr.timers.epoch() != epoch {
+        false
+    } else if vr.timers.master_down() > 0.0 {
         true
     } else {
         // print debugging information
@@ -168,14 +175,13 @@ fn is_master_down_disabled(vr: &Arc<RwLock<VirtualRouter>>, debug: &Verbose) ->
 }
 
 // is_advert_disabled() function
-/// return boolean true is the advertisement interval vr's timer
-/// is higher than zero
-fn is_advert_disabled(vr: &Arc<RwLock<VirtualRouter>>, debug: &Verbose) -> bool {
+/// return boolean true is the advertisement vr's timer
+/// is started and the timers have not been renewed
+fn is_advert_disabled(vr: &Arc<RwLock<VirtualRouter>>, epoch: u32, debug: &Verbose) -> bool {
     let vr = vr.read().unwrap();
-    if vr.timers.advert() > 0 {
-        true
-    } else if vr.timers.advert() == 255 {
-        // special non-zero value to disable timer
+    if vr.timers.epoch() != epoch {
+        false
+    } else if vr.timers.advert().is_some() {
         true
     } else {
         // print debugging information
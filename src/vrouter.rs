@@ -13,7 +13,7 @@ use libc::{c_void, write};
 use libc::{sendto, AF_PACKET};
 
 // debugging
-use crate::debug::Verbose;
+use crate::debug::{print_error, Verbose};
 
 // packets related function
 use crate::packets::as_u8_slice;
@@ -27,6 +27,25 @@ use crate::os::freebsd::arp;
 #[cfg(target_os = "linux")]
 use crate::os::linux::arp;
 
+// neighbor discovery protocol
+#[cfg(target_os = "linux")]
+use crate::os::linux::ndp;
+
+// packets
+use crate::packets::VRRPpkt6;
+
+// notify hooks
+use crate::hooks::{Notifier, Transition};
+
+// sync groups
+use crate::threads::SyncGroup;
+
+// statistics
+use crate::stats::{NewMasterReason, Stats};
+
+// std
+use std::net::{IpAddr, Ipv6Addr};
+
 /// Virtual Router Structure
 #[derive(Debug)]
 pub struct VirtualRouter {
@@ -34,6 +53,7 @@ pub struct VirtualRouter {
     pub states: fsm::States,
     pub timers: fsm::Timers,
     pub flags: fsm::Flags,
+    pub stats: Stats,
 }
 
 // VirtualRouter Type Implementation
@@ -44,10 +64,13 @@ impl VirtualRouter {
         vrid: u8,
         ifname: String,
         prio: u8,
-        vip: [u8; 4],
-        advertint: u8,
+        mut vips: Vec<Vip>,
+        advertint: u32,
         preempt: bool,
+        preempt_delay: u32,
+        startup_delay: u32,
         rfc3768: bool,
+        version: u8,
         auth_type: u8,
         auth_secret: Option<String>,
         protocols: Arc<Mutex<Protocols>>,
@@ -57,6 +80,11 @@ impl VirtualRouter {
         vif_name: String,
         fd: i32,
         socket_filter: bool,
+        track_interface: Vec<(String, u8)>,
+        track_script: Vec<(String, u8)>,
+        unicast_peers: Vec<[u8; 4]>,
+        unicast_src: Option<[u8; 4]>,
+        check_ttl: bool,
     ) -> io::Result<VirtualRouter> {
         // --- Linux specific interface handling
         #[cfg(target_os = "linux")]
@@ -78,16 +106,58 @@ impl VirtualRouter {
         // create new IPv4 netmasks vector
         let mut v4masks = Vec::new();
 
-        // build interface IPv4 addresses list
+        // create new IPv6 addresses vector
+        let mut v6addrs = Vec::new();
+
+        // build interface IPv4 and IPv6 addresses lists
         #[cfg(any(target_os = "linux", target_os = "freebsd"))]
         {
             let _r = os::multi::libc::get_addrlist(&ifname, &mut v4addrs, &mut v4masks);
+            let _r = os::multi::libc::get_addrlist6(&ifname, &mut v6addrs);
+        }
+
+        // IPv6 virtual routers (VRRPv3 only)
+        if vips[0].ip().is_ipv6() {
+            // IPv6 virtual routers are only supported on Linux
+            if cfg!(not(target_os = "linux")) {
+                print_error(
+                    debug,
+                    DEBUG_SRC_VR,
+                    format!(
+                        "IPv6 virtual routers are not supported on this platform, vrid {}",
+                        vrid
+                    ),
+                );
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "IPv6 virtual routers not supported",
+                ));
+            }
+            // make sure there is at least one link-local address to source advertisements from
+            if !v6addrs.iter().any(|a| is_ipv6_linklocal(a)) {
+                print_error(
+                    debug,
+                    DEBUG_SRC_VR,
+                    format!(
+                        "at least one IPv6 link-local address must be available on interface {}",
+                        ifname
+                    ),
+                );
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "no ipv6 link-local address configured on vr's interface",
+                ));
+            }
         }
         // make sure there is a least one ip/mask pair, otherwise return an error
-        if v4addrs.is_empty() || v4masks.is_empty() {
-            println!(
-                "error(vr): at least one IPv4 address must be available on interface {}",
-                ifname
+        else if v4addrs.is_empty() || v4masks.is_empty() {
+            print_error(
+                debug,
+                DEBUG_SRC_VR,
+                format!(
+                    "at least one IPv4 address must be available on interface {}",
+                    ifname
+                ),
             );
             return Err(io::Error::new(
                 io::ErrorKind::Other,
@@ -95,17 +165,62 @@ impl VirtualRouter {
             ));
         }
 
+        // the unicast advertisements must be sourced from a local address
+        if let Some(src) = unicast_src {
+            if !v4addrs.contains(&src) {
+                print_error(
+                    debug,
+                    DEBUG_SRC_VR,
+                    format!(
+                        "unicast source {:?} is not an address of interface {}",
+                        src, ifname
+                    ),
+                );
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "unicast source address not configured on vr's interface",
+                ));
+            }
+        }
+
+        // virtual addresses without a prefix length use the primary address' one,
+        // or a /64 for IPv6 virtual addresses
+        for v in vips.iter_mut() {
+            if v.prefixlen.is_none() {
+                v.prefixlen = match v.ip() {
+                    IpAddr::V4(_) => Some(v4masks[0].iter().map(|b| b.count_ones() as u8).sum()),
+                    IpAddr::V6(_) => Some(VRRP_V3_IPV6_VIP_PREFIXLEN),
+                };
+            }
+        }
+
         // print debugging information
         print_debug(
             debug,
             DEBUG_LEVEL_EXTENSIVE,
             DEBUG_SRC_MAIN,
             format!(
-                "creating new virtal-router, vrid {} on interface {}, ipaddrs {:?}",
-                vrid, ifname, v4addrs
+                "creating new virtal-router, vrid {} on interface {}, ipaddrs {:?}, ip6addrs {:?}",
+                vrid, ifname, v4addrs, v6addrs
             ),
         );
 
+        // VRRPv3 removed authentication (RFC5798 9.)
+        let auth_type = if version == 3 && auth_type != 0 {
+            print_debug(
+                debug,
+                DEBUG_LEVEL_LOW,
+                DEBUG_SRC_VR,
+                format!(
+                    "authentication is not supported with VRRPv3, disabling it for vrid {}",
+                    vrid
+                ),
+            );
+            0
+        } else {
+            auth_type
+        };
+
         // verify authentication settings
         match auth_type {
             // if authentication types require a secret
@@ -122,8 +237,15 @@ impl VirtualRouter {
             _ => {}
         }
 
-        // calculate skew_time according to RFC3768 6.1
-        let skew_time: f32 = (256.0 - prio as f32) / 256.0;
+        // advertisement interval in (fractional) seconds
+        let interval = advertint as f32 / 1000.0;
+
+        // calculate skew_time according to RFC3768 6.1,
+        // or RFC5798 6.1 (scaled to the advertisement interval) for VRRPv3
+        let skew_time: f32 = match version {
+            3 => ((256.0 - prio as f32) * interval) / 256.0,
+            _ => (256.0 - prio as f32) / 256.0,
+        };
 
         // return the newly built VirtualRouter
         Ok(VirtualRouter {
@@ -133,14 +255,18 @@ impl VirtualRouter {
                 ifname,
                 ifindex,
                 prio,
-                vip,
+                vips,
                 v4addrs,
                 v4masks,
+                v6addrs,
                 advertint,
                 skew_time,
-                (3.0 * advertint as f32) + skew_time,
+                (3.0 * interval) + skew_time,
                 preempt,
+                preempt_delay,
+                startup_delay,
                 rfc3768,
+                version,
                 auth_type,
                 [0; 8],
                 auth_secret,
@@ -151,23 +277,35 @@ impl VirtualRouter {
                 0,
                 fd,
                 socket_filter,
+                unicast_peers,
+                unicast_src,
+                check_ttl,
+                track_interface
+                    .into_iter()
+                    .map(|(name, decrement)| TrackedObject::new(name, decrement))
+                    .collect(),
+                track_script
+                    .into_iter()
+                    .map(|(name, weight)| TrackedObject::new(name, weight))
+                    .collect(),
                 Option::None,
                 Option::None,
             ),
             // initialize the timers
-            timers: fsm::Timers::new(5.0, 1),
+            timers: fsm::Timers::default(),
             // initialize the flags to 0x1 (down flag set)
             flags: fsm::Flags::new(0x1),
+            // initialize the statistics
+            stats: Stats::new(),
         })
     }
     // is_owner_vip() method
-    // check is the VirtualRouter is the owner of the VIP
-    pub fn is_owner_vip(&self, vip: &[u8; 4]) -> bool {
-        if self.parameters.ipaddrs().contains(vip) {
-            true
-        } else {
-            false
-        }
+    // check is the VirtualRouter is the owner of all the VIPs
+    pub fn is_owner_vip(&self) -> bool {
+        self.parameters.vips().iter().all(|v| match v.ip() {
+            IpAddr::V4(ip) => self.parameters.ipaddrs().contains(&ip.octets()),
+            IpAddr::V6(ip) => self.parameters.ip6addrs().contains(&ip.octets()),
+        })
     }
     // states() getter
     pub fn get_states(&self) -> &fsm::States {
@@ -186,9 +324,83 @@ impl VirtualRouter {
             fsm::States::Init => "Init",
         }
     }
+    // apply_config() method
+    /// update the reloadable parameters from the virtual router's configuration,
+    /// restarting its timers if the priority or the advertisement interval changed
+    pub fn apply_config(&mut self, c: &config::VRConfig) {
+        let prio = self.parameters.prio();
+        let changed = self.parameters.reconfigure(
+            c.priority(),
+            c.preemption(),
+            c.preempt_delay(),
+            c.startup_delay(),
+            c.timer_advert(),
+            c.auth_type(),
+            c.auth_secret(),
+        );
+        if self.parameters.prio() != prio {
+            self.publish(events::EventKind::Priority {
+                from: prio,
+                to: self.parameters.prio(),
+            });
+        }
+        if changed {
+            if let Some(tx) = self.parameters.notification() {
+                let _r = tx.lock().unwrap().send(fsm::Event::Reconfigure);
+            }
+        }
+    }
+    // publish() method
+    /// send an event of the virtual router to the client API's event streams
+    pub fn publish(&self, kind: events::EventKind) {
+        events::publish(
+            self.parameters.vrid(),
+            self.parameters.cfg_interface(),
+            kind,
+        );
+    }
+    // notify_transition() method
+    /// queue the notify hooks of a state transition
+    pub fn notify_transition(&self, old_state: &str) {
+        self.stats.count_transition(self.current_state());
+        if self.current_state() != "Master" {
+            self.stats.set_new_master_reason(NewMasterReason::NotMaster);
+        }
+        self.publish(events::EventKind::Transition {
+            from: old_state.to_string(),
+            to: self.current_state().to_string(),
+        });
+        if let Some(n) = self.parameters.notifier() {
+            n.notify(Transition {
+                group: self.parameters.vrid(),
+                interface: self.parameters.cfg_interface(),
+                vif: self.parameters.macvlan_interface(),
+                vip: self.parameters.attr_vip(),
+                old_state: old_state.to_string(),
+                new_state: self.current_state().to_string(),
+            });
+        }
+    }
     // send_advertisement() method
     /// Send a VRRP ADVERTISEMENT message
     pub fn send_advertisement(&self, fd: i32, debug: &Verbose) -> io::Result<()> {
+        // IPv6 virtual routers
+        let r = if self.parameters.is_ipv6() {
+            self.send_advertisement6(fd, debug)
+        } else {
+            self.send_advertisement4(fd, debug)
+        };
+        if r.is_ok() {
+            self.stats.count_sent();
+            if self.parameters.prio() == 0 {
+                self.stats.count_prio_zero_sent();
+            }
+        }
+        r
+    }
+    // send_advertisement4() method
+    /// Send a VRRP ADVERTISEMENT message over IPv4
+    fn send_advertisement4(&self, fd: i32, debug: &Verbose) -> io::Result<()> {
         // generate initial VRRP ADVERTISEMENT frame/packet
         let advert = VRRPpkt::gen_advert(self);
 
@@ -201,10 +413,11 @@ impl VirtualRouter {
             frame.push(*b);
         }
 
-        // set and push the VIP to the ipaddrs
-        let vip = self.parameters.vip();
-        for i in 0..4 {
-            frame.push(vip[i]);
+        // set and push the VIPs to the ipaddrs
+        for vip in self.parameters.vips4() {
+            for i in 0..4 {
+                frame.push(vip[i]);
+            }
         }
 
         // check if rfc3768 compatibility flag is true
@@ -229,8 +442,10 @@ impl VirtualRouter {
             ),
         );
 
-        // add authentication data
+        // add authentication data (VRRPv2 only)
         match self.parameters.authtype() {
+            // VRRPv3 messages do not carry any authentication data
+            _ if self.parameters.version() == 3 => {}
             // AUTH_TYPE_P0 (PROPRIETARY-TRUNCATED-8B-SHA256)
             // AUTH_TYPE_P1 (PROPRIETARY-XOF-8B-SHAKE256)
             AUTH_TYPE_P0 | AUTH_TYPE_P1 => {
@@ -254,9 +469,32 @@ impl VirtualRouter {
             }
         }
 
+        // --- Linux specific unicast handling
+        #[cfg(target_os = "linux")]
+        {
+            // send the advertisement to every unicast peer instead
+            if !self.parameters.unicast_peers().is_empty() {
+                return self.send_unicast_advertisement(&mut frame[VRRP_V2_FRAME_OFFSET..], debug);
+            }
+        }
+        // END Linux specific unicast handling
+
         // generate VRRP checksum (vrrp checksum is at offset 34+6 bytes)
-        let vrrp_checksum =
-            checksums::one_complement_sum(&frame[VRRP_V2_FRAME_OFFSET..], Option::Some(6));
+        let vrrp_checksum = match self.parameters.version() {
+            // VRRPv3 checksum includes the IPv4 pseudo-header (RFC5798 5.2.8)
+            3 => {
+                let mut data = checksums::ipv4_pseudo_header(
+                    &self.parameters.primary_ip(),
+                    &VRRP_V2_IP_MCAST_DST,
+                    IP_UPPER_PROTO_VRRP,
+                    frame.len() - VRRP_V2_FRAME_OFFSET,
+                );
+                let pseudo_len = data.len();
+                data.extend_from_slice(&frame[VRRP_V2_FRAME_OFFSET..]);
+                checksums::one_complement_sum(&data, Option::Some(pseudo_len + 6))
+            }
+            _ => checksums::one_complement_sum(&frame[VRRP_V2_FRAME_OFFSET..], Option::Some(6)),
+        };
         // print debugging information
         print_debug(
             debug,
@@ -307,9 +545,201 @@ impl VirtualRouter {
         return res;
     }
 
+    // send_unicast_advertisement() method
+    /// Send a VRRP ADVERTISEMENT PDU to every unicast peer
+    #[cfg(target_os = "linux")]
+    fn send_unicast_advertisement(&self, pdu: &mut [u8], debug: &Verbose) -> io::Result<()> {
+        // the advertisement is sent once at least one peer was reached,
+        // otherwise the last error is returned
+        let mut res = Ok(());
+        let mut reached = false;
+        for peer in self.parameters.unicast_peers() {
+            // the VRRPv3 checksum includes the peer address (RFC5798 5.2.8)
+            let vrrp_checksum = match self.parameters.version() {
+                3 => {
+                    let mut data = checksums::ipv4_pseudo_header(
+                        &self.parameters.advert_src(),
+                        peer,
+                        IP_UPPER_PROTO_VRRP,
+                        pdu.len(),
+                    );
+                    let pseudo_len = data.len();
+                    data.extend_from_slice(pdu);
+                    checksums::one_complement_sum(&data, Option::Some(pseudo_len + 6))
+                }
+                _ => checksums::one_complement_sum(pdu, Option::Some(6)),
+            };
+            // set vrrp's checksum field
+            pdu[6] = vrrp_checksum.to_be() as u8;
+            pdu[6 + 1] = vrrp_checksum as u8;
+
+            // print debugging information
+            print_debug(
+                debug,
+                DEBUG_LEVEL_EXTENSIVE,
+                DEBUG_SRC_PACKET,
+                format!(
+                    "sending unicast advertisement to peer {:?} out if {}",
+                    peer,
+                    self.parameters.interface()
+                ),
+            );
+            match os::linux::libc::unicast_sendto(self.parameters.unicast_fd(), *peer, pdu) {
+                Ok(_) => reached = true,
+                Err(e) => {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_VR,
+                        format!(
+                            "cannot send advertisement to unicast peer {:?}: {}",
+                            peer, e
+                        ),
+                    );
+                    res = Err(e);
+                }
+            }
+        }
+        if reached {
+            Ok(())
+        } else {
+            res
+        }
+    }
+
+    // send_advertisement6() method
+    /// Send a VRRPv3 ADVERTISEMENT message over IPv6
+    fn send_advertisement6(&self, fd: i32, debug: &Verbose) -> io::Result<()> {
+        // generate initial VRRPv3 ADVERTISEMENT frame/packet
+        let advert = VRRPpkt6::gen_advert(self);
+
+        // build static frame slice
+        let static_frame = unsafe { as_u8_slice(&advert) };
+
+        // initialize frame vector and push static frame into it
+        let mut frame: Vec<u8> = Vec::new();
+        frame.extend_from_slice(static_frame);
+
+        // push the IPv6 addresses (link-local first)
+        for vip in self.parameters.advert_ips6() {
+            frame.extend_from_slice(&vip);
+        }
+
+        // set payload length of the IPv6 packet (offset 18)
+        let payload_len = frame.len() - VRRP_V3_IPV6_FRAME_OFFSET;
+        frame[IP_FRAME_OFFSET + 4] = (payload_len >> 8) as u8;
+        frame[IP_FRAME_OFFSET + 4 + 1] = payload_len as u8;
+
+        // generate VRRP checksum, including the IPv6 pseudo-header (RFC5798 5.2.8)
+        let mut data = checksums::ipv6_pseudo_header(
+            &self.parameters.primary_ip6(),
+            &VRRP_V3_IPV6_MCAST_DST,
+            IP_UPPER_PROTO_VRRP,
+            payload_len,
+        );
+        let pseudo_len = data.len();
+        data.extend_from_slice(&frame[VRRP_V3_IPV6_FRAME_OFFSET..]);
+        let vrrp_checksum = checksums::one_complement_sum(&data, Option::Some(pseudo_len + 6));
+        // print debugging information
+        print_debug(
+            debug,
+            DEBUG_LEVEL_EXTENSIVE,
+            DEBUG_SRC_PACKET,
+            format!("VRRP checksum is {:#X}", vrrp_checksum),
+        );
+        // set vrrp's checksum field
+        frame[VRRP_V3_IPV6_FRAME_OFFSET + 6] = (vrrp_checksum >> 8) as u8;
+        frame[VRRP_V3_IPV6_FRAME_OFFSET + 6 + 1] = vrrp_checksum as u8;
+
+        // print debugging information
+        print_debug(
+            debug,
+            DEBUG_LEVEL_EXTENSIVE,
+            DEBUG_SRC_PACKET,
+            format!(
+                "sending IPv6 advertisement frame out if {}, {:?}",
+                self.parameters.interface(),
+                frame
+            ),
+        );
+
+        // sending raw ethernet frame
+        let ifindex = self.parameters.ifindex();
+        raw_sendto(fd, ifindex, &mut frame, &debug)
+    }
+
+    // send_unsolicited_na() method
+    /// Send unsolicited Neighbor Advertisements for every IPv6 virtual address
+    #[cfg(target_os = "linux")]
+    pub fn send_unsolicited_na(&self, fd: i32, debug: &Verbose) -> io::Result<()> {
+        let vmac = self.parameters.vmac();
+        for vip in self.parameters.vips6() {
+            // build unsolicited Neighbor Advertisement (RFC4861 7.2.6)
+            let naframe = ndp::NAframe {
+                dst_mac: ETHER_IPV6_ALLNODES_DST_MAC,
+                src_mac: vmac,
+                ethertype: ETHER_P_IPV6.to_be(),
+
+                ipvtcfl: [IP_V6_VERSION, 0, 0, 0],
+                iplength: 32u16.to_be(),
+                ipnexthdr: IP_V6_NEXTHDR_ICMPV6,
+                iphoplimit: IP_V6_HOP_LIMIT_VRRP,
+                ipsrc: vip,
+                ipdst: IP_V6_ALLNODES_MCAST_DST,
+
+                icmp_type: ICMPV6_TYPE_NEIGHBOR_ADVERT,
+                icmp_code: 0,
+                icmp_checksum: 0,
+                flags: [ICMPV6_NA_FLAGS_ROUTER_OVERRIDE, 0, 0, 0],
+                target: vip,
+                opt_type: ICMPV6_OPT_TARGET_LLADDR,
+                opt_len: 1,
+                opt_lladdr: vmac,
+            };
+
+            // generate ICMPv6 checksum (including the IPv6 pseudo-header)
+            let mut frame = unsafe { as_u8_slice(&naframe) }.to_vec();
+            let icmp_offset = IP_FRAME_OFFSET + IP_V6_HDR_SIZE;
+            let mut data = checksums::ipv6_pseudo_header(
+                &vip,
+                &IP_V6_ALLNODES_MCAST_DST,
+                IP_V6_NEXTHDR_ICMPV6,
+                frame.len() - icmp_offset,
+            );
+            let pseudo_len = data.len();
+            data.extend_from_slice(&frame[icmp_offset..]);
+            let checksum = checksums::one_complement_sum(&data, Option::Some(pseudo_len + 2));
+            frame[icmp_offset + 2] = (checksum >> 8) as u8;
+            frame[icmp_offset + 2 + 1] = checksum as u8;
+
+            // print debugging information
+            print_debug(
+                debug,
+                DEBUG_LEVEL_HIGH,
+                DEBUG_SRC_ARP,
+                format!(
+                    "sending unsolicited neighbor advertisement for {} on interface {}",
+                    Ipv6Addr::from(vip),
+                    self.parameters.interface()
+                ),
+            );
+
+            raw_sendto(fd, self.parameters.ifindex(), &mut frame, debug)?;
+        }
+        Ok(())
+    }
+
     // broadcast_gratuitious_arp() function
-    /// Broadcast Gratuitious ARP requests
+    /// Broadcast Gratuitious ARP requests for every virtual IP address
     pub fn broadcast_gratuitious_arp(&self, fd: i32, debug: &Verbose) -> io::Result<()> {
+        for vip in self.parameters.vips4() {
+            self.send_gratuitious_arp(fd, vip, debug)?;
+        }
+        Ok(())
+    }
+
+    // send_gratuitious_arp() function
+    /// Send a Gratuitious ARP request for a virtual IP address
+    fn send_gratuitious_arp(&self, fd: i32, vip: [u8; 4], debug: &Verbose) -> io::Result<()> {
         // suppress warnings about usued variables on linux
         #[cfg(target_os = "linux")]
         let _d = debug;
@@ -326,7 +756,7 @@ impl VirtualRouter {
             proto_addr_len: 4,
             opcode: ARP_OP_REQUEST.to_be(),
             sender_hw_addr: ETHER_VRRP_V2_SRC_MAC,
-            sender_proto_addr: self.parameters.vip(),
+            sender_proto_addr: vip,
             target_hw_addr: [0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
             target_proto_addr: [255, 255, 255, 255],
         };
@@ -399,224 +829,342 @@ impl VirtualRouter {
     // set_ip_addresses() method
     /// set or clear IPv4 addresses on a virtual-router interface
     pub fn set_ip_addresses(&self, fd: i32, op: Operation, debug: &Verbose) {
-        // create addr and netmask vector
+        // IPv6 virtual addresses (always using netlink)
+        #[cfg(target_os = "linux")]
+        {
+            if self.parameters.is_ipv6() {
+                match op {
+                    Operation::Add => self.set_ip6_addresses(Operation::Add, debug),
+                    Operation::Rem => self.set_ip6_addresses(Operation::Rem, debug),
+                }
+                return;
+            }
+        }
+
+        // create addr, netmask and label vectors
         let mut addrs: Vec<[u8; 4]> = Vec::new();
         let mut netmasks: Vec<[u8; 4]> = Vec::new();
+        let mut labels: Vec<Option<String>> = Vec::new();
 
-        // push vr's address(es) inside addr vector
-        for ip in self.parameters.ipaddrs() {
-            addrs.push(*ip);
-        }
-        // push address' netmasks inside the netmask vector
-        for m in self.parameters.ipmasks() {
-            netmasks.push(*m);
-        }
-
-        // if true, add vip and netmask to the respective vectors
-        // make sure this is done last, so the VIP is on top of the addrs vector
-        // otherwise it will never replace the current IP when using ioctls
         match op {
+            // add every vip, with its own netmask and label
             Operation::Add => {
-                // add vip to the IP addresses vector
-                addrs.push(self.parameters.vip());
-                // add first address' netmask
-                netmasks.push(self.parameters.ipmasks()[0]);
+                for v in self.parameters.vips() {
+                    addrs.push(v.octets4());
+                    netmasks.push(v.netmask4());
+                    labels.push(v.label().clone());
+                }
+            }
+            // restore the last (primary) address of the interface,
+            // ioctls replace the current IP rather than adding a new one
+            Operation::Rem => {
+                let idx = self.parameters.ipaddrs().len() - 1;
+                addrs.push(self.parameters.ipaddrs()[idx]);
+                netmasks.push(self.parameters.ipmasks()[idx]);
+                labels.push(None);
             }
-            _ => {}
         }
 
         // construct interface name
         let ifname = CString::new(self.parameters.interface().as_bytes() as &[u8]).unwrap();
 
-        // set the last ip address from vector
-        let idx = addrs.len() - 1;
+        for idx in 0..addrs.len() {
+            // print debugging information
+            print_debug(
+                debug,
+                DEBUG_LEVEL_HIGH,
+                DEBUG_SRC_IP,
+                format!(
+                    "setting IP address {}.{}.{}.{} netmask {}.{}.{}.{} on {:?}",
+                    addrs[idx][0],
+                    addrs[idx][1],
+                    addrs[idx][2],
+                    addrs[idx][3],
+                    netmasks[idx][0],
+                    netmasks[idx][1],
+                    netmasks[idx][2],
+                    netmasks[idx][3],
+                    ifname
+                ),
+            );
 
-        // print debugging information
-        print_debug(
-            debug,
-            DEBUG_LEVEL_HIGH,
-            DEBUG_SRC_IP,
-            format!(
-                "setting IP address {}.{}.{}.{} netmask {}.{}.{}.{} on {:?}",
-                addrs[idx][0],
-                addrs[idx][1],
-                addrs[idx][2],
-                addrs[idx][3],
-                netmasks[idx][0],
-                netmasks[idx][1],
-                netmasks[idx][2],
-                netmasks[idx][3],
-                ifname
-            ),
-        );
+            // --- Linux specific interface tyoe handling
+            #[cfg(target_os = "linux")]
+            {
+                let ifindex = match self.parameters.iftype() {
+                    IfTypes::macvlan => self.parameters.vifidx(),
+                    _ => self.parameters.ifindex(),
+                };
 
-        // --- Linux specific interface tyoe handling
-        #[cfg(target_os = "linux")]
-        {
-            let ifindex = match self.parameters.iftype() {
-                IfTypes::macvlan => self.parameters.vifidx(),
-                _ => self.parameters.ifindex(),
-            };
+                // the address label defaults to the interface name
+                let label = match &labels[idx] {
+                    Some(l) => CString::new(l.as_bytes() as &[u8]).unwrap(),
+                    None => ifname.clone(),
+                };
 
-            // set ifindex on physical or macvlan interface
-            // set virtual ip address according to the network driver in use
-            match self.parameters.netdrv() {
-                NetDrivers::ioctl => {
-                    if let Err(e) =
-                        os::linux::netdev::set_ip_address(fd, &ifname, addrs[idx], netmasks[idx])
-                    {
-                        eprintln!(
-                            "error(ip): error while assigning IP address on interface {:?}: {}",
-                            &ifname, e
+                // set ifindex on physical or macvlan interface
+                // set virtual ip address according to the network driver in use
+                match self.parameters.netdrv() {
+                    NetDrivers::ioctl => {
+                        if let Err(e) = os::linux::netdev::set_ip_address(
+                            fd,
+                            &ifname,
+                            addrs[idx],
+                            netmasks[idx],
+                        ) {
+                            print_error(
+                                debug,
+                                DEBUG_SRC_IP,
+                                format!(
+                                    "error while assigning IP address on interface {:?}: {}",
+                                    &ifname, e
+                                ),
+                            );
+                        }
+                    }
+                    NetDrivers::libnl => {
+                        print_debug(
+                            debug,
+                            DEBUG_LEVEL_HIGH,
+                            DEBUG_SRC_IP,
+                            format!(
+                                "setting up IP address on interface {:?} (ifindex: {}) using netlink (libnl)",
+                                &ifname, ifindex
+                            ),
                         );
+                        if let Err(e) = os::linux::libnl::set_ip_address(
+                            ifindex,
+                            &label,
+                            addrs[idx],
+                            netmasks[idx],
+                            Operation::Add,
+                            debug,
+                        ) {
+                            print_error(
+                                debug,
+                                DEBUG_SRC_IP,
+                                format!(
+                                    "error while assigning IP address on interface {:?}: {}",
+                                    &ifname, e
+                                ),
+                            );
+                        }
                     }
                 }
-                NetDrivers::libnl => {
-                    print_debug(
+            }
+            // END Linux specific interface type handling
+
+            // FreeBSD specific interface type handling
+            #[cfg(target_os = "freebsd")]
+            {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_HIGH,
+                    DEBUG_SRC_IP,
+                    format!("setting ip addresss on interface {:?}, fd {}", ifname, fd),
+                );
+                if let Err(e) = os::freebsd::netinet::set_ip_address(
+                    fd,
+                    &ifname,
+                    addrs[idx],
+                    netmasks[idx],
+                    Operation::Add,
+                ) {
+                    print_error(
                         debug,
-                        DEBUG_LEVEL_HIGH,
                         DEBUG_SRC_IP,
                         format!(
-                    "setting up IP address on interface {:?} (ifindex: {}) using netlink (libnl)",
-                    &ifname, ifindex
-                ),
+                            "error while setting IP address on interface {:?}: {}",
+                            ifname, e
+                        ),
                     );
-                    if let Err(e) = os::linux::libnl::set_ip_address(
-                        ifindex,
-                        &ifname,
-                        addrs[idx],
-                        netmasks[idx],
-                        Operation::Add,
-                        debug,
-                    ) {
-                        eprintln!(
-                            "error(ip): error while assigning IP address on interface {:?}: {}",
-                            &ifname, e
-                        );
-                    }
                 }
             }
+            // END FreeBSD specific interface type handling
         }
-        // END Linux specific interface type handling
-
-        // FreeBSD specific interface type handling
-        #[cfg(target_os = "freebsd")]
-        {
-            print_debug(
-                debug,
-                DEBUG_LEVEL_HIGH,
-                DEBUG_SRC_IP,
-                format!("setting ip addresss on interface {:?}, fd {}", ifname, fd),
-            );
-            if let Err(e) = os::freebsd::netinet::set_ip_address(
-                fd,
-                &ifname,
-                addrs[idx],
-                netmasks[idx],
-                Operation::Add,
-            ) {
-                eprintln!(
-                    "error(ip): error while setting IP address on interface {:?}: {}",
-                    ifname, e
-                );
-            }
-        }
-        // END FreeBSD specific interface type handling
     }
 
     // delete_ip_addresses() method
-    /// delete an ip address on a virtual-router interface
+    /// delete the virtual ip addresses on a virtual-router interface
     pub fn delete_ip_addresses(&self, fd: i32, debug: &Verbose) {
-        // create netmasks vector
-        let mut netmasks: Vec<[u8; 4]> = Vec::new();
-
-        // push first ip's netmask
-        // always take the mask of the primary (top) ip
-        netmasks.push(self.parameters.ipmasks()[0]);
+        // IPv6 virtual addresses (always using netlink)
+        #[cfg(target_os = "linux")]
+        {
+            if self.parameters.is_ipv6() {
+                self.set_ip6_addresses(Operation::Rem, debug);
+                return;
+            }
+        }
 
         // construct interface name
         let ifname = CString::new(self.parameters.interface().as_bytes() as &[u8]).unwrap();
 
-        // print debugging information
-        let vip = self.parameters.vip();
-        print_debug(
-            debug,
-            DEBUG_LEVEL_HIGH,
-            DEBUG_SRC_IP,
-            format!(
-                "removing IP address {}.{}.{}.{} netmask {}.{}.{}.{} on {:?}",
-                vip[0],
-                vip[1],
-                vip[2],
-                vip[3],
-                netmasks[0][0],
-                netmasks[0][1],
-                netmasks[0][2],
-                netmasks[0][3],
-                ifname
-            ),
-        );
+        for v in self.parameters.vips() {
+            let vip = v.octets4();
+            let netmask = v.netmask4();
 
-        // --- Linux specific interface tyoe handling
-        #[cfg(target_os = "linux")]
-        {
-            // workaround compilation warning
-            let _fd = fd;
-            // delete virtual ip address according to the network driver in use
-            match self.parameters.netdrv() {
-                NetDrivers::libnl => {
-                    print_debug(
+            // print debugging information
+            print_debug(
+                debug,
+                DEBUG_LEVEL_HIGH,
+                DEBUG_SRC_IP,
+                format!(
+                    "removing IP address {}.{}.{}.{} netmask {}.{}.{}.{} on {:?}",
+                    vip[0],
+                    vip[1],
+                    vip[2],
+                    vip[3],
+                    netmask[0],
+                    netmask[1],
+                    netmask[2],
+                    netmask[3],
+                    ifname
+                ),
+            );
+
+            // --- Linux specific interface tyoe handling
+            #[cfg(target_os = "linux")]
+            {
+                // workaround compilation warning
+                let _fd = fd;
+                // the address label defaults to the interface name
+                let label = match v.label() {
+                    Some(l) => CString::new(l.as_bytes() as &[u8]).unwrap(),
+                    None => ifname.clone(),
+                };
+                // delete virtual ip address according to the network driver in use
+                match self.parameters.netdrv() {
+                    NetDrivers::libnl => {
+                        print_debug(
+                            debug,
+                            DEBUG_LEVEL_HIGH,
+                            DEBUG_SRC_IP,
+                            format!(
+                                "removing IP address on interface {:?} (ifindex: {}) using netlink (libnl)",
+                                &ifname,
+                                self.parameters.ifindex()
+                            ),
+                        );
+                        if let Err(e) = os::linux::libnl::set_ip_address(
+                            self.parameters.ifindex(),
+                            &label,
+                            vip,
+                            netmask,
+                            Operation::Rem,
+                            debug,
+                        ) {
+                            print_error(
+                                debug,
+                                DEBUG_SRC_IP,
+                                format!(
+                                    "error while removing IP address on interface {:?}: {}",
+                                    &ifname, e
+                                ),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // END Linux specific interface type handling
+
+            // FreeBSD specific interface type handling
+            #[cfg(target_os = "freebsd")]
+            {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_HIGH,
+                    DEBUG_SRC_IP,
+                    format!("setting ip addresss on interface {:?}, fd {}", ifname, fd),
+                );
+                if let Err(e) =
+                    os::freebsd::netinet::set_ip_address(fd, &ifname, vip, netmask, Operation::Rem)
+                {
+                    print_error(
                         debug,
-                        DEBUG_LEVEL_HIGH,
                         DEBUG_SRC_IP,
                         format!(
-                        "removing IP address on interface {:?} (ifindex: {}) using netlink (libnl)",
-                        &ifname,
-                        self.parameters.ifindex()
-                    ),
+                            "error while setting IP address on interface {:?}: {}",
+                            ifname, e
+                        ),
                     );
-                    if let Err(e) = os::linux::libnl::set_ip_address(
-                        self.parameters.ifindex(),
-                        &ifname,
-                        self.parameters.vip(),
-                        netmasks[0],
-                        Operation::Rem,
-                        debug,
-                    ) {
-                        eprintln!(
-                            "error(ip): error while removing IP address on interface {:?}: {}",
-                            &ifname, e
-                        );
-                    }
                 }
-                _ => {}
             }
+            // END FreeBSD specific interface type handling
         }
-        // END Linux specific interface type handling
+    }
 
-        // FreeBSD specific interface type handling
-        #[cfg(target_os = "freebsd")]
-        {
+    // set_ip6_addresses() method
+    /// add or remove the IPv6 virtual addresses on a virtual-router interface
+    #[cfg(target_os = "linux")]
+    fn set_ip6_addresses(&self, op: Operation, debug: &Verbose) {
+        // construct interface name
+        let ifname = CString::new(self.parameters.interface().as_bytes() as &[u8]).unwrap();
+
+        // set ifindex on physical or macvlan interface
+        let ifindex = match self.parameters.iftype() {
+            IfTypes::macvlan => self.parameters.vifidx(),
+            _ => self.parameters.ifindex(),
+        };
+
+        for vip in self.parameters.vips6() {
+            // link-local addresses are always /64
+            let prefixlen = if is_ipv6_linklocal(&vip) {
+                IP_V6_LINKLOCAL_PREFIXLEN
+            } else {
+                self.parameters
+                    .vips()
+                    .iter()
+                    .find(|v| v.ip() == IpAddr::from(vip))
+                    .and_then(|v| v.prefixlen())
+                    .unwrap_or(VRRP_V3_IPV6_VIP_PREFIXLEN)
+            };
+            // print debugging information
             print_debug(
                 debug,
                 DEBUG_LEVEL_HIGH,
                 DEBUG_SRC_IP,
-                format!("setting ip addresss on interface {:?}, fd {}", ifname, fd),
+                format!(
+                    "{} IPv6 address {}/{} on {:?} (ifindex: {}) using netlink (libnl)",
+                    match op {
+                        Operation::Add => "setting",
+                        Operation::Rem => "removing",
+                    },
+                    Ipv6Addr::from(vip),
+                    prefixlen,
+                    ifname,
+                    ifindex
+                ),
             );
-            if let Err(e) = os::freebsd::netinet::set_ip_address(
-                fd,
-                &ifname,
-                self.parameters.vip,
-                netmasks[0],
-                Operation::Rem,
-            ) {
-                eprintln!(
-                    "error(ip): error while setting IP address on interface {:?}: {}",
-                    ifname, e
+            let r = match op {
+                Operation::Add => os::linux::libnl::set_ip6_address(
+                    ifindex,
+                    &ifname,
+                    vip,
+                    prefixlen,
+                    Operation::Add,
+                    debug,
+                ),
+                Operation::Rem => os::linux::libnl::set_ip6_address(
+                    ifindex,
+                    &ifname,
+                    vip,
+                    prefixlen,
+                    Operation::Rem,
+                    debug,
+                ),
+            };
+            if let Err(e) = r {
+                print_error(
+                    debug,
+                    DEBUG_SRC_IP,
+                    format!(
+                        "error while setting IPv6 address on interface {:?}: {}",
+                        &ifname, e
+                    ),
                 );
             }
         }
-        // END FreeBSD specific interface type handling
     }
 
     // get_mac_addresses() method
@@ -632,9 +1180,13 @@ impl VirtualRouter {
             match os::linux::netdev::get_mac_addr(fd, &ifname, debug) {
                 Ok(mac) => mac,
                 Err(e) => {
-                    eprintln!(
-                        "error(mac): error while getting MAC address on interface {:?}: {}",
-                        ifname, e
+                    print_error(
+                        debug,
+                        DEBUG_SRC_MAC,
+                        format!(
+                            "error while getting MAC address on interface {:?}: {}",
+                            ifname, e
+                        ),
                     );
                     [0, 0, 0, 0, 0, 0]
                 }
@@ -655,7 +1207,11 @@ impl VirtualRouter {
         {
             // set mac address
             match os::linux::netdev::set_mac_addr(fd, &ifname, mac, debug) {
-                Err(e) => eprintln!("error(mac): error while setting mac address: {}", e),
+                Err(e) => print_error(
+                    debug,
+                    DEBUG_SRC_MAC,
+                    format!("error while setting mac address: {}", e),
+                ),
                 _ => {}
             }
         }
@@ -663,16 +1219,13 @@ impl VirtualRouter {
     }
 
     // set_ip_routes() method
-    /// set or unset IPv4 routes on virtual-router interfaces
+    /// set or unset IPv4 and IPv6 routes on virtual-router interfaces
     #[cfg(target_os = "linux")]
     pub fn set_ip_routes(&mut self, fd: i32, op: Operation, debug: &Verbose) {
         // acquire mutex lock on protocols
         let protocols = &self.parameters.protocols();
         let protocols = protocols.lock().unwrap();
 
-        // construct interface name
-        let ifname = CString::new(self.parameters.interface().as_bytes() as &[u8]).unwrap();
-
         // ensure routes are added or deleted only once per virtual router
         // routes may be added or removed multiple times across virtual routers
         match op {
@@ -693,70 +1246,7 @@ impl VirtualRouter {
             Some(r) => {
                 // for every static routes
                 for st in r {
-                    // --- Linux specific interface tyoe handling
-                    #[cfg(target_os = "linux")]
-                    {
-                        // add route acccording to the network driver in use
-                        match self.parameters.netdrv() {
-                            NetDrivers::ioctl => {
-                                print_debug(
-                                debug,
-                                DEBUG_LEVEL_HIGH,
-                                DEBUG_SRC_IP,
-                                format!(
-                                "setting up route on interface {:?} (ifindex: {}) using netlink (ioctl)",
-                                &ifname, self.parameters.ifindex()
-                            ),
-                            );
-                                if let Err(e) = os::linux::netdev::set_ip_route(
-                                    fd,
-                                    &self.parameters.interface(),
-                                    st.route(),
-                                    st.mask(),
-                                    st.nh(),
-                                    st.metric(),
-                                    st.mtu(),
-                                    &op,
-                                    debug,
-                                ) {
-                                    eprintln!(
-                                        "error(route): cannot add or delete route {:?}: {}",
-                                        st.route(),
-                                        e
-                                    );
-                                }
-                            }
-                            NetDrivers::libnl => {
-                                print_debug(
-                                debug,
-                                DEBUG_LEVEL_HIGH,
-                                DEBUG_SRC_IP,
-                                format!(
-                                "setting up route on interface {:?} (ifindex: {}) using netlink (libnl)",
-                                &ifname, self.parameters.ifindex()
-                            ),
-                            );
-                                if let Err(e) = os::linux::libnl::set_ip_route(
-                                    fd,
-                                    &self.parameters.interface(),
-                                    st.route(),
-                                    st.mask(),
-                                    st.nh(),
-                                    st.metric(),
-                                    st.mtu(),
-                                    &op,
-                                    debug,
-                                ) {
-                                    eprintln!(
-                                        "error(route): cannot add or delete route {:?}: {}",
-                                        st.route(),
-                                        e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    // END Linux specific interface type handling
+                    self.set_ip_route(fd, st, &op, debug);
                 }
             }
             None => {}
@@ -769,6 +1259,77 @@ impl VirtualRouter {
         }
     }
 
+    // set_ip_route() method
+    /// set or unset a single IPv4 or IPv6 static route on the virtual-router interface
+    #[cfg(target_os = "linux")]
+    pub fn set_ip_route(&self, fd: i32, st: &Static, op: &Operation, debug: &Verbose) {
+        // construct interface name
+        let ifname = CString::new(self.parameters.interface().as_bytes() as &[u8]).unwrap();
+
+        // add route acccording to the network driver in use
+        match (self.parameters.netdrv(), st.route(), st.mask(), st.nh()) {
+            (NetDrivers::ioctl, IpAddr::V4(route), IpAddr::V4(mask), IpAddr::V4(nh)) => {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_HIGH,
+                    DEBUG_SRC_IP,
+                    format!(
+                        "setting up route on interface {:?} (ifindex: {}) using netlink (ioctl)",
+                        &ifname,
+                        self.parameters.ifindex()
+                    ),
+                );
+                if let Err(e) = os::linux::netdev::set_ip_route(
+                    fd,
+                    &self.parameters.interface(),
+                    route.octets(),
+                    mask.octets(),
+                    nh.octets(),
+                    st.metric(),
+                    st.mtu(),
+                    op,
+                    debug,
+                ) {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_ROUTE,
+                        format!("cannot add or delete route {}: {}", st.route(), e),
+                    );
+                }
+            }
+            // the IPv6 routes are always set using libnl
+            _ => {
+                print_debug(
+                    debug,
+                    DEBUG_LEVEL_HIGH,
+                    DEBUG_SRC_IP,
+                    format!(
+                        "setting up route on interface {:?} (ifindex: {}) using netlink (libnl)",
+                        &ifname,
+                        self.parameters.ifindex()
+                    ),
+                );
+                if let Err(e) = os::linux::libnl::set_ip_route(
+                    fd,
+                    &self.parameters.interface(),
+                    st.route(),
+                    st.mask(),
+                    st.nh(),
+                    st.metric(),
+                    st.mtu(),
+                    op,
+                    debug,
+                ) {
+                    print_error(
+                        debug,
+                        DEBUG_SRC_ROUTE,
+                        format!("cannot add or delete route {}: {}", st.route(), e),
+                    );
+                }
+            }
+        }
+    }
+
     // setup_mac_vlan_link() method (Linux specific)
     #[cfg(target_os = "linux")]
     pub fn setup_macvlan_link(
@@ -810,10 +1371,16 @@ impl VirtualRouter {
             }
             // catched an error while setting up the macvlan interface
             Err(e) => {
-                eprintln!(
-                "error(macvlan): cannot perform operation {:?} on macvlan interface (master if: {:?}): {}",
-                op, self.parameters.interface(), e
-            );
+                print_error(
+                    debug,
+                    DEBUG_SRC_MACVLAN,
+                    format!(
+                        "cannot perform operation {:?} on macvlan interface (master if: {:?}): {}",
+                        op,
+                        self.parameters.interface(),
+                        e
+                    ),
+                );
                 None
             }
         }
@@ -827,14 +1394,19 @@ pub struct Parameters {
     interface: String,           // Interface where the virtual router is running
     ifindex: i32,                // Interface ifindex
     prio: u8,                    // Priority (0-255)
-    vip: [u8; 4],                // Virtual IP (not in RFC parameters list)
+    vips: Vec<Vip>,              // Virtual IP(s) (not in RFC parameters list)
     ipaddrs: Vec<[u8; 4]>, // One or more local IPv4 Addresse(s) associated with the virtual router
     ipmasks: Vec<[u8; 4]>, // IPv4 Netmask(s) of above IP addresses
-    adverint: u8,          // Advertisement interval
+    ip6addrs: Vec<[u8; 16]>, // Local IPv6 Addresse(s) of the virtual router interface
+    adverint: u32,         // Advertisement interval (milliseconds)
     skew_time: f32,        // Time to skew Master_Down interval (second)
     master_down: f32,      // Time interval for Backup to declare Master Down
     preempt_mode: bool, // Control whether a higher-priority Backup router can preempt a lower-priority Master
+    preempt_delay: u32, // Time to wait before preempting a lower-priority Master (second)
+    startup_delay: u32, // Time to wait before starting up (second)
     rfc3768: bool,      // RFC2338 compatibility flag
+    version: u8,        // VRRP protocol version (2 or 3)
+    master_adverint: u16, // Master advertisement interval learned from the master (centiseconds)
     auth_type: u8,      // Authentication type being used
     auth_data: [u8; 8], // Autentication data (type specific)
     auth_secret: Option<String>, // Authentication secret
@@ -844,11 +1416,23 @@ pub struct Parameters {
     netdrv: NetDrivers, // Network driver
     iftype: IfTypes,    // Interfaces type
     vif_name: String,   // Virtual interface name (or physical when saved)
+    cfg_interface: String, // Configured interface name (never swapped)
     vif_idx: i32,       // Virtual interface ifindex
     fd: i32,            // Raw socket or BPF file descriptor
     socket_filter: bool, // Linux socket filter support
+    unicast_peers: Vec<[u8; 4]>, // Unicast peers (advertisements are sent by unicast)
+    unicast_src: Option<[u8; 4]>, // Unicast advertisements source address
+    unicast_fd: i32,    // Unicast advertisements raw socket file descriptor
+    check_ttl: bool,    // Verify the advertisements' TTL is 255
+    base_prio: u8,      // Configured priority (before tracking decrements)
+    tracked: Vec<TrackedObject>, // Tracked interfaces
+    tracked_scripts: Vec<TrackedObject>, // Track scripts
     capi_tx: Option<Sender<FSMQueryResult>>, // Client API sender channel
     capi_rx: Option<Receiver<FSMQueryResult>>, // Client API receiver channel
+    notifier: Option<Notifier>, // Notify hooks runner
+    sync_group: Option<Arc<SyncGroup>>, // Sync group the virtual router belongs to
+    last_master: Option<IpAddr>, // Source of the last received advertisement
+    last_master_prio: u8, // Priority of the last received advertisement
 }
 
 /// Parameters Type Implementation
@@ -859,14 +1443,18 @@ impl Parameters {
         interface: String,
         ifindex: i32,
         prio: u8,
-        vip: [u8; 4],
+        vips: Vec<Vip>,
         ipaddrs: Vec<[u8; 4]>,
         ipmasks: Vec<[u8; 4]>,
-        adverint: u8,
+        ip6addrs: Vec<[u8; 16]>,
+        adverint: u32,
         skew_time: f32,
         master_down: f32,
         preempt_mode: bool,
+        preempt_delay: u32,
+        startup_delay: u32,
         rfc3768: bool,
+        version: u8,
         auth_type: u8,
         auth_data: [u8; 8],
         auth_secret: Option<String>,
@@ -877,22 +1465,33 @@ impl Parameters {
         vif_idx: i32,
         fd: i32,
         socket_filter: bool,
+        unicast_peers: Vec<[u8; 4]>,
+        unicast_src: Option<[u8; 4]>,
+        check_ttl: bool,
+        tracked: Vec<TrackedObject>,
+        tracked_scripts: Vec<TrackedObject>,
         capi_tx: Option<Sender<FSMQueryResult>>,
         capi_rx: Option<Receiver<FSMQueryResult>>,
     ) -> Parameters {
         Parameters {
             vrid,
+            cfg_interface: interface.clone(),
             interface,
             ifindex,
             prio,
-            vip,
+            vips,
             ipaddrs,
             ipmasks,
+            ip6addrs,
             adverint,
             skew_time,
             master_down,
             preempt_mode,
+            preempt_delay,
+            startup_delay,
             rfc3768,
+            version,
+            master_adverint: (adverint / 10) as u16,
             auth_type,
             auth_data,
             auth_secret,
@@ -905,8 +1504,19 @@ impl Parameters {
             vif_idx,
             fd,
             socket_filter,
+            unicast_peers,
+            unicast_src,
+            unicast_fd: -1,
+            check_ttl,
+            base_prio: prio,
+            tracked,
+            tracked_scripts,
             capi_tx,
             capi_rx,
+            notifier: Option::None,
+            sync_group: Option::None,
+            last_master: Option::None,
+            last_master_prio: 0,
         }
     }
     // vrid() getter
@@ -922,6 +1532,23 @@ impl Parameters {
     pub fn set_interface(&mut self, intf: String) {
         self.interface = intf;
     }
+    // cfg_interface() getter
+    /// name of the configured interface, whatever the state of the virtual router
+    /// (the running interface of a macvlan master is its virtual interface)
+    pub fn cfg_interface(&self) -> String {
+        self.cfg_interface.clone()
+    }
+    // macvlan_interface() method
+    /// name of the virtual interface of a macvlan virtual router
+    pub fn macvlan_interface(&self) -> Option<String> {
+        match self.iftype {
+            IfTypes::macvlan if self.interface != self.cfg_interface => {
+                Some(self.interface.clone())
+            }
+            IfTypes::macvlan => Some(self.vif_name.clone()),
+            _ => None,
+        }
+    }
     // ifindex() getter
     pub fn ifindex(&self) -> i32 {
         self.ifindex
@@ -934,9 +1561,23 @@ impl Parameters {
     pub fn set_prio(&mut self, prio: u8) {
         self.prio = prio;
     }
-    // vip() getter
+    // vip() method
+    // first (primary) virtual IPv4 address
     pub fn vip(&self) -> [u8; 4] {
-        self.vip
+        self.vips[0].octets4()
+    }
+    // vips() getter
+    pub fn vips(&self) -> &Vec<Vip> {
+        &self.vips
+    }
+    // vips4() method
+    // all the virtual IPv4 addresses
+    pub fn vips4(&self) -> Vec<[u8; 4]> {
+        self.vips
+            .iter()
+            .filter(|v| v.ip().is_ipv4())
+            .map(|v| v.octets4())
+            .collect()
     }
     // ipaddrs() getter
     pub fn ipaddrs(&self) -> &Vec<[u8; 4]> {
@@ -946,10 +1587,82 @@ impl Parameters {
     pub fn ipmasks(&self) -> &Vec<[u8; 4]> {
         &self.ipmasks
     }
+    // is_ipv6() method
+    pub fn is_ipv6(&self) -> bool {
+        self.vips[0].ip().is_ipv6()
+    }
+    // vips6() method
+    // IPv6 virtual addresses, the configured link-local
+    // virtual address first
+    pub fn vips6(&self) -> Vec<[u8; 16]> {
+        let mut vips6: Vec<[u8; 16]> = self
+            .vips
+            .iter()
+            .filter_map(|v| match v.ip() {
+                IpAddr::V6(ip) => Some(ip.octets()),
+                IpAddr::V4(_) => None,
+            })
+            .collect();
+        if let Some(idx) = vips6.iter().position(|v| is_ipv6_linklocal(v)) {
+            let ll = vips6.remove(idx);
+            vips6.insert(0, ll);
+        }
+        vips6
+    }
+    // advert_ips6() method
+    // IPv6 addresses of the advertisements, the first one must be a
+    // link-local address (RFC5798 5.2.9): the configured link-local
+    // virtual address, or the interface's link-local address
+    pub fn advert_ips6(&self) -> Vec<[u8; 16]> {
+        let mut ips6 = self.vips6();
+        match ips6.first() {
+            Some(ip) if is_ipv6_linklocal(ip) => (),
+            _ => ips6.insert(0, self.primary_ip6()),
+        }
+        ips6
+    }
+    // ip6addrs() getter
+    pub fn ip6addrs(&self) -> &Vec<[u8; 16]> {
+        &self.ip6addrs
+    }
+    // primary_ip6() method
+    // first link-local address of the interface
+    pub fn primary_ip6(&self) -> [u8; 16] {
+        match self.ip6addrs.iter().find(|a| is_ipv6_linklocal(a)) {
+            Some(a) => *a,
+            None => [0; 16],
+        }
+    }
+    // primary_ipaddr() method
+    // primary address used to source advertisements
+    pub fn primary_ipaddr(&self) -> IpAddr {
+        if self.is_ipv6() {
+            IpAddr::from(self.primary_ip6())
+        } else {
+            IpAddr::from(self.primary_ip())
+        }
+    }
+    // vmac() method
+    // virtual router MAC address (00-00-5E-00-01-{VRID} or 00-00-5E-00-02-{VRID})
+    pub fn vmac(&self) -> [u8; 6] {
+        let mut vmac = if self.is_ipv6() {
+            ETHER_VRRP_V3_IPV6_SRC_MAC
+        } else {
+            ETHER_VRRP_V2_SRC_MAC
+        };
+        vmac[5] = self.vrid;
+        vmac
+    }
     // adverint() getter
-    pub fn adverint(&self) -> u8 {
+    // advertisement interval in milliseconds
+    pub fn adverint(&self) -> u32 {
         self.adverint
     }
+    // adverint_secs() method
+    // advertisement interval in seconds, as sent in VRRPv2 messages
+    pub fn adverint_secs(&self) -> u8 {
+        (self.adverint / 1000) as u8
+    }
     // skewtime() getter
     pub fn skewtime(&self) -> f32 {
         self.skew_time
@@ -962,10 +1675,154 @@ impl Parameters {
     pub fn preempt(&self) -> bool {
         self.preempt_mode
     }
+    // preempt_delay() getter
+    pub fn preempt_delay(&self) -> u32 {
+        self.preempt_delay
+    }
+    // startup_delay() getter
+    pub fn startup_delay(&self) -> u32 {
+        self.startup_delay
+    }
     // rfc3768() getter
     pub fn rfc3768(&self) -> bool {
         self.rfc3768
     }
+    // version() getter
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+    // adverint_cs() method
+    // advertisement interval in centiseconds, as sent in VRRPv3 messages
+    pub fn adverint_cs(&self) -> u16 {
+        let cs = self.adverint / 10;
+        if cs > VRRP_V3_MAX_ADVERINT as u32 {
+            VRRP_V3_MAX_ADVERINT
+        } else {
+            cs as u16
+        }
+    }
+    // master_adverint() getter
+    pub fn master_adverint(&self) -> u16 {
+        self.master_adverint
+    }
+    // set_master_adverint() setter
+    // learn the master advertisement interval (RFC5798 6.4.2) and
+    // recompute skew_time and master_down interval accordingly
+    pub fn set_master_adverint(&mut self, cs: u16) {
+        self.master_adverint = cs;
+        self.update_skew_time();
+    }
+    // update_skew_time() method
+    // recompute skew_time and master_down interval from the current priority
+    fn update_skew_time(&mut self) {
+        let (interval, skew) = match self.version {
+            3 => {
+                let interval = self.master_adverint as f32 / 100.0;
+                (interval, ((256.0 - self.prio as f32) * interval) / 256.0)
+            }
+            _ => (
+                self.adverint as f32 / 1000.0,
+                (256.0 - self.prio as f32) / 256.0,
+            ),
+        };
+        self.skew_time = skew;
+        self.master_down = (3.0 * interval) + self.skew_time;
+    }
+    // tracked() getter
+    pub fn tracked(&self) -> &Vec<TrackedObject> {
+        &self.tracked
+    }
+    // is_tracking() method
+    // return true if the interface is tracked by this virtual router
+    pub fn is_tracking(&self, ifname: &str) -> bool {
+        self.tracked.iter().any(|t| t.name == ifname)
+    }
+    // set_link_state() method
+    // update a tracked interface's link state, return true if it changed
+    pub fn set_link_state(&mut self, ifname: &str, up: bool) -> bool {
+        let mut changed = false;
+        for t in self.tracked.iter_mut().filter(|t| t.name == ifname) {
+            if t.up != up {
+                t.up = up;
+                changed = true;
+            }
+        }
+        changed
+    }
+    // tracked_scripts() getter
+    pub fn tracked_scripts(&self) -> &Vec<TrackedObject> {
+        &self.tracked_scripts
+    }
+    // is_tracking_script() method
+    // return true if the track script is followed by this virtual router
+    pub fn is_tracking_script(&self, name: &str) -> bool {
+        self.tracked_scripts.iter().any(|t| t.name == name)
+    }
+    // set_script_state() method
+    // update a track script's state, return true if it changed
+    pub fn set_script_state(&mut self, name: &str, healthy: bool) -> bool {
+        let mut changed = false;
+        for t in self.tracked_scripts.iter_mut().filter(|t| t.name == name) {
+            if t.up != healthy {
+                t.up = healthy;
+                changed = true;
+            }
+        }
+        changed
+    }
+    // tracked_prio() method
+    // configured priority lowered by the decrement of every
+    // tracked interface down and every failed track script
+    pub fn tracked_prio(&self) -> u8 {
+        let decrement: u32 = self
+            .tracked
+            .iter()
+            .chain(self.tracked_scripts.iter())
+            .filter(|t| !t.up)
+            .map(|t| t.decrement as u32)
+            .sum();
+        if decrement >= self.base_prio as u32 {
+            1
+        } else {
+            self.base_prio - decrement as u8
+        }
+    }
+    // reconfigure() method
+    /// update the reloadable parameters in place, return true if the
+    /// priority or the advertisement interval changed
+    pub fn reconfigure(
+        &mut self,
+        prio: u8,
+        preempt: bool,
+        preempt_delay: u32,
+        startup_delay: u32,
+        adverint: u32,
+        auth_type: u8,
+        auth_secret: Option<String>,
+    ) -> bool {
+        let old = (self.prio, self.adverint);
+        self.base_prio = prio;
+        self.preempt_mode = preempt;
+        self.preempt_delay = preempt_delay;
+        self.startup_delay = startup_delay;
+        self.adverint = adverint;
+        // VRRPv3 removed authentication (RFC5798 9.)
+        self.auth_type = if self.version == 3 { 0 } else { auth_type };
+        self.auth_secret = auth_secret;
+        // the address owner (255) and a shutting down virtual router (0)
+        // keep their priority
+        if self.prio != 255 && self.prio != 0 {
+            self.prio = self.tracked_prio();
+        }
+        self.update_skew_time();
+        old != (self.prio, self.adverint)
+    }
+    // update_prio() method
+    // set the running priority and recompute the timers depending on it
+    pub fn update_prio(&mut self, prio: u8) {
+        self.prio = prio;
+        self.update_skew_time();
+    }
     // authtype() getter
     pub fn authtype(&self) -> u8 {
         self.auth_type
@@ -976,11 +1833,15 @@ impl Parameters {
     }
     // addrcount() method
     pub fn addrcount(&self) -> u8 {
-        // calculate the number of addresses (or arrays) in ipaddrs vector
-        let num = *&self.ipaddrs.len() as u8;
-        // if rfc3768 compatibility flag is false, add one to account for the VIP
+        // IPv6 virtual routers only advertise their virtual addresses (and a link-local one)
+        if self.is_ipv6() {
+            return self.advert_ips6().len() as u8;
+        }
+        // calculate the number of virtual addresses
+        let num = self.vips4().len() as u8;
+        // if rfc3768 compatibility flag is false, add the local addresses
         if !self.rfc3768 {
-            num + 1
+            num + self.ipaddrs.len() as u8
         } else {
             num
         }
@@ -994,6 +1855,42 @@ impl Parameters {
     pub fn notification(&self) -> &Option<Arc<Mutex<mpsc::Sender<fsm::Event>>>> {
         &self.notification
     }
+    // notifier() getter
+    pub fn notifier(&self) -> &Option<Notifier> {
+        &self.notifier
+    }
+    // set_notifier() setter
+    pub fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = Option::Some(notifier);
+    }
+    // sync_group() getter
+    pub fn sync_group(&self) -> Option<Arc<SyncGroup>> {
+        self.sync_group.clone()
+    }
+    // set_last_master() method
+    /// record the source of a received advertisement, return true if it is a new master
+    pub fn set_last_master(&mut self, master: IpAddr, prio: u8) -> bool {
+        let new = self.last_master != Some(master);
+        self.last_master = Some(master);
+        self.last_master_prio = prio;
+        new
+    }
+    // last_master() getter
+    pub fn last_master(&self) -> Option<IpAddr> {
+        self.last_master
+    }
+    // last_master_prio() getter
+    pub fn last_master_prio(&self) -> u8 {
+        self.last_master_prio
+    }
+    // set_sync_group() setter
+    pub fn set_sync_group(&mut self, sync_group: Arc<SyncGroup>) {
+        self.sync_group = Option::Some(sync_group);
+    }
+    // clear_sync_group() setter
+    pub fn clear_sync_group(&mut self) {
+        self.sync_group = Option::None;
+    }
     // set_notification() setter
     pub fn set_notification(&mut self, chan: Arc<Mutex<mpsc::Sender<fsm::Event>>>) {
         self.notification = Option::Some(chan);
@@ -1056,6 +1953,35 @@ impl Parameters {
     pub fn set_fd(&mut self, fd: i32) {
         self.fd = fd;
     }
+    // unicast_peers() getter
+    pub fn unicast_peers(&self) -> &Vec<[u8; 4]> {
+        &self.unicast_peers
+    }
+    // unicast_src() getter
+    pub fn unicast_src(&self) -> Option<[u8; 4]> {
+        self.unicast_src
+    }
+    // advert_src() method
+    /// source address of the advertisements (unicast source or primary address)
+    pub fn advert_src(&self) -> [u8; 4] {
+        match self.unicast_src {
+            Some(src) => src,
+            None => self.primary_ip(),
+        }
+    }
+    // unicast_fd() getter
+    pub fn unicast_fd(&self) -> i32 {
+        self.unicast_fd
+    }
+    // set_unicast_fd() setter
+    #[cfg(target_os = "linux")]
+    pub fn set_unicast_fd(&mut self, fd: i32) {
+        self.unicast_fd = fd;
+    }
+    // check_ttl() getter
+    pub fn check_ttl(&self) -> bool {
+        self.check_ttl
+    }
     // socket_filter() getter
     #[cfg(target_os = "linux")]
     pub fn socket_filter(&self) -> bool {
@@ -1078,10 +2004,108 @@ impl Parameters {
         self.capi_rx = Some(rx);
     }
     // attr_vip() method
+    // first (primary) virtual IP address
     pub fn attr_vip(&self) -> String {
-        format!(
-            "{}.{}.{}.{}",
-            self.vip[0], self.vip[1], self.vip[2], self.vip[3]
-        )
+        format!("{}", self.vips[0].ip())
+    }
+    // attr_vips() method
+    // all the virtual IP addresses with their prefix lengths
+    pub fn attr_vips(&self) -> Vec<String> {
+        self.vips
+            .iter()
+            .map(|v| match v.prefixlen() {
+                Some(p) => format!("{}/{}", v.ip(), p),
+                None => format!("{}", v.ip()),
+            })
+            .collect()
     }
 }
+
+/// Tracked Object Structure (interface or track script)
+#[derive(Debug, Clone)]
+pub struct TrackedObject {
+    name: String,  // interface or track script name
+    decrement: u8, // priority decrement when the link is down or the script failed
+    up: bool,      // current link state or script health
+}
+
+// Tracked Object Type Implementation
+impl TrackedObject {
+    // new() method
+    // the object is assumed up until its state is learned
+    pub fn new(name: String, decrement: u8) -> TrackedObject {
+        TrackedObject {
+            name,
+            decrement,
+            up: true,
+        }
+    }
+    // name() getter
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    // decrement() getter
+    pub fn decrement(&self) -> u8 {
+        self.decrement
+    }
+    // is_up() getter
+    pub fn is_up(&self) -> bool {
+        self.up
+    }
+}
+
+/// Virtual IP Address Structure
+#[derive(Debug, Clone)]
+pub struct Vip {
+    ip: IpAddr,            // virtual IP address
+    prefixlen: Option<u8>, // prefix length (defaults to the primary address' one)
+    label: Option<String>, // address label (Linux only)
+}
+
+// Virtual IP Address Type Implementation
+impl Vip {
+    // new() method
+    pub fn new(ip: IpAddr, prefixlen: Option<u8>, label: Option<String>) -> Vip {
+        Vip {
+            ip,
+            prefixlen,
+            label,
+        }
+    }
+    // ip() getter
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+    // prefixlen() getter
+    pub fn prefixlen(&self) -> Option<u8> {
+        self.prefixlen
+    }
+    // label() getter
+    pub fn label(&self) -> &Option<String> {
+        &self.label
+    }
+    // octets4() method
+    // IPv4 address octets, or all zeros for IPv6 addresses
+    pub fn octets4(&self) -> [u8; 4] {
+        match self.ip {
+            IpAddr::V4(ip) => ip.octets(),
+            IpAddr::V6(_) => [0; 4],
+        }
+    }
+    // netmask4() method
+    // IPv4 netmask built from the prefix length
+    pub fn netmask4(&self) -> [u8; 4] {
+        let bits = match self.prefixlen {
+            Some(0) => 0,
+            Some(p) => !0u32 << (32 - p.min(32) as u32),
+            None => !0u32,
+        };
+        bits.to_be_bytes()
+    }
+}
+
+// is_ipv6_linklocal() function
+/// return true if the IPv6 address is link-local (fe80::/10)
+pub fn is_ipv6_linklocal(addr: &[u8; 16]) -> bool {
+    addr[0] == 0xfe && (addr[1] & 0xc0) == 0x80
+}